@@ -1,27 +1,155 @@
+// The derives on `Action` reference every variant, including the deprecated ones
+#![allow(deprecated)]
+
+use std::path::PathBuf;
+
 use crossterm::event::KeyEvent;
 use serde::{Deserialize, Serialize};
 use strum::Display;
 
+use crate::{
+  app::undo_stack::UndoEntry,
+  components::{branch_list::export_scope_selector::ExportScope, ui::notification::NotificationLevel},
+  git::git_repo::RepoState,
+};
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Display, Deserialize)]
 pub enum Action {
+  ActivateFilter,
+  AddRemote(String, String),
+  ApplySelectedStash,
+  ApplyStashToBranch(String, String),
+  /// Sent on the auto refresh interval, only becomes a refresh when nothing is being typed.
+  AutoRefresh,
   CheckoutSelectedBranch,
+  CheckoutTrackingBranch,
+  CherryPickCommit(String),
+  CherryPickCompleted(Result<(), String>),
+  ClearMultiSelect,
+  CloseConfirmDialog,
+  CloseHelp,
+  CopyToClipboard(String),
   CreateBranch(String),
+  CreateBranchFromStash(String),
+  CreateStash(String, bool, bool),
+  CreateStashNoVerify(String),
+  CreateStashWithPathspec(String, Vec<String>),
+  CreateWorktree(String),
+  CycleSortOrder,
+  DeleteAllStagedAcrossViews,
   DeleteBranch,
+  DeleteRemoteBranch,
   DeleteStagedBranches,
+  DeleteStagedStashes,
+  DeleteStash,
+  DeleteTag,
+  #[deprecated(note = "Use EndInputMode")]
   EndInputMod,
+  EndInputMode,
+  EndSearch,
   Error(String),
+  ExportBranches(ExportScope),
+  FetchCompleted(Result<(), String>),
+  FetchRemote(Option<String>),
+  FilterChanged(String),
+  InitBranchFromStash,
+  InitExportBranches,
   InitNewBranch,
+  InitNewStash,
+  InitNewStashWithPathspec(Vec<String>),
+  InitPathspecStash,
+  InitNewWorktree,
+  InitRenameBranch,
+  InitSetUpstream,
+  /// A view has deleted its staged items and reloaded, with how many were deleted and how many couldn't be.
+  ItemsLoaded {
+    deleted: usize,
+    failed: usize,
+  },
+  MergeCompleted(Result<(), String>),
+  MergeSelectedBranch,
+  NextView,
+  OpenEditor(PathBuf),
+  OpenShell(PathBuf),
+  Notify {
+    message: String,
+    level: NotificationLevel,
+  },
+  PageDown,
+  PageUp,
+  PopSelectedStash,
+  /// Close the view opened over the tabs, going back to the one beneath.
+  PopView,
+  PruneGoneBranches,
+  PullAllBranches,
+  PullCompleted(Result<(), String>),
+  PullCurrentBranch,
+  PushBranch(bool),
+  PushCompleted(Result<(), String>),
   Quit,
+  RebaseCompleted(Result<(), String>),
+  RebaseOntoSelected,
+  RecordUndo(UndoEntry),
   Refresh,
+  RemoveRemote,
+  RemoveWorktree,
+  RenameBranch(String, String),
+  RenameRemote(String, String),
   Render,
+  RepoStateChanged(RepoState),
+  RequestConfirmation(String, Box<Action>),
+  RequestDeleteAllStaged,
   Resize(u16, u16),
   Resume,
+  ScrollDiffDown,
+  ScrollDiffUp,
+  SearchQueryChanged(String),
+  SelectAll,
+  SelectFirst,
+  SelectItemWithName(String),
+  SelectLast,
   SelectNextBranch,
+  SelectNextCommit,
+  SelectNextFile,
+  SelectNextRemote,
+  SelectNextStash,
+  SelectNextTag,
+  SelectNextWorktree,
+  SelectPosition(usize),
   SelectPreviousBranch,
+  SelectPreviousCommit,
+  SelectPreviousFile,
+  SelectPreviousRemote,
+  SelectPreviousStash,
+  SelectPreviousTag,
+  SelectPreviousWorktree,
+  SetUpstream(String, String),
+  ShowBlame(String),
+  ShowBranchDiff(String),
+  ShowBranches,
+  ShowCommitLog(String),
+  ShowHelp,
+  ShowSelectedCommitDiff,
+  ShowStatus,
+  ShowTags,
   StageBranchForDeletion,
+  StageFile(String),
+  StageStashForDeletion,
   StartInputMode,
+  StartSearch,
   Suspend,
   Tick,
+  ToggleBranchDetails,
+  ToggleBranchGroup,
+  ToggleBranchGrouping,
+  ToggleMultiSelect,
+  ToggleSelectedStashStat,
+  ToggleStashDiff,
+  Undo,
+  UndoNotAvailable(String),
+  UnsetUpstream,
   UnstageBranchForDeletion,
+  UnstageFile(String),
+  UnstageStashForDeletion,
   UpdateNewBranchName(KeyEvent),
 }
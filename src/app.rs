@@ -1,55 +1,483 @@
-use color_eyre::eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-use ratatui::prelude::Rect;
-use tokio::sync::mpsc;
+use std::{
+  path::{Path, PathBuf},
+  process::Command,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+  },
+  time::Duration,
+};
+
+use color_eyre::eyre::{eyre, Result};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+  layout::{Constraint, Layout, Size},
+  prelude::Rect,
+  style::{Color, Modifier, Style},
+  widgets::{Paragraph, Tabs, Wrap},
+};
+use tokio::{
+  sync::mpsc::{self, UnboundedSender},
+  time::MissedTickBehavior,
+};
+use tokio_util::sync::CancellationToken;
+use tracing::warn;
 
 use crate::{
   action::Action,
-  components::{branch_list::BranchList, stash_list::StashList, Component},
+  app::{
+    key_router::KeyRouter,
+    undo_stack::{UndoEntry, UndoStack},
+  },
+  cli::Cli,
+  components::{
+    branch_diff_view::BranchDiffView,
+    branch_list::BranchList,
+    commit_log::CommitLog,
+    remote_branch_list::RemoteBranchList,
+    remote_list::RemoteList,
+    stash_list::StashList,
+    status_list::StatusList,
+    tag_list::TagList,
+    ui::{
+      confirm_dialog::ConfirmDialog,
+      help_overlay::HelpOverlay,
+      notification::{Notification, NotificationLevel},
+      search::SearchComponent,
+      status_bar::StatusBar,
+    },
+    worktree_list::WorktreeList,
+    Component,
+  },
   config::Config,
-  git::{git2_repo::Git2Repo, git_cli_repo::GitCliRepo},
+  git::{
+    any_git_repo::AnyGitRepo,
+    git_repo::{GitBranch, GitRepo},
+  },
   mode::Mode,
   tui,
   tui::Tui,
+  utils::{accessibility, clipboard, crash_report},
 };
 
+mod key_router;
+pub mod undo_stack;
+
+// Below this the layout overlaps and ratatui renders garbage
+const MIN_WIDTH: u16 = 40;
+const MIN_HEIGHT: u16 = 10;
+
+// The keys handled by `KeyRouter`, listed in the help overlay after the active view's own
+const GLOBAL_INSTRUCTIONS: [&str; 9] = [
+  "esc: Quit",
+  "tab: Switch view",
+  "t: Tags",
+  "⇧ + s: Status",
+  "^ + f: Search",
+  "^ + ⇧ + d: Delete all staged",
+  "^ + z: Suspend",
+  "^ + u: Undo",
+  "?: Help",
+];
+
+/// Counts what the views deleted for `DeleteAllStagedAcrossViews` until each has reported back with `ItemsLoaded`.
+struct StagedDeletion {
+  remaining_views: usize,
+  deleted: usize,
+  failed: usize,
+}
+
+impl StagedDeletion {
+  fn new(views: usize) -> Self {
+    StagedDeletion { remaining_views: views, deleted: 0, failed: 0 }
+  }
+
+  /// Adds one view's result, returning the notification once every view has reported.
+  fn record(&mut self, deleted: usize, failed: usize) -> Option<Action> {
+    self.deleted += deleted;
+    self.failed += failed;
+    self.remaining_views = self.remaining_views.saturating_sub(1);
+    if self.remaining_views > 0 {
+      return None;
+    }
+    if self.failed == 0 {
+      let message = format!("Deleted {} items", self.deleted);
+      return Some(Action::Notify { message, level: NotificationLevel::Success });
+    }
+    let message = format!("Deleted {} items, {} failed", self.deleted, self.failed);
+    Some(Action::Notify { message, level: NotificationLevel::Warning })
+  }
+}
+
+fn is_too_small(size: Size) -> bool {
+  size.width < MIN_WIDTH || size.height < MIN_HEIGHT
+}
+
+/// Runs the user's shell in `dir`, returning once they exit it.
+fn open_shell(dir: &Path) -> Result<()> {
+  let default_shell = if cfg!(windows) { "cmd" } else { "sh" };
+  let shell = std::env::var("SHELL").unwrap_or_else(|_| String::from(default_shell));
+  Command::new(shell).current_dir(dir).status()?;
+  Ok(())
+}
+
+fn open_editor(path: &Path) -> Result<()> {
+  let default_editor = if cfg!(windows) { "notepad" } else { "vi" };
+  let editor = std::env::var("VISUAL")
+    .or_else(|_| std::env::var("EDITOR"))
+    .ok()
+    .filter(|editor| !editor.trim().is_empty())
+    .unwrap_or_else(|| String::from(default_editor));
+  // Editors are often set with their arguments, like `code --wait`
+  let mut parts = editor.split_whitespace();
+  let program = parts.next().unwrap_or(default_editor);
+  let status = Command::new(program).args(parts).arg(path).status()?;
+  if !status.success() {
+    return Err(eyre!("{} exited with {}", program, status));
+  }
+  Ok(())
+}
+
+/// Sends `AutoRefresh` every `interval` until the returned token is cancelled. The refresh itself happens on the main
+/// loop like any other action, so it can't overlap a manual one.
+fn spawn_auto_refresh(interval: Duration, action_tx: UnboundedSender<Action>) -> CancellationToken {
+  let cancellation_token = CancellationToken::new();
+  let cancelled = cancellation_token.clone();
+  tokio::spawn(async move {
+    let mut ticker = tokio::time::interval(interval);
+    // A slow refresh shouldn't queue up a burst of them
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    // The first tick is immediate and everything was just loaded
+    ticker.tick().await;
+    loop {
+      tokio::select! {
+        _ = cancelled.cancelled() => break,
+        _ = ticker.tick() => {
+          if action_tx.send(Action::AutoRefresh).is_err() {
+            break;
+          }
+        },
+      }
+    }
+  });
+  cancellation_token
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub enum View {
   Branches,
   Stashes,
+  RemoteBranches,
+  Remotes,
+  Tags,
+  Worktrees,
+  Status,
 }
 
-const TICK_RATE: f64 = 10.0;
-const FRAME_RATE: f64 = 30.0;
-
 pub struct App {
   pub config: Config,
   pub branch_list: Box<dyn Component>,
   pub stash_list: Box<dyn Component>,
+  pub remote_branch_list: Box<dyn Component>,
+  pub remote_list: Box<dyn Component>,
+  pub tag_list: Box<dyn Component>,
+  pub worktree_list: Box<dyn Component>,
+  pub status_list: Box<dyn Component>,
+  pub confirm_dialog: Option<ConfirmDialog>,
+  pub search: Option<SearchComponent>,
+  pub help: Option<HelpOverlay>,
+  pub notification: Option<Notification>,
+  pub status_bar: StatusBar,
+  // Reads what is checked out for the status bar, and restores what undo brings back. Every view shares it
+  head_repo: AnyGitRepo,
+  undo_stack: UndoStack,
   pub should_quit: bool,
   pub should_suspend: bool,
+  // A worktree to open a shell in once the pending actions are handled
+  shell_dir: Option<PathBuf>,
+  // A file to open in the user's editor once the pending actions are handled
+  edit_path: Option<PathBuf>,
   pub mode: Mode,
+  // The mode to return to once the terminal is big enough again
+  mode_before_too_small: Mode,
   pub view: View,
+  // Views opened over the tabs, like a branch's commit log, the last is shown until it is popped
+  view_stack: Vec<Box<dyn Component>>,
+  // A delete across views waiting to hear back from each of them
+  staged_deletion: Option<StagedDeletion>,
+  key_router: KeyRouter,
 }
 
 impl App {
-  pub fn new() -> Result<Self> {
-    let config = Config::new()?;
-    // TODO only have a single repo that is shared
-    let branch_list = Box::new(BranchList::new(Box::new(GitCliRepo::from_cwd().unwrap())));
-    let stash_list = Box::new(StashList::new(Box::new(Git2Repo::from_cwd().unwrap())));
+  pub fn new(args: Cli) -> Result<Self> {
+    let mut config = Config::new()?;
+    config.frame_rate = args.frame_rate.unwrap_or(config.frame_rate);
+    config.tick_rate = args.tick_rate.unwrap_or(config.tick_rate);
+    if let Some(git_path) = args.git_path {
+      config.git_executable = git_path;
+    }
+    config.preferred = args.backend.unwrap_or(config.preferred);
+    config.validate().map_err(|err| eyre!(err))?;
+    accessibility::init(&config);
+    let repo = AnyGitRepo::open(config.preferred, &config.git_executable)?;
+    let mut branch_list = BranchList::new(Arc::new(repo.clone()), &config);
+    if let Some(starting_branch) = args.starting_branch {
+      // Only select the starting branch once so later refreshes keep the user's selection
+      let selected = AtomicBool::new(false);
+      branch_list = branch_list.set_on_items_loaded(Box::new(move |list| {
+        if !selected.load(Ordering::Relaxed) {
+          selected.store(list.jump_to_item_by_name(&starting_branch), Ordering::Relaxed);
+        }
+      }));
+    }
+    let branch_list = Box::new(branch_list);
+    let stash_list = Box::new(StashList::new(Box::new(repo.clone()), &config));
+    let remote_branch_list = Box::new(RemoteBranchList::new(Box::new(repo.clone())));
+    let remote_list = Box::new(RemoteList::new(Box::new(repo.clone()), &config));
+    let tag_list = Box::new(TagList::new(Box::new(repo.clone())));
+    let worktree_list = Box::new(WorktreeList::new(Box::new(repo.clone())));
+    let status_list = Box::new(StatusList::new(Box::new(repo.clone())));
+    let head_repo = repo;
+    let mut status_bar = StatusBar::default();
+    status_bar.set_repo_path(std::env::current_dir()?.display().to_string());
+    status_bar.set_auto_refresh(config.refresh_interval_secs);
     let mode = Mode::Default;
-    Ok(Self { config, branch_list, stash_list, should_quit: false, should_suspend: false, mode, view: View::Branches })
+    let key_router = KeyRouter::new(&config.keybindings);
+    let mut app = Self {
+      config,
+      branch_list,
+      stash_list,
+      remote_branch_list,
+      remote_list,
+      tag_list,
+      worktree_list,
+      status_list,
+      confirm_dialog: None,
+      search: None,
+      help: None,
+      notification: None,
+      status_bar,
+      head_repo,
+      undo_stack: UndoStack::default(),
+      should_quit: false,
+      should_suspend: false,
+      shell_dir: None,
+      edit_path: None,
+      mode,
+      mode_before_too_small: mode,
+      view: View::Branches,
+      view_stack: Vec::new(),
+      staged_deletion: None,
+      key_router,
+    };
+    app.refresh_head();
+    Ok(app)
+  }
+
+  fn active_component(&mut self) -> &mut Box<dyn Component> {
+    if let Some(stacked) = self.view_stack.last_mut() {
+      return stacked;
+    }
+    match self.view {
+      View::Branches => &mut self.branch_list,
+      View::Stashes => &mut self.stash_list,
+      View::RemoteBranches => &mut self.remote_branch_list,
+      View::Remotes => &mut self.remote_list,
+      View::Tags => &mut self.tag_list,
+      View::Worktrees => &mut self.worktree_list,
+      View::Status => &mut self.status_list,
+    }
+  }
+
+  fn inactive_components(&mut self) -> Vec<&mut Box<dyn Component>> {
+    // The tab beneath a stacked view is inactive too
+    let active_tab = if self.view_stack.is_empty() { Some(self.view) } else { None };
+    let tabs = [
+      (View::Branches, &mut self.branch_list),
+      (View::Stashes, &mut self.stash_list),
+      (View::RemoteBranches, &mut self.remote_branch_list),
+      (View::Remotes, &mut self.remote_list),
+      (View::Tags, &mut self.tag_list),
+      (View::Worktrees, &mut self.worktree_list),
+      (View::Status, &mut self.status_list),
+    ];
+    tabs.into_iter().filter(|(view, _)| Some(*view) != active_tab).map(|(_, component)| component).collect()
+  }
+
+  fn push_commit_log(&mut self, branch_name: String, action_tx: &UnboundedSender<Action>) -> Result<()> {
+    let mut commit_log = CommitLog::new(Arc::new(self.head_repo.clone()), GitBranch::new(branch_name));
+    commit_log.register_action_handler(action_tx.clone())?;
+    self.view_stack.push(Box::new(commit_log));
+    Ok(())
+  }
+
+  fn push_branch_diff(&mut self, head: String, branch_name: String) {
+    let branch_diff =
+      BranchDiffView::new(Arc::new(self.head_repo.clone()), GitBranch::new(head), GitBranch::new(branch_name));
+    self.view_stack.push(Box::new(branch_diff));
+  }
+
+  fn collect_all_staged_items(&self) -> (Vec<String>, Vec<String>) {
+    (self.branch_list.staged_items(), self.stash_list.staged_items())
+  }
+
+  fn confirm_delete_all_staged(&self) -> Option<Action> {
+    let (branches, stashes) = self.collect_all_staged_items();
+    if branches.is_empty() && stashes.is_empty() {
+      return None;
+    }
+    let message = format!(
+      "Delete {} staged branches ({}) and {} staged stashes ({})?",
+      branches.len(),
+      branches.join(", "),
+      stashes.len(),
+      stashes.join(", ")
+    );
+    Some(Action::RequestConfirmation(message, Box::new(Action::DeleteAllStagedAcrossViews)))
+  }
+
+  fn refresh_head(&mut self) {
+    let head = self.head_repo.current_head();
+    if let Err(err) = &head {
+      // A repository without commits has no HEAD yet
+      log::debug!("Failed to read HEAD: {}", err);
+    }
+    self.status_bar.set_head(head.ok());
+  }
+
+  /// Reverses the most recent destructive operation, the returned action says how it went.
+  fn undo(&mut self) -> Action {
+    let Some(entry) = self.undo_stack.pop() else {
+      return Action::UndoNotAvailable(String::from("Nothing to undo"));
+    };
+    match entry {
+      UndoEntry::DeletedBranch { name, sha, upstream } => {
+        let result = self.head_repo.create_branch_at(&name, &sha).and_then(|()| {
+          match upstream {
+            // The remote branch may have gone since, the branch is still worth having back without it
+            Some(upstream) => {
+              self.head_repo.set_upstream(&GitBranch::new(name.clone()), &upstream).or_else(|err| {
+                warn!("Could not restore the upstream of {}: {}", name, err);
+                Ok(())
+              })
+            },
+            None => Ok(()),
+          }
+        });
+        match result {
+          Ok(()) => Action::Notify { message: format!("Restored {}", name), level: NotificationLevel::Success },
+          Err(err) => {
+            warn!("Could not restore {}: {}", name, err);
+            Action::Notify {
+              message: format!("Could not restore {}: {}", name, err),
+              level: NotificationLevel::Warning,
+            }
+          },
+        }
+      },
+      UndoEntry::DroppedStash { message } => {
+        Action::UndoNotAvailable(format!("Dropped stashes can't be restored: {}", message))
+      },
+    }
+  }
+
+  /// Switches mode, or the mode to come back to when the terminal is too small for the UI right now.
+  fn set_mode(&mut self, mode: Mode) {
+    if self.mode == Mode::TooSmall {
+      self.mode_before_too_small = mode;
+    } else {
+      self.mode = mode;
+    }
+  }
+
+  fn check_size(&mut self, size: Size) {
+    if is_too_small(size) && self.mode != Mode::TooSmall {
+      self.mode_before_too_small = self.mode;
+      self.mode = Mode::TooSmall;
+    } else if !is_too_small(size) && self.mode == Mode::TooSmall {
+      self.mode = self.mode_before_too_small;
+    }
+  }
+
+  fn render(&mut self, tui: &mut Tui, action_tx: &UnboundedSender<Action>) -> Result<()> {
+    if self.mode == Mode::TooSmall {
+      let size = tui.current_size()?;
+      let message =
+        format!("Terminal too small: need at least {}x{}, got {}x{}", MIN_WIDTH, MIN_HEIGHT, size.width, size.height);
+      tui.draw(|f| f.render_widget(Paragraph::new(message).wrap(Wrap { trim: true }), f.area()))?;
+      return Ok(());
+    }
+    let titles = vec![
+      String::from(self.branch_list.title()),
+      String::from(self.stash_list.title()),
+      String::from(self.remote_branch_list.title()),
+      String::from(self.remote_list.title()),
+      String::from(self.tag_list.title()),
+      String::from(self.worktree_list.title()),
+      String::from(self.status_list.title()),
+    ];
+    let (selected_tab, component) = match self.view {
+      View::Branches => (0, &mut self.branch_list),
+      View::Stashes => (1, &mut self.stash_list),
+      View::RemoteBranches => (2, &mut self.remote_branch_list),
+      View::Remotes => (3, &mut self.remote_list),
+      View::Tags => (4, &mut self.tag_list),
+      View::Worktrees => (5, &mut self.worktree_list),
+      View::Status => (6, &mut self.status_list),
+    };
+    let component = self.view_stack.last_mut().unwrap_or(component);
+    let confirm_dialog = &mut self.confirm_dialog;
+    let search = &mut self.search;
+    let help = &mut self.help;
+    self.status_bar.set_item_counts(component.item_counts());
+    let notification = &mut self.notification;
+    let status_bar = &mut self.status_bar;
+    tui.draw(|f| {
+      let [tabs_area, component_area, status_area] =
+        Layout::vertical([Constraint::Length(1), Constraint::Fill(1), Constraint::Length(1)]).areas(f.area());
+      let tabs = Tabs::new(titles)
+        .select(selected_tab)
+        .style(Style::default().fg(Color::White))
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED));
+      f.render_widget(tabs, tabs_area);
+      let mut r = status_bar.draw(f, status_area);
+      r = r.and_then(|_| component.draw(f, component_area));
+      if let Some(search) = search {
+        r = r.and_then(|_| search.draw(f, component_area));
+      }
+      if let Some(notification) = notification {
+        r = r.and_then(|_| notification.draw(f, component_area));
+      }
+      if let Some(help) = help {
+        r = r.and_then(|_| help.draw(f, component_area));
+      }
+      if let Some(dialog) = confirm_dialog {
+        r = r.and_then(|_| dialog.draw(f, f.area()));
+      }
+      if let Err(e) = r {
+        action_tx.send(Action::Error(format!("Failed to draw: {:?}", e))).unwrap();
+      }
+    })?;
+    Ok(())
   }
 
   pub async fn run(&mut self) -> Result<()> {
     let (action_tx, mut action_rx) = mpsc::unbounded_channel();
 
-    let mut tui = tui::Tui::new()?.tick_rate(TICK_RATE).frame_rate(FRAME_RATE);
-    // tui.mouse(true);
+    let mut tui = tui::Tui::new()?.tick_rate(self.config.tick_rate).frame_rate(self.config.frame_rate).mouse(true);
     tui.enter()?;
+    self.check_size(tui.current_size()?);
 
     self.branch_list.register_action_handler(action_tx.clone())?;
     self.stash_list.register_action_handler(action_tx.clone())?;
+    self.remote_branch_list.register_action_handler(action_tx.clone())?;
+    self.remote_list.register_action_handler(action_tx.clone())?;
+    self.tag_list.register_action_handler(action_tx.clone())?;
+    self.worktree_list.register_action_handler(action_tx.clone())?;
+    let auto_refresh = self
+      .config
+      .refresh_interval_secs
+      .map(|secs| spawn_auto_refresh(Duration::from_secs_f64(secs), action_tx.clone()));
 
     loop {
       if let Some(e) = tui.next().await {
@@ -58,28 +486,51 @@ impl App {
           tui::Event::Tick => action_tx.send(Action::Tick)?,
           tui::Event::Render => action_tx.send(Action::Render)?,
           tui::Event::Resize(x, y) => action_tx.send(Action::Resize(x, y))?,
+          // The active view gets first go at esc while it has something to back out of
+          tui::Event::Key(key)
+            if self.mode == Mode::Default && key.code == KeyCode::Esc && self.active_component().handles_escape() => {},
           tui::Event::Key(key) => {
-            if self.mode == Mode::Default {
-              let action = match key {
-                KeyEvent { code: KeyCode::Esc, modifiers: _, state: _, kind: _ } => Some(Action::Quit),
-                KeyEvent { code: KeyCode::Char('c' | 'C'), modifiers: KeyModifiers::CONTROL, state: _, kind: _ } => {
-                  Some(Action::Quit)
-                },
-                _ => None,
-              };
-              if action.is_some() {
-                action_tx.send(action.unwrap())?;
+            if let Some(action) = self.key_router.route(self.mode, key.code, key.modifiers) {
+              // Quitting is the only thing a key can do while the UI isn't visible
+              if self.mode != Mode::TooSmall || action == Action::Quit {
+                action_tx.send(action)?;
               }
-            };
+            }
           },
           _ => {},
         }
 
-        let component: &mut Box<dyn Component> = match self.view {
-          View::Branches => &mut self.branch_list,
-          View::Stashes => &mut self.stash_list,
-        };
-        if let Some(action) = component.handle_events(Some(e.clone()))? {
+        if self.mode == Mode::Confirm {
+          if let Some(dialog) = self.confirm_dialog.as_mut() {
+            if let Some(action) = dialog.handle_events(Some(e.clone()))? {
+              action_tx.send(Action::CloseConfirmDialog)?;
+              if action != Action::CloseConfirmDialog {
+                action_tx.send(action)?;
+              }
+            }
+          }
+        } else if self.mode == Mode::Search {
+          if let Some(action) =
+            self.search.as_mut().map(|search| search.handle_events(Some(e.clone()))).transpose()?.flatten()
+          {
+            action_tx.send(action)?;
+          } else if let tui::Event::Key(KeyEvent { code: KeyCode::Up | KeyCode::Down, .. }) = e {
+            // Moving through the results is up to the view
+            if let Some(action) = self.active_component().handle_events(Some(e.clone()))? {
+              action_tx.send(action)?;
+            }
+          }
+        } else if self.mode == Mode::Help {
+          if let Some(action) =
+            self.help.as_mut().map(|help| help.handle_events(Some(e.clone()))).transpose()?.flatten()
+          {
+            action_tx.send(action)?;
+          }
+        } else if self.mode == Mode::TooSmall {
+          // Nothing else is visible so only the app wide keys apply
+        } else if matches!(e, tui::Event::Mouse(_)) && self.mode != Mode::Default {
+          // Clicks would move the selection out from under an open input
+        } else if let Some(action) = self.active_component().handle_events(Some(e.clone()))? {
           action_tx.send(action)?;
         }
       }
@@ -88,46 +539,219 @@ impl App {
         if action != Action::Tick && action != Action::Render {
           log::debug!("{action:?}");
         }
-        let component: &mut Box<dyn Component> = match self.view {
-          View::Branches => &mut self.branch_list,
-          View::Stashes => &mut self.stash_list,
-        };
-
+        #[allow(deprecated)]
+        let action = if action == Action::EndInputMod { Action::EndInputMode } else { action };
         match action {
-          Action::StartInputMode => self.mode = Mode::Input,
-          Action::EndInputMod => self.mode = Mode::Default,
+          Action::StartInputMode => self.set_mode(Mode::Input),
+          Action::EndInputMode => self.set_mode(Mode::Default),
           Action::Quit => self.should_quit = true,
+          // Refreshing mid input would drop what is being typed, the next interval will catch up
+          Action::AutoRefresh if self.mode == Mode::Default => action_tx.send(Action::Refresh)?,
+          Action::Suspend if cfg!(windows) => {
+            let message = String::from("Suspend not supported on Windows");
+            action_tx.send(Action::Notify { message, level: NotificationLevel::Warning })?;
+          },
           Action::Suspend => self.should_suspend = true,
+          Action::RequestDeleteAllStaged => {
+            if let Some(action) = self.confirm_delete_all_staged() {
+              action_tx.send(action)?;
+            }
+          },
           Action::Resume => self.should_suspend = false,
-          Action::Resize(w, h) => {
-            tui.resize(Rect::new(0, 0, w, h))?;
-            tui.draw(|f| {
-              let r = component.draw(f, f.area());
-              if let Err(e) = r {
-                action_tx.send(Action::Error(format!("Failed to draw: {:?}", e))).unwrap();
-              }
-            })?;
+          Action::NextView => {
+            self.view = match self.view {
+              View::Branches => View::Stashes,
+              View::Stashes => View::RemoteBranches,
+              View::RemoteBranches => View::Remotes,
+              View::Remotes => View::Tags,
+              View::Tags => View::Worktrees,
+              View::Worktrees => View::Status,
+              View::Status => View::Branches,
+            };
+          },
+          Action::ShowBranches => self.view = View::Branches,
+          Action::ShowCommitLog(ref branch_name) => {
+            self.push_commit_log(branch_name.clone(), &action_tx)?;
+            // Esc and tab belong to the stacked view rather than the app wide keys
+            self.set_mode(Mode::Input);
+          },
+          Action::ShowBranchDiff(ref branch_name) => {
+            match self.head_repo.current_head() {
+              Ok((head, _)) => {
+                self.push_branch_diff(head, branch_name.clone());
+                self.set_mode(Mode::Input);
+              },
+              Err(err) => {
+                action_tx.send(Action::Notify { message: err.to_string(), level: NotificationLevel::Warning })?
+              },
+            }
+          },
+          Action::PopView => {
+            self.view_stack.pop();
+            if self.view_stack.is_empty() {
+              self.set_mode(Mode::Default);
+            }
+          },
+          Action::ShowTags => self.view = View::Tags,
+          Action::ShowStatus => self.view = View::Status,
+          Action::StartSearch => {
+            self.search = Some(SearchComponent::default());
+            self.set_mode(Mode::Search);
+          },
+          Action::ShowHelp => {
+            let component = self.active_component();
+            let help = HelpOverlay::new(component.title(), component.instructions(), &GLOBAL_INSTRUCTIONS);
+            self.help = Some(help);
+            self.set_mode(Mode::Help);
+          },
+          Action::CloseHelp => {
+            self.help = None;
+            self.set_mode(Mode::Default);
+          },
+          Action::EndSearch => {
+            self.search = None;
+            self.set_mode(Mode::Default);
           },
-          Action::Render => {
-            tui.draw(|f| {
-              let r = component.draw(f, f.area());
-              if let Err(e) = r {
-                action_tx.send(Action::Error(format!("Failed to draw: {:?}", e))).unwrap();
+          Action::OpenShell(ref dir) => self.shell_dir = Some(dir.clone()),
+          Action::OpenEditor(ref path) => self.edit_path = Some(path.clone()),
+          Action::RequestConfirmation(ref message, ref on_confirm) => {
+            self.confirm_dialog = Some(ConfirmDialog::new(message.clone(), *on_confirm.clone()));
+            self.set_mode(Mode::Confirm);
+          },
+          Action::DeleteAllStagedAcrossViews => {
+            let deletions = [
+              (&mut self.branch_list, Action::DeleteStagedBranches),
+              (&mut self.stash_list, Action::DeleteStagedStashes),
+            ];
+            self.staged_deletion = Some(StagedDeletion::new(deletions.len()));
+            // Each view answers with `ItemsLoaded` once it is done
+            for (component, delete_action) in deletions {
+              if let Some(action) = component.update(delete_action)? {
+                action_tx.send(action)?;
               }
-            })?;
+            }
+          },
+          Action::ItemsLoaded { deleted, failed } => {
+            // A delete in a single view reports back too, only one across views is announced
+            if let Some(notification) = self.staged_deletion.as_mut().and_then(|tally| tally.record(deleted, failed)) {
+              self.staged_deletion = None;
+              action_tx.send(notification)?;
+            }
+          },
+          // HEAD has moved, or is stuck part way through on a conflict
+          Action::CherryPickCompleted(_) => action_tx.send(Action::Refresh)?,
+          Action::RepoStateChanged(repo_state) => self.status_bar.set_repo_state(repo_state),
+          Action::CopyToClipboard(ref text) => {
+            let notification = match clipboard::set_text(text) {
+              Ok(()) => Action::Notify { message: format!("Copied: {}", text), level: NotificationLevel::Success },
+              Err(err) => {
+                warn!("Could not copy to the clipboard: {}", err);
+                let message = format!("Could not copy to the clipboard: {}", err);
+                Action::Notify { message, level: NotificationLevel::Warning }
+              },
+            };
+            action_tx.send(notification)?;
+          },
+          Action::RecordUndo(ref entry) => self.undo_stack.push(entry.clone()),
+          Action::Undo => {
+            action_tx.send(self.undo())?;
+            action_tx.send(Action::Refresh)?;
+          },
+          Action::UndoNotAvailable(ref reason) => {
+            self.notification = Some(Notification::new(reason.clone(), NotificationLevel::Info));
+          },
+          Action::Notify { ref message, level } => {
+            self.notification = Some(Notification::new(message.clone(), level));
+          },
+          Action::Tick => {
+            if self.notification.as_ref().is_some_and(|notification| notification.is_expired()) {
+              self.notification = None;
+            }
           },
+          Action::CloseConfirmDialog => {
+            self.confirm_dialog = None;
+            self.set_mode(Mode::Default);
+          },
+          Action::Resize(w, h) => {
+            self.check_size(Size::new(w, h));
+            tui.resize(Rect::new(0, 0, w, h))?;
+            self.render(&mut tui, &action_tx)?;
+          },
+          Action::Render => self.render(&mut tui, &action_tx)?,
           _ => {},
         }
-        if let Some(action) = component.update(action.clone())? {
+        crash_report::set_current_action(Some(action.clone()));
+        if let Some(action) = self.active_component().update(action.clone())? {
           action_tx.send(action)?
         };
+        // Hidden views reload too so they aren't stale when switched to, and background results reach the view that
+        // started them even if the user has moved on
+        if matches!(
+          action,
+          Action::Refresh
+            | Action::FetchCompleted(_)
+            | Action::PushCompleted(_)
+            | Action::PullCompleted(_)
+            | Action::MergeCompleted(_)
+            | Action::RebaseCompleted(_)
+        ) {
+          for component in self.inactive_components() {
+            if let Some(action) = component.update(action.clone())? {
+              action_tx.send(action)?
+            };
+          }
+        }
+        // Anything that can move HEAD, refreshes follow operations done outside the app
+        if matches!(
+          action,
+          Action::Refresh
+            | Action::CheckoutSelectedBranch
+            | Action::CreateBranch(_)
+            | Action::RenameBranch(_, _)
+            | Action::CreateBranchFromStash(_)
+            | Action::PullCompleted(_)
+            | Action::MergeCompleted(_)
+            | Action::RebaseCompleted(_)
+        ) {
+          self.refresh_head();
+        }
+        // Every view shares the repo, so this picks up warnings from whichever ran the command
+        let warnings = self.head_repo.get_and_clear_warnings();
+        if !warnings.is_empty() {
+          action_tx.send(Action::Notify { message: warnings.join("\n"), level: NotificationLevel::Warning })?;
+        }
+        crash_report::set_current_action(None);
       }
-      if self.should_suspend {
+      if let Some(dir) = self.shell_dir.take() {
+        tui.exit()?;
+        let result = open_shell(&dir);
+        tui = Tui::new()?.tick_rate(self.config.tick_rate).frame_rate(self.config.frame_rate).mouse(true);
+        tui.enter()?;
+        if let Err(err) = result {
+          let message = format!("Failed to open a shell in {}: {}", dir.display(), err);
+          action_tx.send(Action::Notify { message, level: NotificationLevel::Warning })?;
+        }
+        // Whatever was done in the shell may have changed the repository
+        action_tx.send(Action::Refresh)?;
+      } else if let Some(path) = self.edit_path.take() {
+        tui.exit()?;
+        let result = open_editor(&path);
+        tui = Tui::new()?.tick_rate(self.config.tick_rate).frame_rate(self.config.frame_rate).mouse(true);
+        tui.enter()?;
+        if let Err(err) = result {
+          let message = format!("Failed to open {} in an editor: {}", path.display(), err);
+          action_tx.send(Action::Notify { message, level: NotificationLevel::Warning })?;
+        }
+        action_tx.send(Action::Refresh)?;
+      } else if self.should_suspend {
         tui.suspend()?;
         action_tx.send(Action::Resume)?;
-        tui = Tui::new()?.tick_rate(TICK_RATE).frame_rate(FRAME_RATE);
+        tui = Tui::new()?.tick_rate(self.config.tick_rate).frame_rate(self.config.frame_rate).mouse(true);
         tui.enter()?;
       } else if self.should_quit {
+        if let Some(auto_refresh) = &auto_refresh {
+          auto_refresh.cancel();
+        }
         tui.stop()?;
         break;
       }
@@ -136,3 +760,37 @@ impl App {
     Ok(())
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn too_small_below_the_minimum_size() {
+    assert!(!is_too_small(Size::new(MIN_WIDTH, MIN_HEIGHT)));
+    assert!(!is_too_small(Size::new(200, 60)));
+    assert!(is_too_small(Size::new(MIN_WIDTH - 1, MIN_HEIGHT)));
+    assert!(is_too_small(Size::new(MIN_WIDTH, MIN_HEIGHT - 1)));
+    assert!(is_too_small(Size::new(0, 0)));
+  }
+
+  #[test]
+  fn staged_deletion_is_announced_once_every_view_reports() {
+    let mut tally = StagedDeletion::new(2);
+    assert_eq!(tally.record(3, 0), None);
+    assert_eq!(
+      tally.record(2, 0),
+      Some(Action::Notify { message: String::from("Deleted 5 items"), level: NotificationLevel::Success })
+    );
+  }
+
+  #[test]
+  fn staged_deletion_counts_failures() {
+    let mut tally = StagedDeletion::new(2);
+    tally.record(1, 1);
+    assert_eq!(
+      tally.record(2, 0),
+      Some(Action::Notify { message: String::from("Deleted 3 items, 1 failed"), level: NotificationLevel::Warning })
+    );
+  }
+}
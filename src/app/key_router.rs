@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+
+use crossterm::event::{KeyCode, KeyModifiers};
+use tracing::error;
+
+use crate::{
+  action::Action,
+  config::keybindings::{normalize, parse_key},
+  mode::Mode,
+};
+
+/// Maps app wide key presses to actions, per mode. Component specific keys are handled by the components.
+pub struct KeyRouter {
+  bindings: HashMap<(Mode, KeyCode, KeyModifiers), Action>,
+}
+
+impl KeyRouter {
+  /// The default bindings overlaid with `keybindings`, which map a mode to key descriptions like `ctrl+q` and
+  /// their action.
+  pub fn new(keybindings: &HashMap<Mode, HashMap<String, Action>>) -> Self {
+    let mut router = KeyRouter::default();
+    for (mode, bindings) in keybindings {
+      for (key, action) in bindings {
+        match parse_key(key) {
+          Some((code, modifiers)) => router.bind(*mode, code, modifiers, action.clone()),
+          None => error!("Ignoring keybinding with unknown key: {}", key),
+        }
+      }
+    }
+    router
+  }
+
+  fn bind(&mut self, mode: Mode, code: KeyCode, modifiers: KeyModifiers, action: Action) {
+    self.bindings.insert((mode, normalize(code), modifiers), action);
+  }
+
+  pub fn route(&self, mode: Mode, code: KeyCode, modifiers: KeyModifiers) -> Option<Action> {
+    self.bindings.get(&(mode, normalize(code), modifiers)).cloned()
+  }
+}
+
+impl Default for KeyRouter {
+  fn default() -> Self {
+    let mut router = KeyRouter { bindings: HashMap::new() };
+    for mode in [Mode::Default, Mode::TooSmall] {
+      router.bind(mode, KeyCode::Esc, KeyModifiers::NONE, Action::Quit);
+      router.bind(mode, KeyCode::Char('c'), KeyModifiers::CONTROL, Action::Quit);
+    }
+    router.bind(Mode::Default, KeyCode::Char('z'), KeyModifiers::CONTROL, Action::Suspend);
+    router.bind(Mode::Default, KeyCode::Char('u'), KeyModifiers::CONTROL, Action::Undo);
+    router.bind(Mode::Default, KeyCode::Tab, KeyModifiers::NONE, Action::NextView);
+    router.bind(Mode::Default, KeyCode::Char('t'), KeyModifiers::NONE, Action::ShowTags);
+    router.bind(Mode::Default, KeyCode::Char('s'), KeyModifiers::SHIFT, Action::ShowStatus);
+    router.bind(Mode::Default, KeyCode::Char('f'), KeyModifiers::CONTROL, Action::StartSearch);
+    // Most terminals report `?` as shifted
+    router.bind(Mode::Default, KeyCode::Char('?'), KeyModifiers::NONE, Action::ShowHelp);
+    router.bind(Mode::Default, KeyCode::Char('?'), KeyModifiers::SHIFT, Action::ShowHelp);
+    router.bind(
+      Mode::Default,
+      KeyCode::Char('d'),
+      KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+      Action::RequestDeleteAllStaged,
+    );
+    router
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const ALL_MODES: [Mode; 6] = [Mode::Default, Mode::Input, Mode::Confirm, Mode::Search, Mode::Help, Mode::TooSmall];
+
+  #[test]
+  fn default_bindings_route_to_their_actions_in_each_mode() {
+    let ctrl_shift = KeyModifiers::CONTROL | KeyModifiers::SHIFT;
+    let defaults = [
+      (KeyCode::Esc, KeyModifiers::NONE, Action::Quit, &[Mode::Default, Mode::TooSmall][..]),
+      (KeyCode::Char('c'), KeyModifiers::CONTROL, Action::Quit, &[Mode::Default, Mode::TooSmall]),
+      (KeyCode::Char('z'), KeyModifiers::CONTROL, Action::Suspend, &[Mode::Default]),
+      (KeyCode::Char('u'), KeyModifiers::CONTROL, Action::Undo, &[Mode::Default]),
+      (KeyCode::Tab, KeyModifiers::NONE, Action::NextView, &[Mode::Default]),
+      (KeyCode::Char('t'), KeyModifiers::NONE, Action::ShowTags, &[Mode::Default]),
+      (KeyCode::Char('s'), KeyModifiers::SHIFT, Action::ShowStatus, &[Mode::Default]),
+      (KeyCode::Char('f'), KeyModifiers::CONTROL, Action::StartSearch, &[Mode::Default]),
+      (KeyCode::Char('?'), KeyModifiers::NONE, Action::ShowHelp, &[Mode::Default]),
+      (KeyCode::Char('?'), KeyModifiers::SHIFT, Action::ShowHelp, &[Mode::Default]),
+      (KeyCode::Char('d'), ctrl_shift, Action::RequestDeleteAllStaged, &[Mode::Default]),
+    ];
+    let router = KeyRouter::default();
+    for (code, modifiers, action, modes) in defaults {
+      for mode in ALL_MODES {
+        let expected = modes.contains(&mode).then(|| action.clone());
+        assert_eq!(router.route(mode, code, modifiers), expected, "{:?} {:?} in {:?}", modifiers, code, mode);
+      }
+    }
+  }
+
+  #[test]
+  fn configured_bindings_overlay_the_defaults() {
+    let keybindings = HashMap::from([(Mode::Default, HashMap::from([(String::from("ctrl+z"), Action::Quit)]))]);
+    let router = KeyRouter::new(&keybindings);
+    assert_eq!(router.route(Mode::Default, KeyCode::Char('z'), KeyModifiers::CONTROL), Some(Action::Quit));
+    assert_eq!(router.route(Mode::Default, KeyCode::Tab, KeyModifiers::NONE), Some(Action::NextView));
+  }
+}
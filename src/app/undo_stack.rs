@@ -0,0 +1,34 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+// Older entries are forgotten once this many are held
+const MAX_ENTRIES: usize = 5;
+
+/// What is needed to reverse a destructive operation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UndoEntry {
+  /// A deleted local branch, recreated at the commit it pointed at and tracking its upstream again.
+  DeletedBranch { name: String, sha: String, upstream: Option<String> },
+  /// A dropped stash, its commit can be garbage collected at any time so undo only explains that it is gone.
+  DroppedStash { message: String },
+}
+
+/// The most recent destructive operations, newest last.
+#[derive(Debug, Default)]
+pub struct UndoStack {
+  entries: VecDeque<UndoEntry>,
+}
+
+impl UndoStack {
+  pub fn push(&mut self, entry: UndoEntry) {
+    if self.entries.len() == MAX_ENTRIES {
+      self.entries.pop_front();
+    }
+    self.entries.push_back(entry);
+  }
+
+  pub fn pop(&mut self) -> Option<UndoEntry> {
+    self.entries.pop_back()
+  }
+}
@@ -1,7 +1,65 @@
-use clap::Parser;
+use std::path::PathBuf;
 
-use crate::utils::version;
+use clap::{ArgAction, Parser, ValueHint};
+
+use crate::{cli::completions::Shell, config::RATE_RANGE, git::any_git_repo::GitRepoBackend, utils::version};
+
+pub mod completions;
 
 #[derive(Parser, Debug)]
-#[command(author, version = version(), about)]
-pub struct Cli {}
+#[command(author, version = version(), about, disable_version_flag = true)]
+pub struct Cli {
+  /// Frames rendered per second, between 1 and 120, overrides the config file
+  #[arg(long, value_name = "FLOAT", value_parser = rate)]
+  pub frame_rate: Option<f64>,
+  /// Ticks per second, between 1 and 120, overrides the config file
+  #[arg(long, value_name = "FLOAT", value_parser = rate)]
+  pub tick_rate: Option<f64>,
+  /// Branch to select when the app starts
+  #[arg(long, value_name = "BRANCH")]
+  pub starting_branch: Option<String>,
+  /// The git to run, overrides the config file
+  #[arg(long, value_name = "PATH", value_hint = ValueHint::ExecutablePath)]
+  pub git_path: Option<PathBuf>,
+  /// The git backend to try first, overrides the config file
+  #[arg(long, value_enum)]
+  pub backend: Option<GitRepoBackend>,
+  /// Print a completion script for the shell and exit
+  ///
+  /// For bash, save it where bash-completion looks, e.g.
+  /// `git-branch-manager --completions bash > ~/.bash_completion.d/git-branch-manager`, and source it from
+  /// `~/.bash_completion`. zsh wants the script named `_git-branch-manager` in a directory on `$fpath`, fish looks in
+  /// `~/.config/fish/completions/` and PowerShell can dot source it from `$PROFILE`.
+  #[arg(long, value_enum, value_name = "SHELL")]
+  pub completions: Option<Shell>,
+  /// Print version, along with the git that would be used, and exit
+  #[arg(short = 'V', long, action = ArgAction::SetTrue)]
+  pub version: bool,
+}
+
+fn rate(value: &str) -> Result<f64, String> {
+  let rate: f64 = value.parse().map_err(|_| format!("`{}` isn't a number", value))?;
+  if !RATE_RANGE.contains(&rate) {
+    return Err(format!("must be between {} and {}", RATE_RANGE.start(), RATE_RANGE.end()));
+  }
+  Ok(rate)
+}
+
+#[cfg(test)]
+mod tests {
+  use clap::error::ErrorKind;
+
+  use super::*;
+
+  #[test]
+  fn rates_must_be_in_range() {
+    let args = Cli::try_parse_from(["git-branch-manager", "--frame-rate", "120", "--tick-rate", "1"]).unwrap();
+    assert_eq!((args.frame_rate, args.tick_rate), (Some(120.0), Some(1.0)));
+    for arg in ["--frame-rate", "--tick-rate"] {
+      for value in ["0.5", "120.5", "fast"] {
+        let err = Cli::try_parse_from(["git-branch-manager", arg, value]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ValueValidation);
+      }
+    }
+  }
+}
@@ -0,0 +1,212 @@
+use clap::{builder::PossibleValue, Arg, CommandFactory, ValueEnum, ValueHint};
+
+use crate::cli::Cli;
+
+/// Shells `--completions` can write a script for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Shell {
+  Bash,
+  Zsh,
+  Fish,
+  Powershell,
+}
+
+/// An option as the completion scripts need it.
+struct CompletedOption {
+  short: Option<char>,
+  long: Option<String>,
+  help: String,
+  takes_value: bool,
+  value_name: String,
+  values: Vec<String>,
+  is_path: bool,
+}
+
+impl CompletedOption {
+  fn from_arg(arg: &Arg) -> Self {
+    let values = arg.get_possible_values();
+    CompletedOption {
+      short: arg.get_short(),
+      long: arg.get_long().map(String::from),
+      // Only the summary line, the rest of a long help doesn't fit in a completion menu
+      help: arg.get_help().map(|help| help.to_string()).unwrap_or_default().lines().next().unwrap_or_default().into(),
+      takes_value: arg.get_action().takes_values(),
+      value_name: arg.get_value_names().and_then(|names| names.first()).map_or(String::new(), |name| name.to_string()),
+      values: values
+        .iter()
+        .filter(|value| !value.is_hide_set())
+        .map(PossibleValue::get_name)
+        .map(String::from)
+        .collect(),
+      is_path: matches!(arg.get_value_hint(), ValueHint::AnyPath | ValueHint::FilePath | ValueHint::ExecutablePath),
+    }
+  }
+
+  /// The option as typed, long and short forms.
+  fn flags(&self) -> Vec<String> {
+    let long = self.long.iter().map(|long| format!("--{}", long));
+    long.chain(self.short.iter().map(|short| format!("-{}", short))).collect()
+  }
+}
+
+/// The completion script for `shell`, covering every option `Cli` accepts.
+pub fn generate(shell: Shell) -> String {
+  let mut command = Cli::command();
+  // Building adds `--help` and `--version`
+  command.build();
+  let options: Vec<CompletedOption> = command
+    .get_arguments()
+    .filter(|arg| !arg.is_positional() && !arg.is_hide_set())
+    .map(CompletedOption::from_arg)
+    .collect();
+  let name = command.get_name();
+  match shell {
+    Shell::Bash => bash(name, &options),
+    Shell::Zsh => zsh(name, &options),
+    Shell::Fish => fish(name, &options),
+    Shell::Powershell => powershell(name, &options),
+  }
+}
+
+fn bash(name: &str, options: &[CompletedOption]) -> String {
+  let function = format!("_{}", name.replace('-', "_"));
+  let mut value_cases = String::new();
+  for option in options.iter().filter(|option| option.takes_value) {
+    let reply = if option.is_path {
+      String::from("COMPREPLY=($(compgen -f -- \"$cur\"))")
+    } else {
+      format!("COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))", option.values.join(" "))
+    };
+    value_cases.push_str(&format!("    {})\n      {}\n      return\n      ;;\n", option.flags().join("|"), reply));
+  }
+  let flags: Vec<String> = options.iter().flat_map(CompletedOption::flags).collect();
+  format!(
+    "{function}() {{
+  local cur prev
+  cur=\"${{COMP_WORDS[COMP_CWORD]}}\"
+  prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"
+  case \"$prev\" in
+{value_cases}  esac
+  COMPREPLY=($(compgen -W \"{flags}\" -- \"$cur\"))
+}}
+
+complete -F {function} {name}
+",
+    flags = flags.join(" ")
+  )
+}
+
+fn zsh(name: &str, options: &[CompletedOption]) -> String {
+  let mut specs = String::new();
+  for option in options {
+    let help = option.help.replace('\'', "'\\''").replace('[', "\\[").replace(']', "\\]").replace(':', "\\:");
+    let value = if !option.takes_value {
+      String::new()
+    } else if option.is_path {
+      format!(":{}:_files", option.value_name)
+    } else if option.values.is_empty() {
+      format!(":{}:", option.value_name)
+    } else {
+      format!(":{}:({})", option.value_name, option.values.join(" "))
+    };
+    let flags = option.flags();
+    let exclusions = if flags.len() > 1 { format!("({})", flags.join(" ")) } else { String::new() };
+    for flag in &flags {
+      specs.push_str(&format!(" \\\n  '{}{}[{}]{}'", exclusions, flag, help, value));
+    }
+  }
+  format!("#compdef {name}\n\n_arguments -s{specs}\n")
+}
+
+fn fish(name: &str, options: &[CompletedOption]) -> String {
+  let mut script = String::new();
+  for option in options {
+    let mut line = format!("complete -c {}", name);
+    if let Some(short) = option.short {
+      line.push_str(&format!(" -s {}", short));
+    }
+    if let Some(long) = &option.long {
+      line.push_str(&format!(" -l {}", long));
+    }
+    line.push_str(&format!(" -d '{}'", option.help.replace('\'', "\\'")));
+    if option.takes_value && option.is_path {
+      line.push_str(" -r -F");
+    } else if option.takes_value {
+      line.push_str(" -r -f");
+      if !option.values.is_empty() {
+        line.push_str(&format!(" -a '{}'", option.values.join(" ")));
+      }
+    }
+    script.push_str(&line);
+    script.push('\n');
+  }
+  script
+}
+
+fn powershell(name: &str, options: &[CompletedOption]) -> String {
+  let quote = |text: &str| format!("'{}'", text.replace('\'', "''"));
+  let mut value_cases = String::new();
+  for option in options.iter().filter(|option| option.takes_value && !option.is_path) {
+    let values: Vec<String> = option
+      .values
+      .iter()
+      .map(|value| {
+        format!("[CompletionResult]::new({0}, {0}, [CompletionResultType]::ParameterValue, {0})", quote(value))
+      })
+      .collect();
+    for flag in option.flags() {
+      value_cases.push_str(&format!("    {} {{ @({}) }}\n", quote(&flag), values.join(", ")));
+    }
+  }
+  let flags: Vec<String> = options
+    .iter()
+    .flat_map(|option| {
+      option.flags().into_iter().map(|flag| {
+        format!(
+          "      [CompletionResult]::new({0}, {0}, [CompletionResultType]::ParameterName, {1})",
+          quote(&flag),
+          quote(&option.help)
+        )
+      })
+    })
+    .collect();
+  format!(
+    "using namespace System.Management.Automation
+
+Register-ArgumentCompleter -Native -CommandName {name} -ScriptBlock {{
+  param($wordToComplete, $commandAst, $cursorPosition)
+  $previous = $commandAst.CommandElements | Where-Object {{ $_.Extent.EndOffset -lt $cursorPosition }} | Select-Object -Last 1
+  $completions = switch ($previous.ToString()) {{
+{value_cases}    default {{
+      @(
+{flags}
+      )
+    }}
+  }}
+  $completions | Where-Object {{ $_.CompletionText -like \"$wordToComplete*\" }}
+}}
+",
+    name = quote(name),
+    flags = flags.join(",\n")
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn bash_completes_every_option() {
+    let script = generate(Shell::Bash);
+    assert!(script.contains("complete -F _git_branch_manager git-branch-manager"));
+    assert!(script.contains("--completions"));
+    assert!(script.contains("compgen -W \"cli git2\""));
+  }
+
+  #[test]
+  fn fish_offers_backend_values() {
+    let script = generate(Shell::Fish);
+    assert!(script.contains("complete -c git-branch-manager -l backend"));
+    assert!(script.contains("-a 'cli git2'"));
+  }
+}
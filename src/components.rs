@@ -8,8 +8,24 @@ use crate::{
   tui::{Event, Frame},
 };
 
+pub mod branch_diff_view;
 pub mod branch_list;
+pub mod commit_log;
+pub mod generic_input;
+pub mod input_history;
+pub mod list_filter;
+pub mod list_navigation;
+#[cfg(test)]
+pub mod list_snapshot;
+pub mod loading_operation;
+pub mod remote_branch_list;
+pub mod remote_list;
 pub mod stash_list;
+pub mod status_list;
+pub mod tag_list;
+pub mod traits;
+pub mod ui;
+pub mod worktree_list;
 
 /// `Component` is a trait that represents a visual and interactive element of the user interface.
 ///
@@ -85,6 +101,46 @@ pub trait Component {
   fn update(&mut self, action: Action) -> Result<Option<Action>> {
     Ok(None)
   }
+  /// Names of the items staged for deletion, so deletions can be confirmed across views.
+  ///
+  /// # Returns
+  ///
+  /// * `Vec<String>` - The names of the staged items.
+  fn staged_items(&self) -> Vec<String> {
+    vec![]
+  }
+  /// The title of the component, used to label the component in the tab bar.
+  ///
+  /// # Returns
+  ///
+  /// * `&str` - The title of the component.
+  fn title(&self) -> &str {
+    "Component"
+  }
+  /// How many items the component lists, for the status bar.
+  ///
+  /// # Returns
+  ///
+  /// * `(usize, usize)` - The number of visible items, which may be filtered, and the total number of items.
+  fn item_counts(&self) -> (usize, usize) {
+    (0, 0)
+  }
+  /// The keys the component responds to in its current state, for the footer and the help overlay.
+  ///
+  /// # Returns
+  ///
+  /// * `Vec<&'static str>` - Instructions like `d: Delete`.
+  fn instructions(&self) -> Vec<&'static str> {
+    vec![]
+  }
+  /// Whether the component uses esc itself right now, e.g. to clear a filter, rather than it quitting the app.
+  ///
+  /// # Returns
+  ///
+  /// * `bool` - True when esc should go to the component.
+  fn handles_escape(&self) -> bool {
+    false
+  }
   /// Render the component on the screen. (REQUIRED)
   ///
   /// # Arguments
@@ -97,3 +153,51 @@ pub trait Component {
   /// * `Result<()>` - An Ok result or an error.
   fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()>;
 }
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use super::*;
+  use crate::{
+    config::Config,
+    git::{git_repo::GitBranch, mock_git_repo::MockGitRepo},
+  };
+
+  fn mock() -> Box<MockGitRepo> {
+    Box::default()
+  }
+
+  #[test]
+  fn each_view_has_its_title() {
+    let config = Config::default();
+    let feature = GitBranch::new(String::from("feature/a"));
+    let views: Vec<Box<dyn Component>> = vec![
+      Box::new(branch_list::BranchList::new(Arc::new(MockGitRepo::default()), &config)),
+      Box::new(stash_list::StashList::new(mock(), &config)),
+      Box::new(tag_list::TagList::new(mock())),
+      Box::new(status_list::StatusList::new(mock())),
+      Box::new(worktree_list::WorktreeList::new(mock())),
+      Box::new(remote_list::RemoteList::new(mock(), &config)),
+      Box::new(remote_branch_list::RemoteBranchList::new(mock())),
+      Box::new(commit_log::CommitLog::new(Arc::new(MockGitRepo::default()), feature.clone())),
+      Box::new(branch_diff_view::BranchDiffView::new(
+        Arc::new(MockGitRepo::default()),
+        GitBranch::new(String::from("main")),
+        feature,
+      )),
+    ];
+    let titles: Vec<&str> = views.iter().map(|view| view.title()).collect();
+    assert_eq!(titles, [
+      "Branches",
+      "Stashes",
+      "Tags",
+      "Status",
+      "Worktrees",
+      "Remotes",
+      "Remote Branches",
+      "Commits on feature/a",
+      "Diff main..feature/a",
+    ]);
+  }
+}
@@ -0,0 +1,158 @@
+use std::sync::Arc;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+  layout::{Alignment, Constraint, Direction, Layout, Rect},
+  style::{Color, Style},
+  text::{Line, Text},
+  widgets::{block::Title, Block, Borders, Paragraph, Wrap},
+};
+use tracing::error;
+
+use crate::{
+  action::Action,
+  components::{
+    ui::{self, ansi::styled_lines, page_indicator::page_indicator},
+    Component,
+  },
+  error::Error,
+  git::git_repo::{GitBranch, GitRepo},
+  tui::Frame,
+};
+
+/// The diff from one branch to another, opened over the branch list and closed with esc or q.
+pub struct BranchDiffView {
+  repo: Arc<dyn GitRepo + Send + Sync>,
+  from: GitBranch,
+  to: GitBranch,
+  title: String,
+  error: Option<String>,
+  lines: Vec<(String, Style)>,
+  scroll: usize,
+  // Rows of diff that fit, from the last draw, for paging
+  visible_height: usize,
+}
+
+impl BranchDiffView {
+  pub fn new(repo: Arc<dyn GitRepo + Send + Sync>, from: GitBranch, to: GitBranch) -> Self {
+    let title = format!("Diff {}..{}", from.name, to.name);
+    let mut branch_diff_view =
+      BranchDiffView { repo, from, to, title, error: None, lines: Vec::new(), scroll: 0, visible_height: 0 };
+    let result = branch_diff_view.refresh();
+    branch_diff_view.maybe_handle_git_error(result.err());
+    branch_diff_view
+  }
+
+  fn refresh(&mut self) -> Result<(), Error> {
+    let diff = self.repo.diff_branch(&self.from, &self.to)?;
+    self.lines = styled_lines(&diff);
+    if self.lines.is_empty() {
+      self.lines.push((format!("No differences between {} and {}", self.from.name, self.to.name), Style::default()));
+    }
+    self.scroll = self.scroll.min(self.max_scroll());
+    Ok(())
+  }
+
+  fn max_scroll(&self) -> usize {
+    self.lines.len().saturating_sub(1)
+  }
+
+  fn scroll_down(&mut self, rows: usize) {
+    self.scroll = self.scroll.saturating_add(rows).min(self.max_scroll());
+  }
+
+  fn scroll_up(&mut self, rows: usize) {
+    self.scroll = self.scroll.saturating_sub(rows);
+  }
+
+  fn maybe_handle_git_error(&mut self, err: Option<Error>) {
+    if let Some(error) = err {
+      error!("{}", error);
+      self.error = Some(error.to_string());
+    }
+  }
+
+  fn render_diff(&mut self, f: &mut Frame<'_>, area: Rect) {
+    // The borders take a row from the top and bottom
+    self.visible_height = usize::from(area.height.saturating_sub(2));
+    let mut block = Block::default().title(self.title.as_str()).borders(Borders::ALL);
+    if let Some(page) = page_indicator(self.scroll, self.lines.len(), self.visible_height) {
+      block = block.title(Title::from(page).alignment(Alignment::Right));
+    }
+    let lines: Vec<Line> = self.lines.iter().map(|(line, style)| Line::styled(line.as_str(), *style)).collect();
+    let scroll = u16::try_from(self.scroll).unwrap_or(u16::MAX);
+    f.render_widget(Paragraph::new(Text::from(lines)).block(block).scroll((scroll, 0)), area);
+  }
+}
+
+impl Component for BranchDiffView {
+  fn handle_key_events(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+    self.error = None;
+    match key {
+      KeyEvent { code: KeyCode::Esc | KeyCode::Char('q'), modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        return Ok(Some(Action::PopView));
+      },
+      KeyEvent { code: KeyCode::Down | KeyCode::Char('j'), modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        self.scroll_down(1)
+      },
+      KeyEvent { code: KeyCode::Up | KeyCode::Char('k'), modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        self.scroll_up(1)
+      },
+      KeyEvent { code: KeyCode::PageDown, modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        self.scroll_down(self.visible_height.max(1))
+      },
+      KeyEvent { code: KeyCode::PageUp, modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        self.scroll_up(self.visible_height.max(1))
+      },
+      KeyEvent { code: KeyCode::Char('r' | 'R'), modifiers: KeyModifiers::CONTROL, kind: _, state: _ } => {
+        return Ok(Some(Action::Refresh));
+      },
+      _ => {},
+    }
+    Ok(None)
+  }
+
+  fn update(&mut self, action: Action) -> color_eyre::Result<Option<Action>> {
+    if action == Action::Refresh {
+      let result = self.refresh();
+      self.maybe_handle_git_error(result.err());
+    }
+    Ok(None)
+  }
+
+  fn title(&self) -> &str {
+    &self.title
+  }
+
+  fn item_counts(&self) -> (usize, usize) {
+    (self.lines.len(), self.lines.len())
+  }
+
+  fn instructions(&self) -> Vec<&'static str> {
+    vec!["esc/q: Back to branches", "j/k: Scroll", "^ + r: Refresh"]
+  }
+
+  fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> color_eyre::Result<()> {
+    let instructions = self.instructions();
+    // The layout margin takes a column from each side
+    let footer_height = ui::instruction_footer::preferred_height(&instructions, area.width.saturating_sub(2));
+    let error_height = self.error.as_ref().map_or(0, |error| error.lines().count() + 2);
+    let layout = Layout::new(Direction::Vertical, [
+      Constraint::Min(1),
+      Constraint::Length(u16::try_from(error_height)?),
+      Constraint::Length(footer_height),
+    ])
+    .margin(1)
+    .split(area);
+    self.render_diff(f, layout[0]);
+    if let Some(error) = &self.error {
+      let component = Paragraph::new(Text::from(error.as_str()))
+        .block(Block::bordered().title("Error"))
+        .style(Style::from(Color::Red))
+        .wrap(Wrap { trim: true });
+      f.render_widget(component, layout[1]);
+    }
+    ui::instruction_footer::render(f, layout[2], &instructions);
+    Ok(())
+  }
+}
@@ -1,93 +1,469 @@
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::{
+  collections::{HashMap, HashSet},
+  path::PathBuf,
+  sync::Arc,
+  time::{Duration, Instant},
+};
+
+use crossterm::event::{KeyCode, KeyEvent, MouseEvent};
 use ratatui::{
-  layout::{Constraint, Direction, Layout, Rect},
+  layout::{Alignment, Constraint, Direction, Flex, Layout, Rect},
   style::{Color, Modifier, Style},
-  text::Text,
-  widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+  text::{Line, Text},
+  widgets::{block::Title, Block, Borders, Cell, Clear, Paragraph, Row, Table, TableState, Wrap},
 };
+use tokio::sync::mpsc::UnboundedSender;
 use tracing::error;
 
 use crate::{
   action::Action,
+  app::undo_stack::UndoEntry,
   components::{
-    branch_list::{branch_input::BranchInput, branch_item::BranchItem, instruction_footer::InstructionFooter},
+    branch_list::{
+      branch_data_source::BranchDataSource,
+      branch_detail_panel::BranchDetailPanel,
+      branch_input_handler::{BranchInput, BranchInputHandler},
+      branch_item::{group_header_row, BranchItem, BranchRowContext},
+      branch_rename_input_handler::{BranchRenameInput, BranchRenameInputHandler},
+      export_scope_selector::{ExportScope, ExportScopeSelector},
+      instruction_footer::InstructionFooter,
+      sort::{sort_branches, sort_by_branch, SortOrder},
+      upstream_input_handler::{UpstreamInput, UpstreamInputHandler},
+      worktree_path_input_handler::{WorktreePathInput, WorktreePathInputHandler},
+    },
+    list_filter::ListFilter,
+    list_navigation::{ListClick, ListNavigation},
+    loading_operation::LoadingOperation,
+    traits::{has_columns::HasColumns, list_data_source::ListDataSource, managed_item::ManagedItem},
+    ui::{self, diff_popup::DiffPopup, notification::NotificationLevel, page_indicator::page_indicator},
     Component,
   },
-  error::Error,
-  git::git_repo::{GitBranch, GitRepo},
+  config::{keybindings::KeyBindingConfig, theme::Theme, BranchListColumns, Config},
+  error::{failed_deletions, Error},
+  git::git_repo::{GitBranch, GitRemoteBranch, GitRepo, PullResult, RepoState},
   tui::Frame,
+  utils::{accessibility, get_data_dir},
 };
 
-mod branch_input;
+mod branch_data_source;
+mod branch_detail_panel;
+mod branch_input_handler;
 mod branch_item;
+mod branch_rename_input_handler;
+pub mod export_scope_selector;
 mod instruction_footer;
+mod sort;
+mod upstream_input_handler;
+mod worktree_path_input_handler;
+
+/// Runs after every load of the branches, before the next render, so callers can adjust the list or selection.
+pub type ItemsLoadedCallback = Box<dyn Fn(&mut BranchList) + Send + Sync>;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum Mode {
   Selection,
   Input,
+  RenameInput,
+  UpstreamInput,
+  WorktreeInput,
+}
+
+/// A row of the list, group headers are only shown when branches are grouped by prefix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum BranchRow {
+  Group { name: String, branch_count: usize, collapsed: bool },
+  Branch(usize),
 }
 
 pub struct BranchList {
   mode: Mode,
-  repo: Box<dyn GitRepo>,
+  // Shared with background operations like fetching
+  repo: Arc<dyn GitRepo + Send + Sync>,
+  action_tx: Option<UnboundedSender<Action>>,
   error: Option<String>,
+  pull_results: Option<Vec<PullResult>>,
+  diff_popup: Option<DiffPopup>,
+  export_scope_selector: Option<ExportScopeSelector>,
+  preserve_selection_on_refresh: bool,
+  show_branch_age: bool,
+  columns: BranchListColumns,
+  sort_order: SortOrder,
+  // Branch creation times need a git call each, keyed by branch name and dropped when the branch moves
+  creation_times: HashMap<String, Option<i64>>,
+  on_items_loaded: Option<ItemsLoadedCallback>,
+  main_branch: String,
+  protected_branches: Vec<String>,
+  key_bindings: KeyBindingConfig,
+  theme: Arc<Theme>,
+  show_details: bool,
+  loading: LoadingOperation,
+  // Advances every tick while loading so the spinner moves between git's updates
+  spinner_frame: usize,
+  repo_state: RepoState,
+  last_announced: Option<String>,
+  refresh_debounce: Duration,
+  last_refresh: Option<Instant>,
   // List state
   branches: Vec<BranchItem>,
-  list_state: ListState,
+  filter: ListFilter,
+  table_state: TableState,
   selected_index: usize,
+  navigation: ListNavigation,
+  // Names of the branches picked for bulk operations, in the order they were picked. Names rather than indices so
+  // they survive branches being added, removed and re-sorted.
+  multi_selected: Vec<String>,
+  grouped: bool,
+  collapsed_groups: HashSet<String>,
+  // Set when a group header is selected rather than a branch
+  selected_group: Option<String>,
   // Components
   branch_input: BranchInput,
+  rename_input: BranchRenameInput,
+  upstream_input: UpstreamInput,
+  worktree_input: WorktreePathInput,
+  detail_panel: BranchDetailPanel,
+  data_source: BranchDataSource,
   instruction_footer: InstructionFooter,
 }
 
 impl BranchList {
-  pub fn new(repo: Box<dyn GitRepo>) -> Self {
-    // Assume branch names are all valid as they come from git
-    let branches: Vec<BranchItem> =
-      repo.local_branches().unwrap().iter().map(|branch| BranchItem::new(branch.clone(), true)).collect();
-    BranchList {
+  pub fn new(repo: Arc<dyn GitRepo + Send + Sync>, config: &Config) -> Self {
+    let mut branch_list = BranchList {
       repo,
       mode: Mode::Selection,
+      action_tx: None,
       error: None,
-      branches,
-      list_state: ListState::default(),
+      pull_results: None,
+      diff_popup: None,
+      export_scope_selector: None,
+      preserve_selection_on_refresh: config.preserve_selection_on_refresh,
+      show_branch_age: config.show_branch_age,
+      columns: config.branch_list_columns,
+      sort_order: SortOrder::default(),
+      creation_times: HashMap::new(),
+      on_items_loaded: None,
+      main_branch: config.main_branch.clone(),
+      protected_branches: config.protected_branches.clone(),
+      key_bindings: config.branch_keybindings.clone(),
+      show_details: false,
+      loading: LoadingOperation::None,
+      spinner_frame: 0,
+      repo_state: RepoState::Clean,
+      last_announced: None,
+      refresh_debounce: Duration::from_millis(config.refresh_debounce_ms),
+      last_refresh: None,
+      branches: Vec::new(),
+      filter: ListFilter::default(),
+      table_state: TableState::default(),
       selected_index: 0,
-      branch_input: BranchInput::new(),
+      navigation: ListNavigation::default(),
+      multi_selected: Vec::new(),
+      grouped: false,
+      collapsed_groups: HashSet::new(),
+      selected_group: None,
+      branch_input: BranchInput::new(BranchInputHandler::default(), "branch_input", config.theme.clone()),
+      rename_input: BranchRenameInput::new(BranchRenameInputHandler::default(), "rename_input", config.theme.clone()),
+      upstream_input: UpstreamInput::new(UpstreamInputHandler::default(), "upstream_input", config.theme.clone()),
+      worktree_input: WorktreePathInput::new(
+        WorktreePathInputHandler::default(),
+        "worktree_input",
+        config.theme.clone(),
+      ),
+      theme: config.theme.clone(),
+      detail_panel: BranchDetailPanel::default(),
+      data_source: BranchDataSource::new(config.max_branches_displayed),
       instruction_footer: InstructionFooter::default(),
+    };
+    let result = branch_list.refresh();
+    branch_list.maybe_handle_git_error(result.err());
+    branch_list.select_head();
+    branch_list
+  }
+
+  /// Selects the checked out branch, when there is one, so the list opens on it.
+  fn select_head(&mut self) {
+    if let Ok(head) = self.repo.current_branch() {
+      self.jump_to_item_by_name(&head.name);
+    }
+  }
+
+  /// The callback also runs straight away for the branches loaded by `new`.
+  pub fn set_on_items_loaded(mut self, callback: ItemsLoadedCallback) -> Self {
+    self.on_items_loaded = Some(callback);
+    self.run_on_items_loaded();
+    self
+  }
+
+  fn run_on_items_loaded(&mut self) {
+    // Take the callback so it can borrow the list mutably
+    if let Some(callback) = self.on_items_loaded.take() {
+      callback(self);
+      self.on_items_loaded = Some(callback);
+    }
+  }
+
+  fn load_branches(&mut self) -> Result<Vec<BranchItem>, Error> {
+    let mut branches = self.data_source.load(&*self.repo)?;
+    let ahead_behind = self.repo.get_ahead_behind_bulk()?;
+    for branch in branches.iter_mut() {
+      (branch.ahead, branch.behind) = ahead_behind.get(&branch.name).copied().unwrap_or_default();
+    }
+    if self.show_branch_age || self.columns.last_commit_date || self.sort_order == SortOrder::LastCommitDate {
+      let last_commit_times = self.repo.get_last_commit_times()?;
+      for branch in branches.iter_mut() {
+        branch.last_commit_at = last_commit_times.get(&branch.name).copied();
+      }
+    }
+    if self.sort_order == SortOrder::CreationDate {
+      for branch in branches.iter_mut() {
+        if !self.creation_times.contains_key(&branch.name) {
+          let created_at = self.repo.get_branch_creation_time(branch)?;
+          self.creation_times.insert(branch.name.clone(), created_at);
+        }
+        branch.created_at = self.creation_times[&branch.name];
+      }
+    }
+    sort_branches(&mut branches, self.sort_order);
+    let worktrees = self.repo.list_worktrees()?;
+    for branch in branches.iter_mut() {
+      branch.is_worktree =
+        worktrees.iter().any(|worktree| !worktree.is_main && worktree.branch.as_ref() == Some(&branch.name));
     }
+    // Assume branch names are all valid as they come from git
+    Ok(
+      branches
+        .into_iter()
+        .map(|branch| {
+          let merged = self.data_source.is_merged(&branch.name);
+          BranchItem { merged, ..BranchItem::new(branch, true) }
+        })
+        .collect(),
+    )
   }
 
   pub fn clear_error(&mut self) {
     self.error = None;
+    self.pull_results = None;
   }
 
-  pub fn select_previous(&mut self) {
-    if self.selected_index == 0 {
-      self.selected_index = self.branches.len() - 1;
-      return;
+  fn visible_indices(&self) -> Vec<usize> {
+    self.filter.visible_indices(self.branches.len())
+  }
+
+  /// Recompute the visible branches, moving the selection to the first one if the selected branch was filtered out.
+  fn apply_filter(&mut self) {
+    self.filter.apply(&self.branches, |item| vec![item.branch.name.as_str()]);
+    let visible = self.visible_indices();
+    if !visible.contains(&self.selected_index) {
+      self.selected_index = visible.first().copied().unwrap_or(0);
     }
-    if self.selected_index >= self.branches.len() {
-      self.selected_index = self.branches.len() - 1;
+    self.forget_missing_group();
+  }
+
+  /// What the list shows, the visible branches under a header for each group when grouped by prefix.
+  fn rows(&self) -> Vec<BranchRow> {
+    let visible = self.visible_indices();
+    if !self.grouped {
+      return visible.into_iter().map(BranchRow::Branch).collect();
+    }
+    let mut rows = Vec::new();
+    for group in BranchDataSource::groups(self.branches.iter().map(|item| &item.branch)) {
+      let indices: Vec<usize> = group.indices.into_iter().filter(|index| visible.contains(index)).collect();
+      // Groups with every branch filtered out are hidden too
+      if indices.is_empty() {
+        continue;
+      }
+      let collapsed = self.collapsed_groups.contains(&group.name);
+      rows.push(BranchRow::Group { name: group.name, branch_count: indices.len(), collapsed });
+      if !collapsed {
+        rows.extend(indices.into_iter().map(BranchRow::Branch));
+      }
+    }
+    rows
+  }
+
+  /// Where the selection is among the rows, `None` when the selected branch or group isn't shown.
+  fn selected_row(&self, rows: &[BranchRow]) -> Option<usize> {
+    rows.iter().position(|row| {
+      match (row, &self.selected_group) {
+        (BranchRow::Group { name, .. }, Some(selected)) => name == selected,
+        (BranchRow::Branch(index), None) => *index == self.selected_index,
+        _ => false,
+      }
+    })
+  }
+
+  fn select_row(&mut self, row: &BranchRow) {
+    match row {
+      BranchRow::Group { name, .. } => self.selected_group = Some(name.clone()),
+      BranchRow::Branch(index) => {
+        self.selected_group = None;
+        self.selected_index = *index;
+      },
+    }
+  }
+
+  /// Selects a branch by its index in all branches, expanding its group so it can be seen.
+  fn select_branch(&mut self, index: usize) {
+    self.selected_index = index;
+    self.selected_group = None;
+    if let Some(item) = self.branches.get(index) {
+      self.collapsed_groups.remove(BranchDataSource::group_name(&item.branch.name));
+    }
+  }
+
+  /// Moves the selection back to a branch when its group header is no longer shown.
+  fn forget_missing_group(&mut self) {
+    if self.selected_group.is_some() && self.selected_row(&self.rows()).is_none() {
+      self.selected_group = None;
+    }
+  }
+
+  fn toggle_selected_group(&mut self) {
+    let Some(name) = self.selected_group.clone() else {
       return;
+    };
+    if !self.collapsed_groups.remove(&name) {
+      self.collapsed_groups.insert(name);
     }
-    self.selected_index -= 1;
+  }
+
+  // `selected_index` indexes all branches, selection moves between the shown rows skipping collapsed groups
+  pub fn select_previous(&mut self) {
+    let rows = self.rows();
+    let Some(last) = rows.last() else {
+      return;
+    };
+    let target = match self.selected_row(&rows) {
+      Some(0) | None => last,
+      Some(position) => &rows[position - 1],
+    };
+    self.select_row(target);
   }
 
   pub fn select_next(&mut self) {
-    if self.selected_index == self.branches.len() - 1 {
-      self.selected_index = 0;
+    let rows = self.rows();
+    let Some(first) = rows.first() else {
       return;
+    };
+    let target = match self.selected_row(&rows) {
+      Some(position) if position + 1 < rows.len() => &rows[position + 1],
+      _ => first,
+    };
+    self.select_row(target);
+  }
+
+  /// Moves the selection for a `ListNavigation` motion, between the shown rows.
+  fn apply_motion(&mut self, action: &Action) {
+    let rows = self.rows();
+    let position = self.selected_row(&rows).unwrap_or(0);
+    if let Some(target) = self.navigation.target_position(action, position, rows.len()) {
+      self.select_row(&rows[target]);
     }
-    if self.selected_index >= self.branches.len() {
-      self.selected_index = 0;
+  }
+
+  fn announce_selection(&mut self) {
+    let Some(selected) = self.get_selected_branch() else {
       return;
+    };
+    let announcement = format!(
+      "Selected branch {} of {}: {}",
+      self.selected_index + 1,
+      self.branches.len(),
+      selected.branch.display_name()
+    );
+    if self.last_announced.as_ref() != Some(&announcement) {
+      accessibility::announce(&announcement);
+      self.last_announced = Some(announcement);
     }
-    self.selected_index += 1;
+  }
+
+  /// Select the first branch matching `predicate`, returns whether a match was found.
+  pub fn select_by_predicate<F: Fn(&BranchItem) -> bool>(&mut self, predicate: F) -> bool {
+    let Some(index) = self.branches.iter().position(predicate) else {
+      return false;
+    };
+    self.select_branch(index);
+    true
+  }
+
+  pub fn jump_to_item_by_name(&mut self, name: &str) -> bool {
+    self.select_by_predicate(|b| b.branch.unique_key() == name)
+  }
+
+  /// Coalesces refresh requests that arrive straight after another refresh, e.g. from a watcher and an operation.
+  fn refreshed_recently(&self) -> bool {
+    self.last_refresh.is_some_and(|last_refresh| last_refresh.elapsed() < self.refresh_debounce)
+  }
+
+  fn refresh(&mut self) -> Result<(), Error> {
+    let previous_key = self.get_selected_branch().map(|b| String::from(b.branch.unique_key()));
+    let previous_index = self.selected_index;
+    self.last_refresh = Some(Instant::now());
+    self.branches = self.load_branches()?;
+    self.forget_missing_multi_selected();
+    self.load_repo_state()?;
+    let stale = self.data_source.take_stale();
+    for name in &stale {
+      self.creation_times.remove(name);
+    }
+    // Details compare against the main branch so they are all out of date when it moves
+    if stale.contains(&self.main_branch) {
+      self.detail_panel.clear();
+    } else {
+      self.detail_panel.invalidate(&stale);
+    }
+
+    if !self.preserve_selection_on_refresh {
+      self.selected_index = 0;
+      *self.table_state.offset_mut() = 0;
+    } else if !previous_key.is_some_and(|key| self.jump_to_item_by_name(&key)) {
+      self.selected_index = previous_index.min(self.branches.len().saturating_sub(1));
+    }
+    if self.filter.is_active() {
+      self.apply_filter();
+    }
+    self.forget_missing_group();
+    self.run_on_items_loaded();
+    Ok(())
+  }
+
+  fn load_repo_state(&mut self) -> Result<(), Error> {
+    let repo_state = self.repo.get_repository_state()?;
+    if repo_state == self.repo_state {
+      return Ok(());
+    }
+    self.repo_state = repo_state;
+    if let Some(Err(err)) = self.action_tx.as_ref().map(|tx| tx.send(Action::RepoStateChanged(repo_state))) {
+      error!("Failed to send the repository state: {}", err);
+    }
+    Ok(())
+  }
+
+  /// Branch operations tend to fail part way through a rebase or merge, so check the user wants to go ahead.
+  fn confirm_if_in_progress(&self, action: Action, doing: &str) -> Action {
+    if self.repo_state == RepoState::Clean {
+      return action;
+    }
+    let message = format!("A {} is in progress, {} may fail. Continue?", self.repo_state.operation(), doing);
+    Action::RequestConfirmation(message, Box::new(action))
+  }
+
+  /// Deleted branches can only be recovered from the reflog, so always check first.
+  fn confirm_delete(&self, action: Action, names: &[&str]) -> Option<Action> {
+    let mut message = match names {
+      [] => return None,
+      [name] => format!("Delete branch {}?", name),
+      names => format!("Delete {} staged branches ({})?", names.len(), names.join(", ")),
+    };
+    if self.repo_state != RepoState::Clean {
+      message = format!("A {} is in progress, deleting may fail. {}", self.repo_state.operation(), message);
+    }
+    Some(Action::RequestConfirmation(message, Box::new(action)))
   }
 
   fn get_selected_branch(&self) -> Option<&BranchItem> {
-    self.branches.get(self.selected_index)
+    self.branches.get(self.selected_index).filter(|_| self.selected_group.is_none())
   }
 
   fn checkout_selected(&mut self) -> Result<(), Error> {
@@ -103,7 +479,41 @@ impl BranchList {
     Ok(())
   }
 
+  fn toggle_multi_select(&mut self) {
+    let Some(name) = self.get_selected_branch().map(|b| b.branch.name.clone()) else {
+      return;
+    };
+    match self.multi_selected.iter().position(|picked| picked == &name) {
+      Some(position) => {
+        self.multi_selected.remove(position);
+      },
+      None => self.multi_selected.push(name),
+    }
+  }
+
+  /// Names of every branch that can be picked for bulk operations, the checked out one can't be deleted.
+  fn multi_selectable(&self) -> Vec<String> {
+    self.branches.iter().filter(|b| !b.branch.is_head).map(|b| b.branch.name.clone()).collect()
+  }
+
+  /// Drops picked branches that are no longer listed, e.g. once they have been deleted.
+  fn forget_missing_multi_selected(&mut self) {
+    let branches = &self.branches;
+    self.multi_selected.retain(|name| branches.iter().any(|b| &b.branch.name == name));
+  }
+
+  fn stage_multi_selected_for_deletion(&mut self) {
+    for name in std::mem::take(&mut self.multi_selected) {
+      if let Some(item) = self.branches.iter_mut().find(|item| item.branch.name == name && !item.branch.is_head) {
+        item.stage_for_deletion(true);
+      }
+    }
+  }
+
   pub fn stage_selected_for_deletion(&mut self, stage: bool) {
+    if self.selected_group.is_some() {
+      return;
+    }
     let maybe_selected = self.branches.get_mut(self.selected_index);
     if maybe_selected.is_none() {
       return;
@@ -124,91 +534,474 @@ impl BranchList {
     if delete_result.is_err() {
       return Ok(());
     }
+    self.record_undo(&self.branches[self.selected_index].branch);
     self.branches.remove(self.selected_index);
+    self.forget_missing_multi_selected();
     if self.selected_index >= self.branches.len() {
       self.selected_index -= 1;
     }
     Ok(())
   }
 
-  pub fn delete_staged_branches(&mut self) -> Result<(), Error> {
+  /// Deletes the staged branches, returning how many were deleted and the names of those that failed with why.
+  fn delete_staged_branches(&mut self) -> (usize, Vec<(String, String)>) {
     let mut indexes_to_delete: Vec<usize> = Vec::new();
+    let mut failures: Vec<(String, String)> = Vec::new();
 
     for branch_index in 0..self.branches.len() {
       let branch_item = &self.branches[branch_index];
       if !branch_item.staged_for_deletion {
         continue;
       }
-      let del_result = self.repo.delete_branch(&branch_item.branch);
-      if del_result.is_ok() {
-        indexes_to_delete.push(branch_index);
-      } else {
-        // TODO communicate deletion error
+      match self.repo.delete_branch(&branch_item.branch) {
+        Ok(()) => {
+          self.record_undo(&branch_item.branch);
+          indexes_to_delete.push(branch_index);
+        },
+        Err(err) => failures.push((branch_item.branch.name.clone(), err.reason())),
       }
     }
 
+    let deleted = indexes_to_delete.len();
     // Sort and reverse, so we remove branches starting from the end,
     // which means we don't need to worry about changing array positions.
     indexes_to_delete.reverse();
     for index in indexes_to_delete {
       self.branches.remove(index);
     }
+    self.forget_missing_multi_selected();
     if self.selected_index >= self.branches.len() {
-      self.selected_index = self.branches.len() - 1
+      self.selected_index = self.branches.len().saturating_sub(1)
     } else if self.selected_index != 0 {
       self.selected_index -= 1
     }
+    (deleted, failures)
+  }
+
+  /// Remembers a deleted branch so it can be brought back with undo.
+  fn record_undo(&self, branch: &GitBranch) {
+    if branch.sha.is_empty() {
+      return;
+    }
+    let upstream = branch.upstream.as_ref().filter(|upstream| !upstream.gone).map(|upstream| upstream.name.clone());
+    let entry = UndoEntry::DeletedBranch { name: branch.name.clone(), sha: branch.sha.clone(), upstream };
+    if let Some(Err(err)) = self.action_tx.as_ref().map(|tx| tx.send(Action::RecordUndo(entry))) {
+      error!("Failed to record the deletion of {}: {}", branch.name, err);
+    }
+  }
+
+  /// The remote and the branch name on it for the selected branch's upstream.
+  fn selected_remote_branch(&self) -> Option<(String, GitRemoteBranch)> {
+    let upstream = self.get_selected_branch()?.branch.upstream.clone().filter(|upstream| !upstream.gone)?;
+    let (remote, _) = upstream.name.split_once('/')?;
+    Some((String::from(remote), upstream))
+  }
+
+  fn request_delete_remote_branch(&mut self) -> Option<Action> {
+    let (remote, upstream) = self.selected_remote_branch()?;
+    let short_name = upstream.name.strip_prefix(&format!("{}/", remote)).unwrap_or(&upstream.name);
+    if self.protected_branches.iter().any(|protected| protected == short_name) {
+      self.error = Some(format!("{} is protected, it can't be deleted from {}", short_name, remote));
+      return None;
+    }
+    let message = format!("Delete remote branch {}? This cannot be undone.", upstream.name);
+    Some(Action::RequestConfirmation(message, Box::new(Action::DeleteRemoteBranch)))
+  }
+
+  fn delete_remote_branch(&mut self) -> Result<(), Error> {
+    let Some((remote, upstream)) = self.selected_remote_branch() else {
+      return Ok(());
+    };
+    self.repo.delete_remote_branch(&remote, &upstream)?;
+    self.refresh()
+  }
+
+  fn init_rename_input(&mut self) -> Option<Action> {
+    // A detached HEAD is listed like `(HEAD detached at 8fb5d9b)` and isn't a branch that can be renamed
+    let selected = self.get_selected_branch().filter(|b| !b.branch.name.starts_with('('))?;
+    let old_name = selected.branch.name.clone();
+    let existing_names = self.branches.iter().map(|b| b.branch.name.clone()).collect();
+    self.rename_input.handler_mut().set_branch(old_name.clone(), existing_names);
+    self.rename_input.init_style();
+    self.rename_input.set_text(old_name);
+    self.mode = Mode::RenameInput;
+    Some(Action::StartInputMode)
+  }
+
+  fn init_upstream_input(&mut self) -> Result<Option<Action>, Error> {
+    let Some(selected) = self.get_selected_branch().filter(|b| !b.branch.name.starts_with('(')) else {
+      return Ok(None);
+    };
+    let branch_name = selected.branch.name.clone();
+    let remote_branches: Vec<String> = self.repo.remote_branches()?.into_iter().map(|branch| branch.name).collect();
+    // Suggest the remote branch of the same name, the usual choice
+    let suggestion =
+      remote_branches.iter().find(|name| name.split_once('/').is_some_and(|(_, name)| name == branch_name));
+    let suggestion = suggestion.cloned().unwrap_or_default();
+    self.upstream_input.handler_mut().set_branch(branch_name, remote_branches);
+    self.upstream_input.init_style();
+    self.upstream_input.set_text(suggestion);
+    self.mode = Mode::UpstreamInput;
+    Ok(Some(Action::StartInputMode))
+  }
+
+  fn set_upstream(&mut self, branch_name: &str, upstream: &str) -> Result<(), Error> {
+    let Some(item) = self.branches.iter().find(|b| b.branch.name == branch_name) else {
+      return Ok(());
+    };
+    self.repo.set_upstream(&item.branch, upstream.trim())?;
+    self.detail_panel.invalidate(&[String::from(branch_name)]);
+    // Ahead and behind counts change with the upstream so reload rather than patch the branch
+    self.refresh()
+  }
+
+  fn unset_upstream(&mut self) -> Result<(), Error> {
+    let Some(selected) = self.get_selected_branch().filter(|b| b.branch.upstream.is_some()) else {
+      return Ok(());
+    };
+    let branch = selected.branch.clone();
+    self.repo.unset_upstream(&branch)?;
+    self.detail_panel.invalidate(&[branch.name]);
+    self.refresh()
+  }
+
+  fn rename_branch(&mut self, old_name: &str, new_name: &str) -> Result<(), Error> {
+    let Some(item) = self.branches.iter_mut().find(|b| b.branch.name == old_name) else {
+      return Ok(());
+    };
+    self.repo.rename_branch(&item.branch, new_name)?;
+    item.branch.name = String::from(new_name);
+    for picked in self.multi_selected.iter_mut().filter(|picked| *picked == old_name) {
+      *picked = String::from(new_name);
+    }
+    sort_by_branch(&mut self.branches, self.sort_order, |item| &item.branch);
+    self.detail_panel.invalidate(&[String::from(old_name)]);
+    self.jump_to_item_by_name(new_name);
     Ok(())
   }
 
   fn create_branch(&mut self, name: String) -> Result<(), Error> {
-    let branch = GitBranch { name: name.clone(), is_head: false, upstream: None };
+    let branch = GitBranch::new(name.clone());
     self.repo.create_branch(&branch)?;
     self.branches.push(BranchItem::new(branch, true));
-    self.branches.sort_by(|a, b| a.branch.name.cmp(&b.branch.name));
+    sort_by_branch(&mut self.branches, self.sort_order, |item| &item.branch);
     self.repo.checkout_branch_from_name(&name)?;
     for existing_branch in self.branches.iter_mut() {
       existing_branch.branch.is_head = existing_branch.branch.name == name;
     }
-    self.selected_index = self.branches.iter().position(|b| b.branch.name == name).unwrap_or(0);
+    if !self.jump_to_item_by_name(&name) {
+      self.selected_index = 0;
+    }
+    Ok(())
+  }
+
+  /// Suggests a sibling of the main worktree named after the repository and branch, e.g. `../repo-feature-foo`.
+  fn default_worktree_path(&self, branch: &GitBranch) -> Result<String, Error> {
+    let worktrees = self.repo.list_worktrees()?;
+    let main_path = worktrees.iter().find(|worktree| worktree.is_main).map(|worktree| worktree.path.as_path());
+    let Some(main_path) = main_path else {
+      return Ok(branch.name.replace('/', "-"));
+    };
+    let repo_name = main_path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+    let dir_name = format!("{}-{}", repo_name, branch.name.replace('/', "-"));
+    let path = main_path.parent().map_or_else(|| PathBuf::from(&dir_name), |parent| parent.join(&dir_name));
+    Ok(path.to_string_lossy().into_owned())
+  }
+
+  fn init_worktree_input(&mut self) -> Result<Option<Action>, Error> {
+    let Some(selected) = self.get_selected_branch().filter(|b| !b.branch.is_head && !b.branch.is_worktree) else {
+      return Ok(None);
+    };
+    let path = self.default_worktree_path(&selected.branch.clone())?;
+    self.mode = Mode::WorktreeInput;
+    self.worktree_input.init_style();
+    self.worktree_input.set_text(path);
+    Ok(Some(Action::StartInputMode))
+  }
+
+  fn create_worktree(&mut self, path: &str) -> Result<(), Error> {
+    let Some(selected) = self.get_selected_branch() else {
+      return Ok(());
+    };
+    self.repo.create_worktree(&selected.branch, path)?;
+    self.refresh()
+  }
+
+  /// Run a slow git operation off the UI thread, `completed` makes the action sent with the result when it finishes.
+  fn run_in_background<F>(
+    &mut self,
+    description: &'static str,
+    operation: F,
+    completed: fn(Result<(), String>) -> Action,
+  ) where
+    F: FnOnce(&dyn GitRepo) -> Result<(), Error> + Send + 'static,
+  {
+    let Some(action_tx) = self.action_tx.clone() else {
+      return;
+    };
+    // One at a time, git takes locks that a second operation would trip over
+    if self.loading != LoadingOperation::None {
+      return;
+    }
+    self.loading = LoadingOperation::processing(description);
+    self.spinner_frame = 0;
+    let repo = Arc::clone(&self.repo);
+    tokio::task::spawn_blocking(move || {
+      let result = operation(&*repo).map_err(|err| err.to_string());
+      if let Err(err) = action_tx.send(completed(result)) {
+        error!("Failed to send the result of {}: {}", description, err);
+      }
+    });
+  }
+
+  fn fetch(&mut self, remote: Option<String>) {
+    self.run_in_background("Fetching", move |repo| repo.fetch(remote.as_deref()), Action::FetchCompleted);
+  }
+
+  fn merge_selected(&mut self) {
+    let Some(selected) = self.get_selected_branch().filter(|b| !b.branch.is_head) else {
+      return;
+    };
+    let branch = selected.branch.clone();
+    self.run_in_background("Merging", move |repo| repo.merge_branch(&branch), Action::MergeCompleted);
+  }
+
+  fn rebase_onto_selected(&mut self) {
+    let Some(selected) = self.get_selected_branch().filter(|b| !b.branch.is_head) else {
+      return;
+    };
+    let onto = selected.branch.clone();
+    self.run_in_background("Rebasing", move |repo| repo.rebase_branch(&onto), Action::RebaseCompleted);
+  }
+
+  fn push_selected(&mut self, force: bool) {
+    let Some(selected) = self.get_selected_branch().filter(|b| b.branch.is_head) else {
+      return;
+    };
+    let branch = selected.branch.clone();
+    // Branches without an upstream need a remote, ask rather than guess which one
+    let Some(upstream) = branch.upstream.as_ref().filter(|upstream| !upstream.gone) else {
+      self.error = Some(format!(
+        "{} has no upstream to push to, set one with `git push --set-upstream <remote> {}`",
+        branch.name, branch.name
+      ));
+      return;
+    };
+    let remote = upstream.name.split_once('/').map(|(remote, _)| String::from(remote));
+    self.run_in_background(
+      "Pushing",
+      move |repo| repo.push_branch(&branch, remote.as_deref(), force),
+      Action::PushCompleted,
+    );
+  }
+
+  fn pull_current(&mut self) {
+    let Some(selected) = self.get_selected_branch().filter(|b| b.branch.is_head) else {
+      return;
+    };
+    if selected.branch.upstream.as_ref().is_none_or(|upstream| upstream.gone) {
+      let name = &selected.branch.name;
+      self.error = Some(format!(
+        "{} has no upstream to pull from, set one with `git branch --set-upstream-to <remote>/<branch> {}`",
+        name, name
+      ));
+      return;
+    }
+    self.run_in_background("Pulling", |repo| repo.pull(None, false), Action::PullCompleted);
+  }
+
+  fn prune_gone_branches(&mut self) -> color_eyre::Result<()> {
+    let pruned = match self.repo.prune_gone_branches() {
+      Ok(pruned) => pruned,
+      Err(err) => {
+        self.maybe_handle_git_error(Some(err));
+        return Ok(());
+      },
+    };
+    for branch in &pruned {
+      self.record_undo(branch);
+    }
+    let names: Vec<String> = pruned.into_iter().map(|branch| branch.name).collect();
+    self.detail_panel.invalidate(&names);
+    let result = self.refresh();
+    self.maybe_handle_git_error(result.err());
+    let message = if names.is_empty() {
+      String::from("No branches with a gone upstream")
+    } else {
+      format!("Deleted {}", names.join(", "))
+    };
+    if let Some(action_tx) = &self.action_tx {
+      action_tx.send(Action::Notify { message, level: NotificationLevel::Success })?;
+    }
     Ok(())
   }
 
+  fn pull_all_branches(&mut self) -> Result<(), Error> {
+    self.pull_results = Some(self.repo.pull_all_branches()?);
+    self.refresh()
+  }
+
   fn maybe_handle_git_error(&mut self, err: Option<Error>) {
-    if err.is_some() {
-      let error = err.unwrap();
+    if let Some(error) = err {
       error!("{}", error);
       self.error = Some(error.to_string());
     }
   }
 
+  fn show_selected_commit_diff(&mut self) -> Result<(), Error> {
+    let Some(selected) = self.get_selected_branch() else {
+      return Ok(());
+    };
+    let name = selected.branch.name.clone();
+    let diff = self.repo.show_commit_diff(&name)?;
+    self.diff_popup = Some(DiffPopup::new(format!("Last commit on {}", name), diff));
+    Ok(())
+  }
+
+  fn export_branches(&mut self, scope: ExportScope) -> color_eyre::Result<()> {
+    let names: Vec<&str> = match scope {
+      ExportScope::All => self.branches.iter().map(|b| b.branch.name.as_str()).collect(),
+      ExportScope::StagedForDeletion => {
+        self.branches.iter().filter(|b| b.staged_for_deletion).map(|b| b.branch.name.as_str()).collect()
+      },
+      ExportScope::Selected => self.get_selected_branch().map(|b| b.branch.name.as_str()).into_iter().collect(),
+    };
+    let path = get_data_dir().join("exported_branches.txt");
+    let result = std::fs::create_dir_all(get_data_dir()).and_then(|_| std::fs::write(&path, names.join("\n") + "\n"));
+    if let Err(err) = result {
+      error!("Failed to export branches: {}", err);
+      self.error = Some(format!("Failed to export branches to {}: {}", path.display(), err));
+      return Ok(());
+    }
+    if let Some(action_tx) = &self.action_tx {
+      let message = format!("Exported {} branches to {}", names.len(), path.display());
+      action_tx.send(Action::Notify { message, level: NotificationLevel::Info })?;
+    }
+    Ok(())
+  }
+
+  #[cfg(test)]
+  pub fn snapshot(&self) -> crate::components::list_snapshot::ListSnapshot {
+    let staged_count = self.branches.iter().filter(|b| b.staged_for_deletion).count();
+    crate::components::list_snapshot::ListSnapshot {
+      items: self
+        .rows()
+        .iter()
+        .map(|row| {
+          match row {
+            BranchRow::Group { name, branch_count, collapsed } => {
+              branch_item::group_label(name, *branch_count, *collapsed)
+            },
+            BranchRow::Branch(index) => String::from(self.branches[*index].branch.display_name()),
+          }
+        })
+        .collect(),
+      selected_index: self.selected_index,
+      has_staged_items: staged_count > 0,
+      filter_active: self.filter.is_active(),
+      staged_count,
+    }
+  }
+
+  fn list_title(&self) -> String {
+    let title = self.data_source.title();
+    if self.sort_order == SortOrder::default() {
+      return String::from(title);
+    }
+    format!("{} [{}]", title, self.sort_order.label())
+  }
+
   fn render_list(&mut self, f: &mut Frame<'_>, area: Rect) {
-    // TODO don't clone, figure out the index to place the pseudo branch in the list
-    let mut branches = self.branches.clone();
+    let area = if self.show_details {
+      let [list_area, details_area] =
+        Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(area);
+      let selected =
+        self.branches.get(self.selected_index).filter(|_| self.selected_group.is_none()).map(|b| &b.branch);
+      self.detail_panel.render(f, details_area, selected, &*self.repo, &self.main_branch);
+      list_area
+    } else {
+      area
+    };
+    self.navigation.set_rendered_area(area);
+    let rows = self.rows();
+    let selected_position = self.selected_row(&rows).unwrap_or(0);
+    let context = BranchRowContext {
+      columns: self.columns,
+      show_age: self.show_branch_age,
+      show_merged: self.loading == LoadingOperation::None,
+      theme: self.theme.clone(),
+    };
+    let mut table_rows: Vec<Row> = rows
+      .iter()
+      .map(|row| {
+        match row {
+          BranchRow::Group { name, branch_count, collapsed } => group_header_row(name, *branch_count, *collapsed),
+          BranchRow::Branch(index) => {
+            let branch = &self.branches[*index];
+            let item =
+              BranchItem { multi_selected: self.multi_selected.contains(&branch.branch.name), ..branch.clone() };
+            item.to_row(&context)
+          },
+        }
+      })
+      .collect();
     let input_state = self.branch_input.input_state.clone();
-    if input_state.value.is_some() && self.mode == Mode::Input {
-      let content = input_state.value.unwrap();
-      branches.push(BranchItem {
-        branch: GitBranch::new(content.clone()),
+    if let Some(content) = input_state.value.filter(|_| self.mode == Mode::Input) {
+      let new_branch = BranchItem {
         staged_for_creation: true,
-        staged_for_deletion: false,
-        is_valid_name: self.branch_input.input_state.is_valid.unwrap_or(false),
-      });
-      branches.sort_by(|a, b| a.branch.name.cmp(&b.branch.name));
-      self.list_state.select(branches.iter().position(|bi| bi.staged_for_creation))
+        ..BranchItem::new(GitBranch::new(content.clone()), input_state.is_valid.unwrap_or(false))
+      };
+      // Shown where it would sort by name among the listed branches
+      let position = rows
+        .iter()
+        .position(|row| matches!(row, BranchRow::Branch(index) if self.branches[*index].branch.name > content))
+        .unwrap_or(rows.len());
+      table_rows.insert(position, new_branch.to_row(&context));
+      self.table_state.select(Some(position));
     } else {
-      self.list_state.select(Some(self.selected_index));
+      self.table_state.select(Some(selected_position));
     }
-
-    let render_items: Vec<ListItem> = branches.iter().map(|git_branch| git_branch.render()).collect();
-    let list = List::new(render_items)
-      .block(Block::default().title("Local Branches").borders(Borders::ALL))
+    // Not part of `branches` so it can't be selected, staged or exported
+    let hidden_count = self.data_source.hidden_count();
+    if hidden_count > 0 {
+      table_rows.push(
+        Row::new([Cell::from(format!("... and {} more", hidden_count))]).style(Style::default().fg(Color::DarkGray)),
+      );
+    }
+    let mut block = Block::default()
+      .title(self.filter.title(&self.list_title()) + &self.loading.title_suffix(self.spinner_frame))
+      .borders(Borders::ALL);
+    // The borders take a row from the top and bottom
+    let visible_height = usize::from(area.height.saturating_sub(2));
+    if let Some(page) = page_indicator(selected_position, table_rows.len(), visible_height) {
+      block = block.title(Title::from(page).alignment(Alignment::Right));
+    }
+    let table = Table::new(table_rows, BranchItem::column_widths(&context))
+      .block(block)
       .style(Style::default().fg(Color::White))
       .highlight_style(Style::default().add_modifier(Modifier::BOLD))
-      .highlight_symbol("→")
-      .repeat_highlight_symbol(true);
+      .highlight_symbol("→");
 
-    f.render_stateful_widget(list, area, &mut self.list_state);
+    f.render_stateful_widget(table, area, &mut self.table_state);
+  }
+
+  fn render_pull_results(&mut self, f: &mut Frame<'_>, area: Rect) {
+    let Some(results) = &self.pull_results else {
+      return;
+    };
+    let mut lines: Vec<Line> = results.iter().map(|result| Line::from(result.summary())).collect();
+    if lines.is_empty() {
+      lines.push(Line::from("No branches with an upstream to update"));
+    }
+    let height = u16::try_from(lines.len() + 2).unwrap_or(u16::MAX);
+    let [popup] = Layout::horizontal([Constraint::Percentage(80)]).flex(Flex::Center).areas(area);
+    let [popup] = Layout::vertical([Constraint::Length(height)]).flex(Flex::Center).areas(popup);
+    let component = Paragraph::new(Text::from(lines))
+      .block(Block::bordered().title("Updated branches"))
+      .style(Style::default().fg(Color::White))
+      .wrap(Wrap { trim: true });
+    f.render_widget(Clear, popup);
+    f.render_widget(component, popup);
   }
 
   fn render_error(&mut self, f: &mut Frame<'_>, area: Rect) {
@@ -226,38 +1019,139 @@ impl BranchList {
 }
 
 impl Component for BranchList {
+  fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> color_eyre::Result<()> {
+    if self.repo_state != RepoState::Clean {
+      tx.send(Action::RepoStateChanged(self.repo_state))?;
+    }
+    self.action_tx = Some(tx);
+    Ok(())
+  }
+
   fn handle_key_events(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
     self.clear_error();
 
     if self.mode == Mode::Input {
       return Ok(Some(Action::UpdateNewBranchName(key)));
     }
-    match key {
-      KeyEvent { code: KeyCode::Down, modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
-        Ok(Some(Action::SelectNextBranch))
-      },
-      KeyEvent { code: KeyCode::Up, modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
-        Ok(Some(Action::SelectPreviousBranch))
+    if self.mode == Mode::WorktreeInput {
+      return Ok(self.worktree_input.handle_key_event(key, &*self.repo));
+    }
+    if self.mode == Mode::RenameInput {
+      return Ok(self.rename_input.handle_key_event(key, &*self.repo));
+    }
+    if self.mode == Mode::UpstreamInput {
+      return Ok(self.upstream_input.handle_key_event(key, &*self.repo));
+    }
+    if let Some(diff_popup) = self.diff_popup.as_mut() {
+      if key.code == KeyCode::Esc {
+        self.diff_popup = None;
+        return Ok(Some(Action::EndInputMode));
+      }
+      return diff_popup.handle_key_events(key);
+    }
+    if let Some(export_scope_selector) = self.export_scope_selector.as_mut() {
+      return export_scope_selector.handle_key_events(key);
+    }
+    if self.filter.is_editing() {
+      let query = String::from(self.filter.query());
+      let action = self.filter.handle_key_event(key);
+      if action.is_none() && self.filter.query() != query {
+        return Ok(Some(Action::FilterChanged(String::from(self.filter.query()))));
+      }
+      self.apply_filter();
+      return Ok(action);
+    }
+    if key.code == KeyCode::Esc && self.filter.is_active() {
+      self.filter.clear();
+      return Ok(Some(Action::FilterChanged(String::new())));
+    }
+    if let Some(action) = self.navigation.handle_key_event(&key) {
+      return Ok(Some(action));
+    }
+    if key.code == KeyCode::Enter && self.selected_group.is_some() {
+      return Ok(Some(Action::ToggleBranchGroup));
+    }
+    let Some(action_name) = self.key_bindings.action_for(&key) else {
+      return Ok(None);
+    };
+    match action_name {
+      "SelectNextBranch" => Ok(Some(Action::SelectNextBranch)),
+      "SelectPreviousBranch" => Ok(Some(Action::SelectPreviousBranch)),
+      "InitNewBranch" => Ok(Some(self.confirm_if_in_progress(Action::InitNewBranch, "creating a branch"))),
+      "CheckoutSelectedBranch" => Ok(Some(self.confirm_if_in_progress(Action::CheckoutSelectedBranch, "checking out"))),
+      "UnstageBranchForDeletion" => Ok(Some(Action::UnstageBranchForDeletion)),
+      "DeleteStagedBranches" => {
+        let staged: Vec<&str> =
+          self.branches.iter().filter(|b| b.staged_for_deletion).map(|b| b.branch.name.as_str()).collect();
+        Ok(self.confirm_delete(Action::DeleteStagedBranches, &staged))
       },
-      KeyEvent { code: KeyCode::Char('c' | 'C'), modifiers: KeyModifiers::SHIFT, kind: _, state: _ } => {
-        Ok(Some(Action::InitNewBranch))
+      "Refresh" => Ok(Some(Action::Refresh)),
+      "InitExportBranches" => Ok(Some(Action::InitExportBranches)),
+      "PullAllBranches" => Ok(Some(Action::PullAllBranches)),
+      "PushBranch" => Ok(Some(Action::PushBranch(false))),
+      "ForcePushBranch" => Ok(Some(Action::PushBranch(true))),
+      "PullCurrentBranch" => Ok(Some(Action::PullCurrentBranch)),
+      "PruneGoneBranches" => {
+        let message = String::from("Prune remotes and delete every local branch whose upstream is gone?");
+        Ok(Some(Action::RequestConfirmation(message, Box::new(Action::PruneGoneBranches))))
       },
-      KeyEvent { code: KeyCode::Char('c' | 'C'), modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
-        Ok(Some(Action::CheckoutSelectedBranch))
+      "FetchRemote" => Ok(Some(Action::FetchRemote(None))),
+      "ToggleBranchDetails" => Ok(Some(Action::ToggleBranchDetails)),
+      "ToggleBranchGrouping" => Ok(Some(Action::ToggleBranchGrouping)),
+      "ShowSelectedCommitDiff" => Ok(Some(Action::ShowSelectedCommitDiff)),
+      "ShowCommitLog" => Ok(self.get_selected_branch().map(|b| Action::ShowCommitLog(b.branch.name.clone()))),
+      "ShowBranchDiff" => {
+        // Diffed against HEAD, so there is nothing to show for HEAD itself or without one
+        if !self.branches.iter().any(|b| b.branch.is_head) {
+          return Ok(None);
+        }
+        Ok(
+          self
+            .get_selected_branch()
+            .filter(|b| !b.branch.is_head)
+            .map(|b| Action::ShowBranchDiff(b.branch.name.clone())),
+        )
       },
-      KeyEvent { code: KeyCode::Char('d' | 'D'), modifiers: KeyModifiers::SHIFT, kind: _, state: _ } => {
-        Ok(Some(Action::UnstageBranchForDeletion))
+      "DeleteRemoteBranch" => Ok(self.request_delete_remote_branch()),
+      "InitRenameBranch" => Ok(Some(Action::InitRenameBranch)),
+      "InitSetUpstream" => {
+        if self.get_selected_branch().is_some_and(|b| b.branch.upstream.is_some()) {
+          return Ok(Some(Action::UnsetUpstream));
+        }
+        Ok(Some(Action::InitSetUpstream))
       },
-      KeyEvent { code: KeyCode::Char('d' | 'D'), modifiers: KeyModifiers::CONTROL, kind: _, state: _ } => {
-        Ok(Some(Action::DeleteStagedBranches))
+      "CopyBranchName" => Ok(self.get_selected_branch().map(|b| Action::CopyToClipboard(b.branch.name.clone()))),
+      "ActivateFilter" => Ok(Some(Action::ActivateFilter)),
+      "InitNewWorktree" => Ok(Some(Action::InitNewWorktree)),
+      "CycleSortOrder" => Ok(Some(Action::CycleSortOrder)),
+      "MergeSelectedBranch" => {
+        if self.get_selected_branch().is_none_or(|b| b.branch.is_head) {
+          return Ok(None);
+        }
+        Ok(Some(self.confirm_if_in_progress(Action::MergeSelectedBranch, "merging")))
       },
-      KeyEvent { code: KeyCode::Char('d' | 'D'), modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
-        if self.get_selected_branch().is_none() {
+      "RebaseOntoSelected" => {
+        if self.get_selected_branch().is_none_or(|b| b.branch.is_head) {
           return Ok(None);
         }
-        let selected = self.get_selected_branch().unwrap();
+        Ok(Some(self.confirm_if_in_progress(Action::RebaseOntoSelected, "rebasing")))
+      },
+      "ToggleMultiSelect" => Ok(Some(Action::ToggleMultiSelect)),
+      "SelectAll" => {
+        if self.multi_selected.len() == self.multi_selectable().len() {
+          return Ok(Some(Action::ClearMultiSelect));
+        }
+        Ok(Some(Action::SelectAll))
+      },
+      "StageBranchForDeletion" => {
+        if !self.multi_selected.is_empty() {
+          return Ok(Some(Action::StageBranchForDeletion));
+        }
+        let Some(selected) = self.get_selected_branch() else {
+          return Ok(None);
+        };
         if selected.staged_for_deletion {
-          return Ok(Some(Action::DeleteBranch));
+          return Ok(self.confirm_delete(Action::DeleteBranch, &[&selected.branch.name]));
         }
         Ok(Some(Action::StageBranchForDeletion))
       },
@@ -265,45 +1159,150 @@ impl Component for BranchList {
     }
   }
 
+  fn handle_mouse_events(&mut self, mouse: MouseEvent) -> color_eyre::Result<Option<Action>> {
+    match self.navigation.handle_mouse_event(&mouse, self.table_state.offset()) {
+      Some(ListClick::Select(position)) => Ok(Some(Action::SelectPosition(position))),
+      Some(ListClick::Activate) if self.selected_group.is_some() => Ok(Some(Action::ToggleBranchGroup)),
+      Some(ListClick::Activate) => {
+        Ok(Some(self.confirm_if_in_progress(Action::CheckoutSelectedBranch, "checking out")))
+      },
+      None => Ok(None),
+    }
+  }
+
   fn update(&mut self, action: Action) -> color_eyre::Result<Option<Action>> {
-    match action {
+    let next_action = match action {
       Action::SelectPreviousBranch => {
         self.select_previous();
+        self.announce_selection();
         Ok(None)
       },
       Action::SelectNextBranch => {
         self.select_next();
+        self.announce_selection();
+        Ok(None)
+      },
+      Action::SelectFirst | Action::SelectLast | Action::SelectPosition(_) | Action::PageDown | Action::PageUp => {
+        self.apply_motion(&action);
+        self.announce_selection();
+        Ok(None)
+      },
+      Action::SelectItemWithName(name) => {
+        self.jump_to_item_by_name(&name);
         Ok(None)
       },
       Action::InitNewBranch => {
         self.mode = Mode::Input;
+        let existing_branches = self.branches.iter().map(|branch_item| &branch_item.branch).collect();
+        self.branch_input.handler_mut().set_existing_branches(existing_branches);
         self.branch_input.init_style();
         Ok(Some(Action::StartInputMode))
       },
-      Action::EndInputMod => {
+      Action::EndInputMode => {
         self.mode = Mode::Selection;
+        self.export_scope_selector = None;
         Ok(None)
       },
-      Action::UpdateNewBranchName(key_event) => {
-        Ok(self.branch_input.handle_key_event(
-          key_event,
-          &*self.repo,
-          self.branches.iter().map(|branch_item| &branch_item.branch).collect(),
-        ))
+      Action::SearchQueryChanged(query) => {
+        self.filter.search(&query);
+        self.apply_filter();
+        // Searching jumps to the best match
+        if let Some(&best) = self.visible_indices().first() {
+          self.select_branch(best);
+        }
+        Ok(None)
       },
+      Action::EndSearch => {
+        // The selection indexes all branches so it stays on the chosen one
+        self.filter.clear();
+        Ok(None)
+      },
+      Action::ActivateFilter => {
+        self.filter.start_editing();
+        Ok(Some(Action::StartInputMode))
+      },
+      Action::FilterChanged(_) => {
+        self.apply_filter();
+        Ok(None)
+      },
+      Action::InitRenameBranch => Ok(self.init_rename_input()),
+      Action::InitSetUpstream => {
+        let result = self.init_upstream_input();
+        let action = result.as_ref().ok().cloned().flatten();
+        self.maybe_handle_git_error(result.err());
+        Ok(action)
+      },
+      Action::SetUpstream(branch_name, upstream) => {
+        self.mode = Mode::Selection;
+        let result = self.set_upstream(&branch_name, &upstream);
+        self.maybe_handle_git_error(result.err());
+        Ok(Some(Action::EndInputMode))
+      },
+      Action::UnsetUpstream => {
+        let result = self.unset_upstream();
+        self.maybe_handle_git_error(result.err());
+        Ok(None)
+      },
+      Action::RenameBranch(old_name, new_name) => {
+        self.mode = Mode::Selection;
+        let result = self.rename_branch(&old_name, &new_name);
+        self.maybe_handle_git_error(result.err());
+        Ok(Some(Action::EndInputMode))
+      },
+      Action::InitNewWorktree => {
+        let result = self.init_worktree_input();
+        let action = result.as_ref().ok().cloned().flatten();
+        self.maybe_handle_git_error(result.err());
+        Ok(action)
+      },
+      Action::CreateWorktree(path) => {
+        self.mode = Mode::Selection;
+        let result = self.create_worktree(&path);
+        if result.is_ok() {
+          if let Some(action_tx) = &self.action_tx {
+            let message = format!("Worktree created at {}", path);
+            action_tx.send(Action::Notify { message, level: NotificationLevel::Success })?;
+          }
+        }
+        self.maybe_handle_git_error(result.err());
+        Ok(Some(Action::EndInputMode))
+      },
+      Action::UpdateNewBranchName(key_event) => Ok(self.branch_input.handle_key_event(key_event, &*self.repo)),
       Action::CheckoutSelectedBranch => {
-        let result = self.checkout_selected();
+        // With several branches picked the last one picked is checked out
+        if let Some(last) = self.multi_selected.pop() {
+          self.select_by_predicate(|b| b.branch.name == last);
+          self.multi_selected.clear();
+        }
+        let result = self.checkout_selected().and_then(|_| self.refresh());
         self.maybe_handle_git_error(result.err());
+        self.select_head();
         Ok(None)
       },
       Action::CreateBranch(name) => {
         self.mode = Mode::Selection;
         let result = self.create_branch(name);
         self.maybe_handle_git_error(result.err());
-        Ok(Some(Action::EndInputMod))
+        Ok(Some(Action::EndInputMode))
       },
       Action::StageBranchForDeletion => {
-        self.stage_selected_for_deletion(true);
+        if self.multi_selected.is_empty() {
+          self.stage_selected_for_deletion(true);
+        } else {
+          self.stage_multi_selected_for_deletion();
+        }
+        Ok(None)
+      },
+      Action::ToggleMultiSelect => {
+        self.toggle_multi_select();
+        Ok(None)
+      },
+      Action::SelectAll => {
+        self.multi_selected = self.multi_selectable();
+        Ok(None)
+      },
+      Action::ClearMultiSelect => {
+        self.multi_selected.clear();
         Ok(None)
       },
       Action::UnstageBranchForDeletion => {
@@ -315,46 +1314,432 @@ impl Component for BranchList {
         self.maybe_handle_git_error(result.err());
         Ok(None)
       },
+      Action::DeleteRemoteBranch => {
+        let result = self.delete_remote_branch();
+        self.maybe_handle_git_error(result.err());
+        Ok(None)
+      },
       Action::DeleteStagedBranches => {
-        let result = self.delete_staged_branches();
+        let (deleted, failures) = self.delete_staged_branches();
+        self.maybe_handle_git_error(failed_deletions(("branch", "branches"), &failures));
+        Ok(Some(Action::ItemsLoaded { deleted, failed: failures.len() }))
+      },
+      Action::Tick if self.loading != LoadingOperation::None => {
+        self.spinner_frame = LoadingOperation::next_spinner_frame(self.spinner_frame);
+        Ok(None)
+      },
+      Action::Refresh => {
+        if !self.refreshed_recently() {
+          let result = self.refresh();
+          self.maybe_handle_git_error(result.err());
+        }
+        Ok(None)
+      },
+      Action::FetchRemote(remote) => {
+        self.fetch(remote);
+        Ok(None)
+      },
+      Action::CycleSortOrder => {
+        self.sort_order = self.sort_order.next();
+        let result = self.refresh();
         self.maybe_handle_git_error(result.err());
         Ok(None)
       },
+      Action::MergeSelectedBranch => {
+        self.merge_selected();
+        Ok(None)
+      },
+      Action::RebaseOntoSelected => {
+        self.rebase_onto_selected();
+        Ok(None)
+      },
+      Action::PushBranch(force) => {
+        self.push_selected(force);
+        Ok(None)
+      },
+      Action::PullCurrentBranch => {
+        self.pull_current();
+        Ok(None)
+      },
+      Action::FetchCompleted(result)
+      | Action::PushCompleted(result)
+      | Action::PullCompleted(result)
+      | Action::MergeCompleted(result)
+      | Action::RebaseCompleted(result) => {
+        self.loading = LoadingOperation::None;
+        // Failures can still change the repository, e.g. a conflicted merge leaves it merging
+        let refreshed = self.refresh();
+        self.maybe_handle_git_error(refreshed.err());
+        if let Err(err) = result {
+          error!("{}", err);
+          self.error = Some(err);
+        }
+        Ok(None)
+      },
+      Action::PullAllBranches => {
+        let result = self.pull_all_branches();
+        self.maybe_handle_git_error(result.err());
+        Ok(None)
+      },
+      Action::PruneGoneBranches => {
+        self.prune_gone_branches()?;
+        Ok(None)
+      },
+      Action::InitExportBranches => {
+        if self.branches.iter().any(|b| b.staged_for_deletion) {
+          self.export_scope_selector = Some(ExportScopeSelector::default());
+          return Ok(Some(Action::StartInputMode));
+        }
+        self.export_branches(ExportScope::All)?;
+        Ok(None)
+      },
+      Action::ExportBranches(scope) => {
+        self.export_branches(scope)?;
+        Ok(self.export_scope_selector.take().map(|_| Action::EndInputMode))
+      },
+      Action::ToggleBranchDetails => {
+        self.show_details = !self.show_details;
+        Ok(None)
+      },
+      Action::ToggleBranchGrouping => {
+        self.grouped = !self.grouped;
+        self.select_branch(self.selected_index);
+        Ok(None)
+      },
+      Action::ToggleBranchGroup => {
+        self.toggle_selected_group();
+        Ok(None)
+      },
+      Action::ShowSelectedCommitDiff => {
+        let result = self.show_selected_commit_diff();
+        let opened = result.is_ok() && self.diff_popup.is_some();
+        self.maybe_handle_git_error(result.err());
+        Ok(opened.then_some(Action::StartInputMode))
+      },
       _ => Ok(None),
+    };
+    // Branches may have been added, removed or renamed
+    if self.filter.is_active() {
+      self.apply_filter();
     }
+    next_action
+  }
+
+  fn staged_items(&self) -> Vec<String> {
+    self.branches.iter().filter(|b| b.staged_for_deletion).map(|b| b.branch.name.clone()).collect()
+  }
+
+  fn title(&self) -> &str {
+    "Branches"
+  }
+
+  fn item_counts(&self) -> (usize, usize) {
+    (self.visible_indices().len(), self.branches.len())
+  }
+
+  fn handles_escape(&self) -> bool {
+    self.filter.is_active()
+  }
+
+  fn instructions(&self) -> Vec<&'static str> {
+    self.instruction_footer.instructions(
+      &self.branches,
+      self.get_selected_branch(),
+      self.multi_selected.len(),
+      self.filter.is_editing(),
+      self.filter.is_active(),
+      self.selected_group.is_some(),
+    )
   }
 
   fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> color_eyre::Result<()> {
-    if self.mode == Mode::Input {
-      let layout =
-        Layout::new(Direction::Vertical, [Constraint::Fill(1), Constraint::Length(3), Constraint::Length(1)])
-          .margin(1)
-          .split(area);
+    let instructions = self.instructions();
+    // The layout margin takes a column from each side
+    let footer_height = ui::instruction_footer::preferred_height(&instructions, area.width.saturating_sub(2));
+
+    if self.mode != Mode::Selection {
+      let layout = Layout::new(Direction::Vertical, [
+        Constraint::Min(1),
+        Constraint::Length(3),
+        Constraint::Length(footer_height),
+      ])
+      .margin(1)
+      .split(area);
       self.render_list(f, layout[0]);
-      self.branch_input.render(f, layout[1]);
-      self.instruction_footer.render(f, layout[2], &self.branches, self.get_selected_branch());
+      match self.mode {
+        Mode::Input => self.branch_input.render(f, layout[1]),
+        Mode::RenameInput => self.rename_input.render(f, layout[1]),
+        Mode::UpstreamInput => self.upstream_input.render(f, layout[1]),
+        Mode::WorktreeInput => self.worktree_input.render(f, layout[1]),
+        Mode::Selection => {},
+      }
+      ui::instruction_footer::render(f, layout[2], &instructions);
       return Ok(());
     }
 
     if self.error.is_some() {
       let err_size = self.error.clone().unwrap().lines().count() + 2;
       let layout = Layout::new(Direction::Vertical, [
-        Constraint::Fill(1),
+        Constraint::Min(1),
         Constraint::Length(u16::try_from(err_size)?),
-        Constraint::Length(1),
+        Constraint::Length(footer_height),
       ])
       .margin(1)
       .split(area);
       self.render_list(f, layout[0]);
       self.render_error(f, layout[1]);
-      self.instruction_footer.render(f, layout[2], &self.branches, self.get_selected_branch());
+      ui::instruction_footer::render(f, layout[2], &instructions);
       return Ok(());
     }
 
-    let layout = Layout::new(Direction::Vertical, [Constraint::Fill(1), Constraint::Length(1)]).margin(1).split(area);
+    let layout =
+      Layout::new(Direction::Vertical, [Constraint::Min(1), Constraint::Length(footer_height)]).margin(1).split(area);
     self.render_list(f, layout[0]);
-    self.instruction_footer.render(f, layout[1], &self.branches, self.get_selected_branch());
+    ui::instruction_footer::render(f, layout[1], &instructions);
+    self.render_pull_results(f, layout[0]);
+    if let Some(diff_popup) = self.diff_popup.as_mut() {
+      diff_popup.draw(f, area)?;
+    }
+    if let Some(export_scope_selector) = self.export_scope_selector.as_mut() {
+      export_scope_selector.draw(f, area)?;
+    }
 
     Ok(())
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{components::list_snapshot::ListSnapshot, git::mock_git_repo::MockGitRepo};
+
+  fn branch_list() -> BranchList {
+    let branches = ["main", "feature/b", "feature/a"].map(|name| GitBranch::new(String::from(name))).to_vec();
+    BranchList::new(Arc::new(MockGitRepo { branches, ..Default::default() }), &Config::default())
+  }
+
+  #[test]
+  fn branches_are_sorted_by_name() {
+    let list = branch_list();
+    assert_eq!(list.snapshot().items, vec!["feature/a", "feature/b", "main"]);
+  }
+
+  #[test]
+  fn collapsed_groups_are_skipped_by_navigation() {
+    let mut list = branch_list();
+    list.update(Action::ToggleBranchGrouping).unwrap();
+    assert_eq!(list.snapshot().items, vec!["▼ feature (2)", "feature/a", "feature/b", "▼ main (1)", "main"]);
+    list.update(Action::SelectFirst).unwrap();
+    list.update(Action::ToggleBranchGroup).unwrap();
+    assert_eq!(list.snapshot().items, vec!["▶ feature (2)", "▼ main (1)", "main"]);
+    list.update(Action::SelectNextBranch).unwrap();
+    list.update(Action::SelectNextBranch).unwrap();
+    assert_eq!(list.snapshot().selected_index, 2);
+  }
+
+  #[test]
+  fn staging_a_branch_is_reflected_in_the_snapshot() {
+    let mut list = branch_list();
+    list.update(Action::SelectNextBranch).unwrap();
+    list.update(Action::StageBranchForDeletion).unwrap();
+    assert_eq!(list.snapshot(), ListSnapshot {
+      items: vec![String::from("feature/a"), String::from("feature/b"), String::from("main")],
+      selected_index: 1,
+      has_staged_items: true,
+      filter_active: false,
+      staged_count: 1,
+    });
+  }
+
+  fn tracking_head(pull_conflicts: Vec<String>) -> BranchList {
+    let upstream = Some(GitRemoteBranch::new(String::from("origin/main")));
+    let branches = vec![GitBranch { is_head: true, upstream, ..GitBranch::new(String::from("main")) }];
+    BranchList::new(Arc::new(MockGitRepo { branches, pull_conflicts, ..Default::default() }), &Config::default())
+  }
+
+  async fn pull(list: &mut BranchList) -> Action {
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    list.register_action_handler(tx).unwrap();
+    list.update(Action::PullCurrentBranch).unwrap();
+    assert!(matches!(list.loading, LoadingOperation::Processing("Pulling", _)));
+    let completed = rx.recv().await.unwrap();
+    list.update(completed.clone()).unwrap();
+    completed
+  }
+
+  #[tokio::test]
+  async fn pulling_the_head_branch_reloads_it() {
+    let mut list = tracking_head(Vec::new());
+    assert_eq!(pull(&mut list).await, Action::PullCompleted(Ok(())));
+    assert_eq!(list.loading, LoadingOperation::None);
+    assert_eq!(list.error, None);
+    assert_eq!(list.snapshot().items, vec!["main"]);
+  }
+
+  #[tokio::test]
+  async fn a_conflicting_pull_lists_the_conflicts() {
+    let mut list = tracking_head(vec![String::from("src/main.rs"), String::from("README.md")]);
+    pull(&mut list).await;
+    assert_eq!(list.loading, LoadingOperation::None);
+    assert_eq!(list.error.as_deref(), Some("Git operation failed: Pulling conflicted in: src/main.rs, README.md"));
+  }
+
+  fn mock_repo(names: &[&str]) -> Arc<MockGitRepo> {
+    let branches = names.iter().map(|&name| GitBranch::new(String::from(name))).collect();
+    Arc::new(MockGitRepo { branches, ..Default::default() })
+  }
+
+  fn refreshed_list(preserve_selection_on_refresh: bool) -> BranchList {
+    let config = Config { preserve_selection_on_refresh, ..Config::default() };
+    BranchList::new(mock_repo(&["feature/a", "feature/b", "main"]), &config)
+  }
+
+  fn selected_name(list: &BranchList) -> &str {
+    list.get_selected_branch().map_or("", |b| b.branch.name.as_str())
+  }
+
+  #[test]
+  fn deleting_the_selected_branch_selects_the_next_on_refresh() {
+    let mut list = refreshed_list(true);
+    list.jump_to_item_by_name("feature/b");
+    list.repo = mock_repo(&["feature/a", "main"]);
+    list.update(Action::Refresh).unwrap();
+    assert_eq!(selected_name(&list), "main");
+  }
+
+  #[test]
+  fn a_renamed_branch_stays_selected_across_refresh() {
+    let mut list = refreshed_list(true);
+    list.jump_to_item_by_name("feature/a");
+    list.update(Action::RenameBranch(String::from("feature/a"), String::from("feature/z"))).unwrap();
+    assert_eq!(selected_name(&list), "feature/z");
+    list.repo = mock_repo(&["feature/b", "feature/z", "main"]);
+    list.update(Action::Refresh).unwrap();
+    assert_eq!(selected_name(&list), "feature/z");
+  }
+
+  #[test]
+  fn refresh_resets_the_selection_when_not_preserving_it() {
+    let mut list = refreshed_list(false);
+    list.jump_to_item_by_name("main");
+    list.update(Action::Refresh).unwrap();
+    assert_eq!(list.snapshot().selected_index, 0);
+  }
+
+  #[test]
+  fn a_partly_failed_delete_summarises_the_failures() {
+    let mut list = BranchList::new(mock_repo(&["ok-branch", "fail-branch"]), &Config::default());
+    for item in list.branches.iter_mut() {
+      item.stage_for_deletion(true);
+    }
+    let action = list.update(Action::DeleteStagedBranches).unwrap();
+    assert_eq!(action, Some(Action::ItemsLoaded { deleted: 1, failed: 1 }));
+    assert_eq!(list.snapshot().items, vec!["fail-branch"]);
+    assert_eq!(
+      list.error.as_deref(),
+      Some("Git operation failed: Failed to delete 1 branch\nfail-branch: Failed to delete fail-branch")
+    );
+  }
+
+  #[test]
+  fn the_list_opens_on_the_head_branch() {
+    let branches = ["feature/a", "feature/b", "main"]
+      .map(|name| GitBranch { is_head: name == "main", ..GitBranch::new(String::from(name)) })
+      .to_vec();
+    let list = BranchList::new(Arc::new(MockGitRepo { branches, ..Default::default() }), &Config::default());
+    assert_eq!(list.snapshot().selected_index, 2);
+  }
+
+  #[test]
+  fn the_list_opens_on_the_first_branch_without_a_head() {
+    assert_eq!(branch_list().snapshot().selected_index, 0);
+  }
+
+  #[test]
+  fn the_selected_branch_is_followed_when_refresh_moves_it() {
+    let config = Config { preserve_selection_on_refresh: true, ..Config::default() };
+    let mut list = BranchList::new(mock_repo(&["feature/a", "feature/c", "main"]), &config);
+    list.jump_to_item_by_name("main");
+    assert_eq!(list.snapshot().selected_index, 2);
+    list.repo = mock_repo(&["feature/a", "feature/b", "feature/c", "fix/d", "main"]);
+    list.update(Action::Refresh).unwrap();
+    assert_eq!(list.snapshot().selected_index, 4);
+    assert_eq!(selected_name(&list), "main");
+  }
+
+  #[test]
+  fn select_by_predicate_selects_the_first_match() {
+    let branches = ["feature/a", "feature/b", "fix/c", "main"]
+      .map(|name| GitBranch { is_head: name == "fix/c", ..GitBranch::new(String::from(name)) })
+      .to_vec();
+    let mut list = BranchList::new(Arc::new(MockGitRepo { branches, ..Default::default() }), &Config::default());
+    list.branches[3].stage_for_deletion(true);
+
+    assert!(list.select_by_predicate(|b| b.branch.is_head));
+    assert_eq!(selected_name(&list), "fix/c");
+    assert!(list.select_by_predicate(|b| b.branch.name.starts_with("feature/")));
+    assert_eq!(selected_name(&list), "feature/a");
+    assert!(list.select_by_predicate(|b| b.staged_for_deletion));
+    assert_eq!(selected_name(&list), "main");
+    assert!(!list.select_by_predicate(|b| b.branch.name.starts_with("release/")));
+    assert_eq!(selected_name(&list), "main");
+  }
+
+  #[test]
+  fn item_counts_follow_the_filter() {
+    let mut list = branch_list();
+    assert_eq!(list.item_counts(), (3, 3));
+    list.update(Action::SearchQueryChanged(String::from("feature"))).unwrap();
+    assert_eq!(list.item_counts(), (2, 3));
+    list.update(Action::EndSearch).unwrap();
+    assert_eq!(list.item_counts(), (3, 3));
+  }
+
+  #[test]
+  fn picked_branches_are_kept_when_a_branch_is_created() {
+    let mut list = branch_list();
+    list.jump_to_item_by_name("feature/b");
+    list.update(Action::ToggleMultiSelect).unwrap();
+    // Sorts before the picked branch, shifting it down the list
+    list.update(Action::CreateBranch(String::from("feature/aa"))).unwrap();
+    let action = list.handle_key_events(KeyEvent::from(KeyCode::Char('d'))).unwrap();
+    assert_eq!(action, Some(Action::StageBranchForDeletion));
+    list.update(Action::StageBranchForDeletion).unwrap();
+    let staged: Vec<&str> =
+      list.branches.iter().filter(|b| b.staged_for_deletion).map(|b| b.branch.name.as_str()).collect();
+    assert_eq!(staged, ["feature/b"]);
+  }
+
+  #[test]
+  fn esc_clears_a_kept_filter() {
+    let mut list = branch_list();
+    assert!(!list.handles_escape());
+    list.update(Action::ActivateFilter).unwrap();
+    for key in [KeyCode::Char('m'), KeyCode::Enter] {
+      if let Some(action) = list.handle_key_events(KeyEvent::from(key)).unwrap() {
+        list.update(action).unwrap();
+      }
+    }
+    assert_eq!(list.snapshot().items, vec!["main"]);
+    assert!(list.handles_escape());
+    let action = list.handle_key_events(KeyEvent::from(KeyCode::Esc)).unwrap();
+    assert_eq!(action, Some(Action::FilterChanged(String::new())));
+    list.update(action.unwrap()).unwrap();
+    assert_eq!(list.snapshot().items, vec!["feature/a", "feature/b", "main"]);
+    assert!(!list.handles_escape());
+  }
+
+  #[test]
+  fn renaming_keeps_the_chosen_sort_order() {
+    let branches = [("feature/a", 300), ("feature/b", 200), ("main", 100)]
+      .map(|(name, last_commit_at)| {
+        GitBranch { last_commit_at: Some(last_commit_at), ..GitBranch::new(String::from(name)) }
+      })
+      .to_vec();
+    let mut list = BranchList::new(Arc::new(MockGitRepo { branches, ..Default::default() }), &Config::default());
+    list.update(Action::CycleSortOrder).unwrap();
+    assert_eq!(list.sort_order, SortOrder::LastCommitDate);
+    list.update(Action::RenameBranch(String::from("feature/a"), String::from("zzz"))).unwrap();
+    assert_eq!(list.snapshot().items, vec!["zzz", "feature/b", "main"]);
+  }
+}
@@ -0,0 +1,122 @@
+use std::collections::{HashMap, HashSet};
+
+use tracing::warn;
+
+use crate::{
+  components::traits::list_data_source::ListDataSource,
+  error::Error,
+  git::git_repo::{GitBranch, GitRepo},
+};
+
+/// The group for branches without a prefix.
+const UNGROUPED: &str = "main";
+
+/// Branches sharing the prefix before the first `/` in their names, e.g. `feature` for `feature/login`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchGroup {
+  pub name: String,
+  /// Indices into the branch list, in list order.
+  pub indices: Vec<usize>,
+}
+
+/// Loads local branches sorted by name and tracks which branches moved since the previous load.
+#[derive(Debug, Default)]
+pub struct BranchDataSource {
+  max_branches: Option<usize>,
+  // How many branches were left out of the last load by `max_branches`
+  hidden_count: usize,
+  // The sha each branch pointed at on the previous load, keyed by branch name
+  known_shas: HashMap<String, String>,
+  stale: Vec<String>,
+  // Branches whose tip is reachable from HEAD, not counting HEAD itself
+  merged: HashSet<String>,
+}
+
+impl BranchDataSource {
+  pub fn new(max_branches: Option<usize>) -> Self {
+    BranchDataSource { max_branches, ..Default::default() }
+  }
+
+  pub fn hidden_count(&self) -> usize {
+    self.hidden_count
+  }
+
+  pub fn is_merged(&self, name: &str) -> bool {
+    self.merged.contains(name)
+  }
+
+  /// The group `branch_name` falls under, branches without a prefix fall under `main`.
+  pub fn group_name(branch_name: &str) -> &str {
+    match branch_name.split_once('/') {
+      // A detached HEAD is listed like `(HEAD detached at origin/main)`
+      Some((prefix, _)) if !prefix.is_empty() && !branch_name.starts_with('(') => prefix,
+      _ => UNGROUPED,
+    }
+  }
+
+  /// Groups `branches` by prefix, ordered by where each group's first branch is in the list.
+  pub fn groups<'a>(branches: impl Iterator<Item = &'a GitBranch>) -> Vec<BranchGroup> {
+    let mut groups: Vec<BranchGroup> = Vec::new();
+    for (index, branch) in branches.enumerate() {
+      let name = Self::group_name(&branch.name);
+      match groups.iter_mut().find(|group| group.name == name) {
+        Some(group) => group.indices.push(index),
+        None => groups.push(BranchGroup { name: String::from(name), indices: vec![index] }),
+      }
+    }
+    groups
+  }
+
+  /// Names of branches that moved or disappeared since the previous load, anything cached for them is out of date.
+  pub fn take_stale(&mut self) -> Vec<String> {
+    std::mem::take(&mut self.stale)
+  }
+}
+
+impl ListDataSource<GitBranch> for BranchDataSource {
+  fn title(&self) -> &str {
+    "Local Branches"
+  }
+
+  fn fetch_items(&mut self, repo: &dyn GitRepo) -> Result<Vec<GitBranch>, Error> {
+    let mut branches = repo.local_branches()?;
+    // HEAD is sorted with everything else rather than pinned to the top
+    branches.sort_by(|a, b| a.name.cmp(&b.name));
+    self.merged.clear();
+    // A detached HEAD has no branch to compare against
+    if let Some(head) = branches.iter().find(|branch| branch.is_head) {
+      self.merged = repo
+        .branches_merged_into(head)?
+        .into_iter()
+        .map(|branch| branch.name)
+        .filter(|name| *name != head.name)
+        .collect();
+    }
+    self.hidden_count = 0;
+    if let Some(max_branches) = self.max_branches.filter(|max| branches.len() > *max) {
+      let total = branches.len();
+      // Make room for HEAD if it would be cut off
+      let head_cut_off = branches.iter().position(|branch| branch.is_head).is_some_and(|index| index >= max_branches);
+      let keep = if head_cut_off { max_branches.saturating_sub(1) } else { max_branches };
+      let mut index = 0;
+      branches.retain(|branch| {
+        index += 1;
+        index <= keep || branch.is_head
+      });
+      self.hidden_count = total - branches.len();
+      warn!("Showing {} of {} branches, max_branches_displayed is {}", branches.len(), total, max_branches);
+    }
+    Ok(branches)
+  }
+
+  fn after_fetch(&mut self, items: &mut Vec<GitBranch>) {
+    let mut previous = std::mem::take(&mut self.known_shas);
+    for branch in items.iter() {
+      if previous.remove(&branch.name).is_some_and(|sha| sha != branch.sha) {
+        self.stale.push(branch.name.clone());
+      }
+      self.known_shas.insert(branch.name.clone(), branch.sha.clone());
+    }
+    self.stale.extend(previous.into_keys());
+  }
+}
@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+
+use ratatui::{
+  layout::Rect,
+  style::{Color, Modifier, Style},
+  text::{Line, Span, Text},
+  widgets::{Block, Borders, Paragraph, Wrap},
+};
+
+use crate::{
+  git::git_repo::{BranchDetails, GitBranch, GitRepo},
+  tui::Frame,
+  utils::time::format_relative_time,
+};
+
+/// Shows the latest commit and main branch comparison for the selected branch.
+#[derive(Debug, Default)]
+pub struct BranchDetailPanel {
+  // Details or the error loading them, keyed by branch name
+  details: HashMap<String, Result<BranchDetails, String>>,
+}
+
+impl BranchDetailPanel {
+  pub fn clear(&mut self) {
+    self.details.clear();
+  }
+
+  /// Drop the loaded details for the named branches so they are reloaded when next shown.
+  pub fn invalidate(&mut self, branch_names: &[String]) {
+    for name in branch_names {
+      self.details.remove(name);
+    }
+  }
+
+  pub fn render(
+    &mut self,
+    f: &mut Frame<'_>,
+    area: Rect,
+    branch: Option<&GitBranch>,
+    repo: &dyn GitRepo,
+    main_branch: &str,
+  ) {
+    let block = Block::default().title("Details").borders(Borders::ALL);
+    let Some(branch) = branch else {
+      f.render_widget(block, area);
+      return;
+    };
+    let details = self
+      .details
+      .entry(branch.name.clone())
+      .or_insert_with(|| repo.get_branch_details(branch, main_branch).map_err(|err| err.to_string()));
+    let text = match details {
+      Ok(details) => details_text(details, main_branch),
+      Err(err) => Text::styled(err.clone(), Style::default().fg(Color::Red)),
+    };
+    f.render_widget(Paragraph::new(text).block(block).wrap(Wrap { trim: true }), area);
+  }
+}
+
+fn details_text(details: &BranchDetails, main_branch: &str) -> Text<'static> {
+  let label = |label: &str| Span::styled(format!("{:<12}", label), Style::default().add_modifier(Modifier::DIM));
+  let short_sha = |sha: &str| String::from(sha.get(..7).unwrap_or(sha));
+  let commit = &details.last_commit;
+  Text::from(vec![
+    Line::from(vec![label("Commit"), Span::raw(short_sha(&commit.sha))]),
+    Line::from(vec![label("Author"), Span::raw(commit.author.clone())]),
+    Line::from(vec![label("Committed"), Span::raw(format_relative_time(commit.committed_at))]),
+    Line::from(vec![label("Subject"), Span::raw(commit.subject.clone())]),
+    Line::default(),
+    Line::from(vec![
+      label(&format!("vs {}", main_branch)),
+      Span::raw(format!("↑{} ↓{}", details.ahead, details.behind)),
+    ]),
+    Line::from(vec![label("Merge base"), Span::raw(short_sha(&details.merge_base_sha))]),
+    Line::from(vec![label("Merged"), Span::raw(if details.is_merged { "yes" } else { "no" })]),
+  ])
+}
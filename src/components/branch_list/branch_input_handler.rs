@@ -0,0 +1,85 @@
+use crate::{
+  action::Action,
+  components::{generic_input::GenericInput, traits::input_handler::InputHandler},
+  git::git_repo::{GitBranch, GitRepo},
+};
+
+// Git allows longer names but most filesystems don't allow longer ref file names
+const MAX_NAME_LENGTH: usize = 255;
+
+pub type BranchInput = GenericInput<GitBranch, BranchInputHandler>;
+
+#[derive(Debug, Default)]
+pub struct BranchInputHandler {
+  // Names already taken, set when the input is opened
+  existing_names: Vec<String>,
+}
+
+impl BranchInputHandler {
+  pub fn set_existing_branches(&mut self, branches: Vec<&GitBranch>) {
+    self.existing_names = branches.iter().map(|branch| branch.name.clone()).collect();
+  }
+}
+
+impl InputHandler<GitBranch> for BranchInputHandler {
+  fn validate_input(&self, repo: &dyn GitRepo, input: &str) -> bool {
+    if input.trim().is_empty() || input.len() > MAX_NAME_LENGTH {
+      return false;
+    }
+    let is_valid = repo.validate_branch_name(input).unwrap_or(false);
+    let is_unique_name = !self.existing_names.iter().any(|name| name == input);
+    is_valid && is_unique_name
+  }
+
+  fn create_submit_action(&self, input: String) -> Action {
+    Action::CreateBranch(input)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::git::mock_git_repo::MockGitRepo;
+
+  fn repo() -> MockGitRepo {
+    MockGitRepo { validate_branch_name: Some(|name| !name.contains("..")), ..Default::default() }
+  }
+
+  fn handler() -> BranchInputHandler {
+    let mut handler = BranchInputHandler::default();
+    let existing = GitBranch::new(String::from("main"));
+    handler.set_existing_branches(vec![&existing]);
+    handler
+  }
+
+  #[test]
+  fn empty_name_is_invalid() {
+    assert!(!handler().validate_input(&repo(), ""));
+  }
+
+  #[test]
+  fn whitespace_name_is_invalid() {
+    assert!(!handler().validate_input(&repo(), "   "));
+  }
+
+  #[test]
+  fn existing_name_is_invalid() {
+    assert!(!handler().validate_input(&repo(), "main"));
+  }
+
+  #[test]
+  fn unique_valid_name_is_valid() {
+    assert!(handler().validate_input(&repo(), "feature/new-thing"));
+  }
+
+  #[test]
+  fn name_rejected_by_git_is_invalid() {
+    assert!(!handler().validate_input(&repo(), "feature..thing"));
+  }
+
+  #[test]
+  fn name_over_max_length_is_invalid() {
+    assert!(!handler().validate_input(&repo(), &"a".repeat(MAX_NAME_LENGTH + 1)));
+    assert!(handler().validate_input(&repo(), &"a".repeat(MAX_NAME_LENGTH)));
+  }
+}
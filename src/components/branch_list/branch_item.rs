@@ -1,10 +1,31 @@
+use std::{
+  sync::Arc,
+  time::{SystemTime, UNIX_EPOCH},
+};
+
 use ratatui::{
+  layout::Constraint,
   style::{Color, Modifier, Style},
   text::{Line, Span},
-  widgets::ListItem,
+  widgets::{Cell, Row},
 };
 
-use crate::git::git_repo::GitBranch;
+use crate::{
+  components::traits::{has_columns::HasColumns, managed_item::ManagedItem},
+  config::{theme::Theme, BranchListColumns},
+  git::git_repo::GitBranch,
+  utils::time::format_relative_time,
+};
+
+/// How the branch list shows its rows.
+#[derive(Debug, Clone)]
+pub struct BranchRowContext {
+  pub columns: BranchListColumns,
+  pub show_age: bool,
+  /// False while the list is loading, as whether a branch is merged may be about to change.
+  pub show_merged: bool,
+  pub theme: Arc<Theme>,
+}
 
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct BranchItem {
@@ -12,36 +33,282 @@ pub struct BranchItem {
   pub staged_for_deletion: bool,
   pub staged_for_creation: bool,
   pub is_valid_name: bool,
+  /// Picked for a bulk operation.
+  pub multi_selected: bool,
+  /// Fully merged into HEAD.
+  pub merged: bool,
 }
 
 impl BranchItem {
   pub fn new(branch: GitBranch, is_valid_name: bool) -> Self {
-    BranchItem { branch, staged_for_deletion: false, staged_for_creation: false, is_valid_name }
+    BranchItem {
+      branch,
+      staged_for_deletion: false,
+      staged_for_creation: false,
+      is_valid_name,
+      multi_selected: false,
+      merged: false,
+    }
   }
 
-  pub fn render(&self) -> ListItem {
-    let mut text = Line::default();
+  fn name_cell(&self, context: &BranchRowContext) -> Line<'static> {
     let mut parts = Vec::new();
-    let mut name = Span::styled(self.branch.name.clone(), Style::default());
+    let mut name = Span::styled(String::from(self.branch.display_name()), Style::default());
+    if context.show_age {
+      name = name.style(Style::default().fg(self.age_color()).add_modifier(Modifier::DIM));
+    }
+    if let Some(head_colour) = context.theme.head_branch_fg.filter(|_| self.branch.is_head) {
+      name = name.style(Style::default().fg(head_colour));
+    }
     if self.staged_for_deletion {
-      name = name.style(Style::default().fg(Color::Red));
+      name = name.style(Style::default().fg(context.theme.staged_deletion_fg));
     }
     if self.staged_for_creation {
-      name = name.style(Style::default().fg(if self.is_valid_name { Color::LightGreen } else { Color::LightRed }));
+      let colour = if self.is_valid_name { context.theme.valid_input_fg } else { context.theme.invalid_input_fg };
+      name = name.style(Style::default().fg(colour));
+    }
+    if self.multi_selected {
+      name = name.style(Style::default().fg(context.theme.multi_selected_fg));
+      parts.push(Span::styled("✓ ", Style::default().fg(context.theme.multi_selected_fg)));
     }
     parts.push(name);
+    if context.show_merged && self.merged {
+      parts.push(Span::styled(" ✓", Style::default().add_modifier(Modifier::DIM)));
+    }
     if self.branch.is_head {
       parts.push(Span::styled(" (HEAD)", Style::default().add_modifier(Modifier::DIM)));
     }
-    if self.branch.upstream.is_some() {
-      let upstream = self.branch.upstream.clone();
-      parts.push(Span::styled(format!(" [{}]", upstream.unwrap().name), Style::default().add_modifier(Modifier::DIM)));
+    if self.branch.is_worktree {
+      parts.push(Span::styled(" (worktree)", Style::default().add_modifier(Modifier::DIM)));
     }
-    text = text.spans(parts);
-    ListItem::from(text)
+    Line::from(parts)
+  }
+
+  fn age_color(&self) -> Color {
+    let Some(last_commit_at) = self.branch.last_commit_at else {
+      return Color::White;
+    };
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs() as i64);
+    let days_ago = (now - last_commit_at).max(0) / (60 * 60 * 24);
+    age_color(u32::try_from(days_ago).unwrap_or(u32::MAX))
   }
 
   pub fn stage_for_deletion(&mut self, stage: bool) {
     self.staged_for_deletion = stage;
   }
 }
+
+/// The header line for a group of branches, e.g. `▼ feature (3)`.
+pub fn group_label(name: &str, branch_count: usize, collapsed: bool) -> String {
+  format!("{} {} ({})", if collapsed { "▶" } else { "▼" }, name, branch_count)
+}
+
+/// A group's header, in the name column.
+pub fn group_header_row(name: &str, branch_count: usize, collapsed: bool) -> Row<'static> {
+  let label = Span::styled(group_label(name, branch_count, collapsed), Style::default().add_modifier(Modifier::BOLD));
+  Row::new([Cell::from(Line::from(label))])
+}
+
+impl HasColumns for BranchItem {
+  type Context = BranchRowContext;
+
+  fn column_widths(context: &BranchRowContext) -> Vec<Constraint> {
+    let mut widths = vec![Constraint::Fill(2)];
+    if context.columns.upstream {
+      widths.push(Constraint::Fill(1));
+    }
+    if context.columns.ahead_behind {
+      widths.push(Constraint::Length(10));
+    }
+    if context.columns.last_commit_date {
+      // Long enough for `11 months ago`
+      widths.push(Constraint::Length(14));
+    }
+    widths
+  }
+
+  fn to_row(&self, context: &BranchRowContext) -> Row<'static> {
+    let dim = Style::default().add_modifier(Modifier::DIM);
+    let mut cells = vec![Cell::from(self.name_cell(context))];
+    if context.columns.upstream {
+      let upstream = self.branch.upstream.as_ref().map(|upstream| format!("[{}]", upstream.name));
+      cells.push(Cell::from(Span::styled(upstream.unwrap_or_default(), dim)));
+    }
+    if context.columns.ahead_behind {
+      let ahead_behind = (self.branch.ahead > 0 || self.branch.behind > 0)
+        .then(|| format!("↑{}↓{}", self.branch.ahead, self.branch.behind));
+      cells.push(Cell::from(Span::styled(ahead_behind.unwrap_or_default(), dim)));
+    }
+    if context.columns.last_commit_date {
+      let date = self.branch.last_commit_at.map(format_relative_time);
+      cells.push(Cell::from(Span::styled(date.unwrap_or_default(), dim)));
+    }
+    Row::new(cells)
+  }
+}
+
+pub fn age_color(days_ago: u32) -> Color {
+  match days_ago {
+    0..7 => Color::Green,
+    7..=30 => Color::Yellow,
+    _ => Color::DarkGray,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use insta::assert_snapshot;
+
+  use super::*;
+  use crate::{components::list_snapshot::row_snapshot, git::git_repo::GitRemoteBranch};
+
+  fn context(columns: BranchListColumns) -> BranchRowContext {
+    BranchRowContext { columns, show_age: false, show_merged: true, theme: Arc::new(Theme::default()) }
+  }
+
+  fn snapshot_with(item: &BranchItem, columns: BranchListColumns) -> String {
+    let context = context(columns);
+    row_snapshot(item.to_row(&context), BranchItem::column_widths(&context), 60)
+  }
+
+  fn snapshot(item: &BranchItem) -> String {
+    snapshot_with(item, BranchListColumns::default())
+  }
+
+  fn branch(name: &str) -> BranchItem {
+    BranchItem::new(GitBranch::new(String::from(name)), true)
+  }
+
+  fn head_with_upstream(gone: bool) -> BranchItem {
+    let mut item = branch("main");
+    item.branch.is_head = true;
+    item.branch.upstream = Some(GitRemoteBranch { name: String::from("origin/main"), gone });
+    item
+  }
+
+  #[test]
+  fn staged_for_deletion() {
+    let mut item = branch("feature/a");
+    item.stage_for_deletion(true);
+    assert_snapshot!(snapshot(&item));
+  }
+
+  #[test]
+  fn staged_for_creation_with_a_valid_name() {
+    let mut item = branch("feature/new");
+    item.staged_for_creation = true;
+    assert_snapshot!(snapshot(&item));
+  }
+
+  #[test]
+  fn staged_for_creation_with_an_invalid_name() {
+    let mut item = BranchItem::new(GitBranch::new(String::from("feature..new")), false);
+    item.staged_for_creation = true;
+    assert_snapshot!(snapshot(&item));
+  }
+
+  #[test]
+  fn head_with_an_upstream() {
+    let mut item = head_with_upstream(false);
+    item.branch.ahead = 2;
+    item.branch.behind = 1;
+    assert_snapshot!(snapshot(&item));
+  }
+
+  #[test]
+  fn head_without_optional_columns() {
+    let mut item = head_with_upstream(false);
+    item.branch.ahead = 2;
+    let columns = BranchListColumns { upstream: false, ahead_behind: false, last_commit_date: false };
+    assert_snapshot!(snapshot_with(&item, columns));
+  }
+
+  #[test]
+  fn head_with_a_gone_upstream() {
+    assert_snapshot!(snapshot(&head_with_upstream(true)));
+  }
+
+  #[test]
+  fn collapsed_group_header() {
+    let widths = BranchItem::column_widths(&context(BranchListColumns::default()));
+    assert_snapshot!(row_snapshot(group_header_row("feature", 3, true), widths, 60));
+  }
+
+  #[test]
+  fn merged() {
+    let mut item = branch("feature/a");
+    item.merged = true;
+    assert_snapshot!(snapshot(&item));
+  }
+
+  // The rendered ahead/behind column, without the name before it
+  fn ahead_behind(ahead: u32, behind: u32) -> String {
+    let mut item = branch("feature/a");
+    (item.branch.ahead, item.branch.behind) = (ahead, behind);
+    let columns = BranchListColumns { upstream: false, ahead_behind: true, last_commit_date: false };
+    let snapshot = snapshot_with(&item, columns);
+    String::from(snapshot.lines().next().unwrap_or_default().trim_start_matches("feature/a").trim())
+  }
+
+  #[test]
+  fn ahead_behind_is_blank_when_up_to_date() {
+    assert_eq!(ahead_behind(0, 0), "");
+  }
+
+  #[test]
+  fn ahead_behind_when_only_ahead() {
+    assert_eq!(ahead_behind(3, 0), "↑3↓0");
+  }
+
+  #[test]
+  fn ahead_behind_when_only_behind() {
+    assert_eq!(ahead_behind(0, 4), "↑0↓4");
+  }
+
+  #[test]
+  fn ahead_behind_when_diverged() {
+    assert_eq!(ahead_behind(2, 5), "↑2↓5");
+  }
+
+  #[test]
+  fn a_custom_theme_is_used_when_rendering() {
+    let config_dir = std::env::temp_dir().join(format!("{}-theme-{}", env!("CARGO_PKG_NAME"), std::process::id()));
+    std::fs::create_dir_all(&config_dir).unwrap();
+    let fields =
+      ["head_branch_fg", "staged_deletion_fg", "multi_selected_fg", "valid_input_fg", "invalid_input_fg", "input_fg"];
+    let theme_toml: String = fields.iter().map(|field| format!("{} = \"white\"\n", field)).collect();
+    std::fs::write(config_dir.join("theme.toml"), theme_toml).unwrap();
+    let theme = Theme::load(&config_dir);
+    std::fs::remove_dir_all(&config_dir).unwrap();
+    assert_eq!(theme, Theme {
+      head_branch_fg: Some(Color::White),
+      staged_deletion_fg: Color::White,
+      multi_selected_fg: Color::White,
+      valid_input_fg: Color::White,
+      invalid_input_fg: Color::White,
+      input_fg: Color::White,
+    });
+
+    let context = BranchRowContext { theme: Arc::new(theme), ..context(BranchListColumns::default()) };
+    let mut item = branch("feature/a");
+    item.stage_for_deletion(true);
+    let snapshot = row_snapshot(item.to_row(&context), BranchItem::column_widths(&context), 60);
+    assert_eq!(snapshot, "feature/a\n  0..9 fg=White\n");
+  }
+
+  #[test]
+  fn age_colours_by_bucket() {
+    assert_eq!(age_color(0), Color::Green);
+    assert_eq!(age_color(6), Color::Green);
+    assert_eq!(age_color(7), Color::Yellow);
+    assert_eq!(age_color(30), Color::Yellow);
+    assert_eq!(age_color(31), Color::DarkGray);
+  }
+
+  #[test]
+  fn a_branch_without_a_last_commit_time_is_white() {
+    let context = BranchRowContext { show_age: true, ..context(BranchListColumns::default()) };
+    let snapshot = row_snapshot(branch("feature/a").to_row(&context), BranchItem::column_widths(&context), 60);
+    assert_eq!(snapshot, "feature/a\n  0..9 fg=White DIM\n");
+  }
+}
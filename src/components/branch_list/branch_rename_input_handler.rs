@@ -0,0 +1,38 @@
+use crate::{
+  action::Action,
+  components::{generic_input::GenericInput, traits::input_handler::InputHandler},
+  git::git_repo::{GitBranch, GitRepo},
+};
+
+pub type BranchRenameInput = GenericInput<GitBranch, BranchRenameInputHandler>;
+
+/// Renames the branch that was selected when the input was opened.
+#[derive(Debug, Default)]
+pub struct BranchRenameInputHandler {
+  old_name: String,
+  // Names already taken, set when the input is opened
+  existing_names: Vec<String>,
+}
+
+impl BranchRenameInputHandler {
+  pub fn set_branch(&mut self, old_name: String, existing_names: Vec<String>) {
+    self.old_name = old_name;
+    self.existing_names = existing_names;
+  }
+}
+
+impl InputHandler<GitBranch> for BranchRenameInputHandler {
+  fn validate_input(&self, repo: &dyn GitRepo, input: &str) -> bool {
+    let is_valid = repo.validate_branch_name(input).unwrap_or(false);
+    let is_unique_name = !self.existing_names.iter().any(|name| name == input);
+    is_valid && is_unique_name
+  }
+
+  fn create_submit_action(&self, input: String) -> Action {
+    Action::RenameBranch(self.old_name.clone(), input)
+  }
+
+  fn get_input_prompt(&self) -> Option<&str> {
+    Some("Rename branch:")
+  }
+}
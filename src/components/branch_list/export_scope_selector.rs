@@ -0,0 +1,48 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+  layout::{Constraint, Flex, Layout, Rect},
+  style::{Color, Style},
+  text::{Line, Text},
+  widgets::{Block, Clear, Paragraph},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{action::Action, components::Component, tui::Frame};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExportScope {
+  All,
+  StagedForDeletion,
+  Selected,
+}
+
+/// Asks which branches to export when there are branches staged for deletion.
+#[derive(Debug, Default)]
+pub struct ExportScopeSelector {}
+
+impl Component for ExportScopeSelector {
+  fn handle_key_events(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+    match key.code {
+      KeyCode::Char('a' | 'A') => Ok(Some(Action::ExportBranches(ExportScope::All))),
+      KeyCode::Char('s' | 'S') => Ok(Some(Action::ExportBranches(ExportScope::StagedForDeletion))),
+      KeyCode::Char('c' | 'C') => Ok(Some(Action::ExportBranches(ExportScope::Selected))),
+      KeyCode::Esc => Ok(Some(Action::EndInputMode)),
+      _ => Ok(None),
+    }
+  }
+
+  fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> color_eyre::Result<()> {
+    let [popup] = Layout::horizontal([Constraint::Length(40)]).flex(Flex::Center).areas(area);
+    let [popup] = Layout::vertical([Constraint::Length(5)]).flex(Flex::Center).areas(popup);
+    let text = Text::from(vec![
+      Line::from("Which branches should be exported?"),
+      Line::default(),
+      Line::from("[A]ll  [S]taged  [C]urrent"),
+    ]);
+    let selector =
+      Paragraph::new(text).block(Block::bordered().title("Export")).style(Style::default().fg(Color::White));
+    f.render_widget(Clear, popup);
+    f.render_widget(selector, popup);
+    Ok(())
+  }
+}
@@ -1,35 +1,97 @@
-use ratatui::{
-  layout::Rect,
-  prelude::{Line, Span},
-};
-
-use crate::{components::branch_list::branch_item::BranchItem, tui::Frame};
+use crate::components::branch_list::branch_item::BranchItem;
 
 #[derive(Debug, Default)]
 pub struct InstructionFooter {}
 
 impl InstructionFooter {
-  pub fn render(&self, f: &mut Frame<'_>, area: Rect, branches: &[BranchItem], selected: Option<&BranchItem>) {
-    let mut commands = vec![Span::raw("esc: Quit")];
-    commands.push(Span::raw(" | ⇧ + c: Checkout new"));
+  pub fn instructions(
+    &self,
+    branches: &[BranchItem],
+    selected: Option<&BranchItem>,
+    multi_selected_count: usize,
+    filter_editing: bool,
+    filter_active: bool,
+    group_selected: bool,
+  ) -> Vec<&'static str> {
+    if filter_editing {
+      return vec!["esc: Clear filter", "enter: Keep filter"];
+    }
+
+    let mut commands = vec![if filter_active { "esc: Clear filter" } else { "esc: Quit" }];
+    commands.push("tab: Switch view");
+    commands.push("/: Filter");
+    commands.push("b: Group by prefix");
+    if group_selected {
+      commands.push("enter: Collapse/expand group");
+    }
+    commands.push("⇧ + c: Checkout new");
+    commands.push("^ + g: Prune gone branches");
+    if multi_selected_count > 0 {
+      commands.push("d: Stage selected for deletion");
+      commands.push("c: Checkout last selected");
+      commands.push("^ + a: Clear selection");
+    } else {
+      commands.push("^ + a: Select all");
+    }
+    if selected.is_some() {
+      commands.push("space: Multi-select");
+    }
     if selected.is_some() && selected.unwrap().staged_for_deletion {
-      commands.push(Span::raw(" | d: Delete"));
-      commands.push(Span::raw(" | ⇧ + d: Unstage for deletion"));
+      commands.push("d: Delete");
+      commands.push("⇧ + d: Unstage for deletion");
     }
 
     if selected.is_some() && !selected.unwrap().branch.is_head {
-      commands.push(Span::raw(" | d: Stage for deletion"));
+      commands.push("d: Stage for deletion");
     }
 
     if selected.is_some() {
-      commands.push(Span::raw(" | c: Checkout"));
+      commands.push("c: Checkout");
+      commands.push("r: Rename");
+      commands.push("v: View last commit");
+      commands.push("⇧ + l: Commit log");
+      commands.push("y: Copy name");
+    }
+
+    if selected.is_some_and(|s| !s.branch.is_head) && branches.iter().any(|b| b.branch.is_head) {
+      commands.push("⇧ + v: Diff against HEAD");
+    }
+
+    if selected.is_some_and(|s| !s.branch.is_head) {
+      commands.push("m: Merge into current");
+      commands.push("⇧ + r: Rebase current onto");
+    }
+
+    if selected.is_some_and(|s| s.branch.is_head) {
+      commands.push("p: Push");
+      commands.push("^ + p: Force push");
+      commands.push("⇧ + p: Pull");
+    }
+
+    if selected.is_some_and(|s| s.branch.upstream.is_some()) {
+      commands.push("u: Unset upstream");
+    } else if selected.is_some_and(|s| !s.branch.name.starts_with('(')) {
+      commands.push("u: Set upstream");
+    }
+
+    if selected.is_some_and(|s| s.branch.upstream.as_ref().is_some_and(|upstream| !upstream.gone)) {
+      commands.push("⇧ + x: Delete remote branch");
+    }
+
+    if selected.is_some_and(|s| !s.branch.is_head && !s.branch.is_worktree) {
+      commands.push("w: New worktree");
     }
 
     if branches.iter().any(|b| b.staged_for_deletion) {
-      commands.push(Span::raw(" | ^ + d: Delete all staged branches"));
+      commands.push("^ + d: Delete all staged branches");
     }
 
-    let footer = Line::from(commands);
-    f.render_widget(footer, area);
+    commands.push("o: Change sort");
+    commands.push("⇧ + i: Toggle details");
+    commands.push("^ + e: Export");
+    commands.push("^ + r: Refresh");
+    commands.push("f: Fetch");
+    commands.push("⇧ + f: Update all tracking branches");
+    commands
   }
 }
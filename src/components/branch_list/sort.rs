@@ -0,0 +1,104 @@
+use std::cmp::Reverse;
+
+use crate::git::git_repo::GitBranch;
+
+/// How the branch list is ordered, `o` cycles through them.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+  #[default]
+  Alphabetical,
+  /// Most recently committed to first.
+  LastCommitDate,
+  /// Furthest from their upstream first.
+  AheadBehind,
+  /// Newest first, by when the branch's reflog starts.
+  CreationDate,
+}
+
+impl SortOrder {
+  pub fn next(self) -> Self {
+    match self {
+      SortOrder::Alphabetical => SortOrder::LastCommitDate,
+      SortOrder::LastCommitDate => SortOrder::AheadBehind,
+      SortOrder::AheadBehind => SortOrder::CreationDate,
+      SortOrder::CreationDate => SortOrder::Alphabetical,
+    }
+  }
+
+  pub fn label(self) -> &'static str {
+    match self {
+      SortOrder::Alphabetical => "by name",
+      SortOrder::LastCommitDate => "by date",
+      SortOrder::AheadBehind => "by ahead/behind",
+      SortOrder::CreationDate => "by creation",
+    }
+  }
+}
+
+/// Sorts `branches` in place, branches missing what they are sorted by go last and ties stay in name order.
+pub fn sort_branches(branches: &mut [GitBranch], order: SortOrder) {
+  sort_by_branch(branches, order, |branch| branch);
+}
+
+/// Like `sort_branches` for items holding a branch, e.g. the list's rows.
+pub fn sort_by_branch<T, F: Fn(&T) -> &GitBranch>(items: &mut [T], order: SortOrder, branch: F) {
+  items.sort_by(|a, b| branch(a).name.cmp(&branch(b).name));
+  match order {
+    SortOrder::Alphabetical => {},
+    SortOrder::LastCommitDate => items.sort_by_key(|item| Reverse(branch(item).last_commit_at)),
+    SortOrder::AheadBehind => items.sort_by_key(|item| Reverse(branch(item).ahead + branch(item).behind)),
+    SortOrder::CreationDate => items.sort_by_key(|item| Reverse(branch(item).created_at)),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn branches() -> Vec<GitBranch> {
+    let branch = |name: &str, last_commit_at, created_at, ahead, behind| {
+      GitBranch { last_commit_at, created_at, ahead, behind, ..GitBranch::new(String::from(name)) }
+    };
+    vec![
+      branch("c", Some(300), Some(100), 0, 1),
+      branch("a", Some(100), None, 2, 3),
+      branch("d", None, Some(300), 0, 0),
+      branch("b", Some(200), Some(200), 1, 0),
+    ]
+  }
+
+  fn sorted(order: SortOrder) -> Vec<String> {
+    let mut branches = branches();
+    sort_branches(&mut branches, order);
+    branches.into_iter().map(|branch| branch.name).collect()
+  }
+
+  #[test]
+  fn alphabetical() {
+    assert_eq!(sorted(SortOrder::Alphabetical), ["a", "b", "c", "d"]);
+  }
+
+  #[test]
+  fn last_commit_date_puts_branches_without_one_last() {
+    assert_eq!(sorted(SortOrder::LastCommitDate), ["c", "b", "a", "d"]);
+  }
+
+  #[test]
+  fn ahead_behind_keeps_ties_in_name_order() {
+    assert_eq!(sorted(SortOrder::AheadBehind), ["a", "b", "c", "d"]);
+  }
+
+  #[test]
+  fn creation_date_puts_branches_without_one_last() {
+    assert_eq!(sorted(SortOrder::CreationDate), ["d", "b", "c", "a"]);
+  }
+
+  #[test]
+  fn cycling_returns_to_the_first_order() {
+    let mut order = SortOrder::default();
+    for _ in 0..4 {
+      order = order.next();
+    }
+    assert_eq!(order, SortOrder::Alphabetical);
+  }
+}
@@ -0,0 +1,36 @@
+use crate::{
+  action::Action,
+  components::{generic_input::GenericInput, traits::input_handler::InputHandler},
+  git::git_repo::{GitBranch, GitRepo},
+};
+
+pub type UpstreamInput = GenericInput<GitBranch, UpstreamInputHandler>;
+
+/// Asks which remote branch the selected branch should track.
+#[derive(Debug, Default)]
+pub struct UpstreamInputHandler {
+  branch_name: String,
+  // Remote branches that could be tracked, set when the input is opened
+  remote_branches: Vec<String>,
+}
+
+impl UpstreamInputHandler {
+  pub fn set_branch(&mut self, branch_name: String, remote_branches: Vec<String>) {
+    self.branch_name = branch_name;
+    self.remote_branches = remote_branches;
+  }
+}
+
+impl InputHandler<GitBranch> for UpstreamInputHandler {
+  fn validate_input(&self, _repo: &dyn GitRepo, input: &str) -> bool {
+    self.remote_branches.iter().any(|name| name == input.trim())
+  }
+
+  fn create_submit_action(&self, input: String) -> Action {
+    Action::SetUpstream(self.branch_name.clone(), input)
+  }
+
+  fn get_input_prompt(&self) -> Option<&str> {
+    Some("Track remote branch:")
+  }
+}
@@ -0,0 +1,28 @@
+use std::path::Path;
+
+use crate::{
+  action::Action,
+  components::{generic_input::GenericInput, traits::input_handler::InputHandler},
+  git::git_repo::{GitRepo, GitWorktree},
+};
+
+pub type WorktreePathInput = GenericInput<GitWorktree, WorktreePathInputHandler>;
+
+/// Asks where the worktree for the selected branch should be created.
+#[derive(Debug, Default)]
+pub struct WorktreePathInputHandler {}
+
+impl InputHandler<GitWorktree> for WorktreePathInputHandler {
+  fn validate_input(&self, _repo: &dyn GitRepo, input: &str) -> bool {
+    // git refuses to add a worktree over an existing directory
+    !input.trim().is_empty() && !Path::new(input.trim()).exists()
+  }
+
+  fn create_submit_action(&self, input: String) -> Action {
+    Action::CreateWorktree(input)
+  }
+
+  fn get_input_prompt(&self) -> Option<&str> {
+    Some("Worktree path:")
+  }
+}
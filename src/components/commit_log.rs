@@ -0,0 +1,298 @@
+use std::sync::Arc;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+  layout::{Alignment, Constraint, Direction, Layout, Rect},
+  style::{Color, Modifier, Style},
+  text::Text,
+  widgets::{block::Title, Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+};
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::error;
+
+use crate::{
+  action::Action,
+  components::{
+    list_navigation::ListNavigation,
+    loading_operation::LoadingOperation,
+    ui::{self, diff_popup::DiffPopup, notification::NotificationLevel, page_indicator::page_indicator},
+    Component,
+  },
+  error::Error,
+  git::git_repo::{GitBranch, GitCommit, GitRepo},
+  tui::Frame,
+};
+
+mod commit_item;
+
+// Older commits are rarely what someone is looking for and long histories are slow to load
+const COMMIT_LIMIT: usize = 200;
+
+/// The recent commits on a branch, opened over the branch list and closed with esc. Commits can be cherry-picked onto
+/// HEAD, or their diff shown, from here.
+pub struct CommitLog {
+  // Shared with the cherry-pick running in the background
+  repo: Arc<dyn GitRepo + Send + Sync>,
+  action_tx: Option<UnboundedSender<Action>>,
+  branch: GitBranch,
+  title: String,
+  error: Option<String>,
+  commits: Vec<GitCommit>,
+  list_state: ListState,
+  selected_index: usize,
+  navigation: ListNavigation,
+  diff_popup: Option<DiffPopup>,
+  loading: LoadingOperation,
+  spinner_frame: usize,
+  // The commit being cherry-picked, for the message once it is done
+  picking: Option<String>,
+}
+
+impl CommitLog {
+  pub fn new(repo: Arc<dyn GitRepo + Send + Sync>, branch: GitBranch) -> Self {
+    let title = format!("Commits on {}", branch.name);
+    let mut commit_log = CommitLog {
+      repo,
+      action_tx: None,
+      branch,
+      title,
+      error: None,
+      commits: Vec::new(),
+      list_state: ListState::default(),
+      selected_index: 0,
+      navigation: ListNavigation::default(),
+      diff_popup: None,
+      loading: LoadingOperation::None,
+      spinner_frame: 0,
+      picking: None,
+    };
+    let result = commit_log.refresh();
+    commit_log.maybe_handle_git_error(result.err());
+    commit_log
+  }
+
+  fn refresh(&mut self) -> Result<(), Error> {
+    self.commits = self.repo.commit_log(&self.branch, COMMIT_LIMIT)?;
+    self.selected_index = self.selected_index.min(self.commits.len().saturating_sub(1));
+    Ok(())
+  }
+
+  fn select_previous(&mut self) {
+    if self.commits.is_empty() {
+      return;
+    }
+    self.selected_index = self.selected_index.checked_sub(1).unwrap_or(self.commits.len() - 1);
+  }
+
+  fn select_next(&mut self) {
+    if self.selected_index + 1 >= self.commits.len() {
+      self.selected_index = 0;
+      return;
+    }
+    self.selected_index += 1;
+  }
+
+  fn show_selected_commit_diff(&mut self) -> Result<(), Error> {
+    let Some(commit) = self.commits.get(self.selected_index) else {
+      return Ok(());
+    };
+    let diff = self.repo.show_commit_diff(&commit.sha)?;
+    let short_sha = commit.sha.get(..7).unwrap_or(&commit.sha);
+    self.diff_popup = Some(DiffPopup::new(format!("{} {}", short_sha, commit.subject), diff));
+    Ok(())
+  }
+
+  fn cherry_pick(&mut self, sha: String) {
+    let Some(action_tx) = self.action_tx.clone() else {
+      return;
+    };
+    // One at a time, a second cherry-pick would trip over the first's lock or conflicts
+    if self.loading != LoadingOperation::None {
+      return;
+    }
+    self.loading = LoadingOperation::processing("Cherry-picking");
+    self.spinner_frame = 0;
+    self.picking = Some(sha.clone());
+    let repo = Arc::clone(&self.repo);
+    tokio::task::spawn_blocking(move || {
+      let result = repo.cherry_pick(&sha).map_err(|err| err.to_string());
+      if let Err(err) = action_tx.send(Action::CherryPickCompleted(result)) {
+        error!("Failed to send the result of cherry-picking {}: {}", sha, err);
+      }
+    });
+  }
+
+  fn maybe_handle_git_error(&mut self, err: Option<Error>) {
+    if let Some(error) = err {
+      error!("{}", error);
+      self.error = Some(error.to_string());
+    }
+  }
+
+  fn render_list(&mut self, f: &mut Frame<'_>, area: Rect) {
+    self.navigation.set_rendered_area(area);
+    self.list_state.select(Some(self.selected_index));
+    let render_items: Vec<ListItem> = self.commits.iter().map(commit_item::render).collect();
+    let mut block =
+      Block::default().title(self.title.clone() + &self.loading.title_suffix(self.spinner_frame)).borders(Borders::ALL);
+    // The borders take a row from the top and bottom
+    let visible_height = usize::from(area.height.saturating_sub(2));
+    if let Some(page) = page_indicator(self.selected_index, render_items.len(), visible_height) {
+      block = block.title(Title::from(page).alignment(Alignment::Right));
+    }
+    let list = List::new(render_items)
+      .block(block)
+      .style(Style::default().fg(Color::White))
+      .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+      .highlight_symbol("→")
+      .repeat_highlight_symbol(true);
+    f.render_stateful_widget(list, area, &mut self.list_state);
+  }
+}
+
+impl Component for CommitLog {
+  fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> color_eyre::Result<()> {
+    self.action_tx = Some(tx);
+    Ok(())
+  }
+
+  fn handle_key_events(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+    self.error = None;
+    if let Some(diff_popup) = self.diff_popup.as_mut() {
+      // Esc backs out of the diff before it backs out of the log
+      if key.code == KeyCode::Esc {
+        self.diff_popup = None;
+        return Ok(None);
+      }
+      return diff_popup.handle_key_events(key);
+    }
+    if let Some(action) = self.navigation.handle_key_event(&key) {
+      return Ok(Some(action));
+    }
+    match key {
+      KeyEvent { code: KeyCode::Esc, modifiers: KeyModifiers::NONE, kind: _, state: _ } => Ok(Some(Action::PopView)),
+      KeyEvent { code: KeyCode::Down, modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        Ok(Some(Action::SelectNextCommit))
+      },
+      KeyEvent { code: KeyCode::Up, modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        Ok(Some(Action::SelectPreviousCommit))
+      },
+      KeyEvent { code: KeyCode::Enter, modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        Ok(self.commits.get(self.selected_index).map(|commit| Action::CherryPickCommit(commit.sha.clone())))
+      },
+      KeyEvent { code: KeyCode::Char('v'), modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        Ok(self.commits.get(self.selected_index).map(|_| Action::ShowSelectedCommitDiff))
+      },
+      KeyEvent { code: KeyCode::Char('r' | 'R'), modifiers: KeyModifiers::CONTROL, kind: _, state: _ } => {
+        Ok(Some(Action::Refresh))
+      },
+      _ => Ok(None),
+    }
+  }
+
+  fn update(&mut self, action: Action) -> color_eyre::Result<Option<Action>> {
+    match action {
+      Action::SelectNextCommit => self.select_next(),
+      Action::SelectPreviousCommit => self.select_previous(),
+      Action::SelectFirst | Action::SelectLast | Action::SelectPosition(_) | Action::PageDown | Action::PageUp => {
+        if let Some(index) = self.navigation.target_position(&action, self.selected_index, self.commits.len()) {
+          self.selected_index = index;
+        }
+      },
+      Action::Refresh => {
+        let result = self.refresh();
+        self.maybe_handle_git_error(result.err());
+      },
+      Action::Tick if self.loading != LoadingOperation::None => {
+        self.spinner_frame = LoadingOperation::next_spinner_frame(self.spinner_frame);
+      },
+      Action::ShowSelectedCommitDiff => {
+        let result = self.show_selected_commit_diff();
+        self.maybe_handle_git_error(result.err());
+      },
+      Action::CherryPickCommit(sha) => self.cherry_pick(sha),
+      Action::CherryPickCompleted(result) => {
+        self.loading = LoadingOperation::None;
+        let sha = self.picking.take().unwrap_or_default();
+        match result {
+          Ok(()) => {
+            let short_sha = sha.get(..7).unwrap_or(&sha);
+            let message = format!("Cherry-picked {} onto HEAD", short_sha);
+            return Ok(Some(Action::Notify { message, level: NotificationLevel::Success }));
+          },
+          Err(err) => {
+            error!("{}", err);
+            self.error = Some(err);
+          },
+        }
+      },
+      _ => {},
+    }
+    Ok(None)
+  }
+
+  fn title(&self) -> &str {
+    &self.title
+  }
+
+  fn item_counts(&self) -> (usize, usize) {
+    (self.commits.len(), self.commits.len())
+  }
+
+  fn instructions(&self) -> Vec<&'static str> {
+    let mut instructions = vec!["esc: Back to branches"];
+    if !self.commits.is_empty() {
+      instructions.push("enter: Cherry-pick onto HEAD");
+      instructions.push("v: Show diff");
+    }
+    instructions.push("^ + r: Refresh");
+    instructions
+  }
+
+  fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> color_eyre::Result<()> {
+    let instructions = self.instructions();
+    // The layout margin takes a column from each side
+    let footer_height = ui::instruction_footer::preferred_height(&instructions, area.width.saturating_sub(2));
+    let error_height = self.error.as_ref().map_or(0, |error| error.lines().count() + 2);
+    let layout = Layout::new(Direction::Vertical, [
+      Constraint::Min(1),
+      Constraint::Length(u16::try_from(error_height)?),
+      Constraint::Length(footer_height),
+    ])
+    .margin(1)
+    .split(area);
+    self.render_list(f, layout[0]);
+    if let Some(error) = &self.error {
+      let component = Paragraph::new(Text::from(error.as_str()))
+        .block(Block::bordered().title("Error"))
+        .style(Style::from(Color::Red))
+        .wrap(Wrap { trim: true });
+      f.render_widget(component, layout[1]);
+    }
+    ui::instruction_footer::render(f, layout[2], &instructions);
+    if let Some(diff_popup) = self.diff_popup.as_mut() {
+      diff_popup.draw(f, area)?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::git::mock_git_repo::MockGitRepo;
+
+  #[test]
+  fn v_shows_the_selected_commits_diff_until_esc() {
+    let commit = GitCommit { sha: String::from("8fb5d9b1c"), subject: String::from("Fix it"), ..Default::default() };
+    let repo = MockGitRepo { commits: vec![commit], ..Default::default() };
+    let mut log = CommitLog::new(Arc::new(repo), GitBranch::new(String::from("main")));
+    let action = log.handle_key_events(KeyEvent::from(KeyCode::Char('v'))).unwrap();
+    assert_eq!(action, Some(Action::ShowSelectedCommitDiff));
+    log.update(action.unwrap()).unwrap();
+    assert!(log.diff_popup.is_some());
+    assert_eq!(log.handle_key_events(KeyEvent::from(KeyCode::Esc)).unwrap(), None);
+    assert!(log.diff_popup.is_none());
+    assert_eq!(log.handle_key_events(KeyEvent::from(KeyCode::Esc)).unwrap(), Some(Action::PopView));
+  }
+}
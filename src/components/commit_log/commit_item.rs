@@ -0,0 +1,19 @@
+use ratatui::{
+  style::{Modifier, Style},
+  text::{Line, Span},
+  widgets::ListItem,
+};
+
+use crate::{git::git_repo::GitCommit, utils::time::format_relative_time};
+
+const SHORT_SHA_LENGTH: usize = 7;
+
+pub fn render(commit: &GitCommit) -> ListItem<'_> {
+  let dim = Style::default().add_modifier(Modifier::DIM);
+  let short_sha = commit.sha.get(..SHORT_SHA_LENGTH).unwrap_or(&commit.sha);
+  ListItem::from(Line::from(vec![
+    Span::styled(format!("{} ", short_sha), dim),
+    Span::raw(commit.subject.as_str()),
+    Span::styled(format!(" - {}, {}", commit.author, format_relative_time(commit.committed_at)), dim),
+  ]))
+}
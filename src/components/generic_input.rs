@@ -0,0 +1,156 @@
+use std::{marker::PhantomData, sync::Arc};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+  layout::Rect,
+  style::Style,
+  widgets::{Block, Borders},
+};
+use tui_textarea::{CursorMove, Input, TextArea};
+
+use crate::{
+  action::Action,
+  components::{input_history::InputHistory, traits::input_handler::InputHandler},
+  config::theme::Theme,
+  git::git_repo::GitRepo,
+  tui::Frame,
+};
+
+#[derive(Debug, Default, Clone)]
+pub struct InputState {
+  pub value: Option<String>,
+  pub is_valid: Option<bool>,
+}
+
+/// A single line text input with history, what is valid and what submitting does is left to the `InputHandler`.
+pub struct GenericInput<T, IH: InputHandler<T>> {
+  pub text_input: TextArea<'static>,
+  pub input_state: InputState,
+  history: InputHistory,
+  handler: IH,
+  theme: Arc<Theme>,
+  item: PhantomData<T>,
+}
+
+impl<T, IH: InputHandler<T>> GenericInput<T, IH> {
+  pub fn new(handler: IH, history_namespace: &'static str, theme: Arc<Theme>) -> Self {
+    GenericInput {
+      text_input: TextArea::default(),
+      input_state: InputState::default(),
+      history: InputHistory::load(history_namespace),
+      handler,
+      theme,
+      item: PhantomData,
+    }
+  }
+
+  pub fn handler(&self) -> &IH {
+    &self.handler
+  }
+
+  pub fn handler_mut(&mut self) -> &mut IH {
+    &mut self.handler
+  }
+
+  pub fn init_style(&mut self) {
+    let mut block = Block::default().borders(Borders::ALL);
+    if let Some(prompt) = self.handler.get_input_prompt() {
+      block = block.title(String::from(prompt));
+    }
+    self.text_input.set_style(Style::default().fg(self.theme.input_fg));
+    self.text_input.set_block(block);
+  }
+
+  /// Pre-fill the input, e.g. with a suggested value.
+  pub fn set_text(&mut self, text: String) {
+    self.show_history_entry(Some(text));
+  }
+
+  fn get_text(&self) -> Option<String> {
+    let input = String::from(self.text_input.lines().first()?.trim());
+    if input.is_empty() {
+      return None;
+    }
+    Some(input)
+  }
+
+  fn clear_text(&mut self) {
+    self.text_input.move_cursor(CursorMove::Head);
+    self.text_input.delete_line_by_end();
+  }
+
+  /// Replace the input text with an entry from history, validation waits until the user edits it.
+  fn show_history_entry(&mut self, entry: Option<String>) {
+    self.clear_text();
+    self.text_input.set_style(Style::default().fg(self.theme.input_fg));
+    self.input_state.is_valid = None;
+    if let Some(entry) = &entry {
+      self.text_input.insert_str(entry);
+    }
+    self.input_state.value = entry;
+  }
+
+  fn validate(&mut self, repo: &dyn GitRepo) {
+    let Some(input) = self.text_input.lines().first() else {
+      return;
+    };
+    let is_valid = self.handler.validate_input(repo, input);
+    let colour = if is_valid { self.theme.valid_input_fg } else { self.theme.invalid_input_fg };
+    self.text_input.set_style(Style::default().fg(colour));
+    self.input_state.is_valid = Some(is_valid);
+  }
+
+  pub fn handle_key_event(&mut self, key_event: KeyEvent, repo: &dyn GitRepo) -> Option<Action> {
+    match key_event {
+      KeyEvent { code: KeyCode::Esc, modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        self.input_state.value = None;
+        self.history.reset_cursor();
+        // purposely don't send the key, we want to delete the line
+        self.clear_text();
+        Some(Action::EndInputMode)
+      },
+      KeyEvent { code: KeyCode::Enter, modifiers: _, kind: _, state: _ } => {
+        // Blank input is never submitted, whatever the handler would accept
+        let Some(input) = self.get_text() else {
+          self.text_input.set_style(Style::default().fg(self.theme.invalid_input_fg));
+          self.input_state.is_valid = Some(false);
+          return None;
+        };
+        if self.input_state.is_valid == Some(false) {
+          // TODO report error
+          return None;
+        }
+        // purposely don't send the key, we want to delete the line
+        self.clear_text();
+        self.history.push(input.clone());
+        Some(self.handler.create_submit_action(input))
+      },
+      KeyEvent { code: KeyCode::Up, modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        if let Some(entry) = self.history.older().map(String::from) {
+          self.show_history_entry(Some(entry));
+        }
+        None
+      },
+      KeyEvent { code: KeyCode::Down, modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        let entry = self.history.newer().map(String::from);
+        self.show_history_entry(entry);
+        None
+      },
+      _ => {
+        if self.text_input.input(Input::from(key_event)) {
+          self.history.reset_cursor();
+          self.validate(repo);
+          let input = self.get_text();
+          if input.is_some() {
+            self.input_state.value = input;
+          }
+        }
+        None
+      },
+    }
+  }
+
+  pub fn render(&mut self, f: &mut Frame<'_>, area: Rect) {
+    f.render_widget(&self.text_input, area);
+  }
+}
@@ -0,0 +1,84 @@
+use std::{
+  collections::{HashMap, VecDeque},
+  fs,
+  path::PathBuf,
+};
+
+use tracing::error;
+
+use crate::utils::get_state_dir;
+
+const MAX_ENTRIES: usize = 50;
+const HISTORY_FILE: &str = "input_history.json";
+
+/// Previously submitted values for an input, most recent first, persisted between sessions under a namespace.
+#[derive(Debug, Default)]
+pub struct InputHistory {
+  namespace: &'static str,
+  submit_history: VecDeque<String>,
+  history_cursor: Option<usize>,
+}
+
+impl InputHistory {
+  pub fn load(namespace: &'static str) -> Self {
+    let submit_history = read_all_history().remove(namespace).unwrap_or_default();
+    InputHistory { namespace, submit_history, history_cursor: None }
+  }
+
+  /// Step back to an older entry, returns the entry to display.
+  pub fn older(&mut self) -> Option<&str> {
+    if self.submit_history.is_empty() {
+      return None;
+    }
+    let cursor = self.history_cursor.map_or(0, |cursor| (cursor + 1).min(self.submit_history.len() - 1));
+    self.history_cursor = Some(cursor);
+    self.submit_history.get(cursor).map(String::as_str)
+  }
+
+  /// Step forward to a newer entry, returns `None` once we are back at the blank input.
+  pub fn newer(&mut self) -> Option<&str> {
+    self.history_cursor = match self.history_cursor {
+      None | Some(0) => None,
+      Some(cursor) => Some(cursor - 1),
+    };
+    self.submit_history.get(self.history_cursor?).map(String::as_str)
+  }
+
+  pub fn reset_cursor(&mut self) {
+    self.history_cursor = None;
+  }
+
+  pub fn push(&mut self, value: String) {
+    self.reset_cursor();
+    self.submit_history.retain(|existing| *existing != value);
+    self.submit_history.push_front(value);
+    self.submit_history.truncate(MAX_ENTRIES);
+    self.save();
+  }
+
+  fn save(&self) {
+    let mut all_history = read_all_history();
+    all_history.insert(String::from(self.namespace), self.submit_history.clone());
+    let result = fs::create_dir_all(get_state_dir())
+      .map_err(|err| err.to_string())
+      .and_then(|_| serde_json::to_string(&all_history).map_err(|err| err.to_string()))
+      .and_then(|json| fs::write(history_path(), json).map_err(|err| err.to_string()));
+    if let Err(err) = result {
+      error!("Failed to save input history: {}", err);
+    }
+  }
+}
+
+fn history_path() -> PathBuf {
+  get_state_dir().join(HISTORY_FILE)
+}
+
+fn read_all_history() -> HashMap<String, VecDeque<String>> {
+  let Ok(content) = fs::read_to_string(history_path()) else {
+    return HashMap::new();
+  };
+  serde_json::from_str(&content).unwrap_or_else(|err| {
+    error!("Failed to parse input history: {}", err);
+    HashMap::new()
+  })
+}
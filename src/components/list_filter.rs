@@ -0,0 +1,109 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{action::Action, components::ui::search::fuzzy_score};
+
+/// A case-insensitive substring filter over a list, or a fuzzy search that also ranks the matches. The underlying
+/// items are never modified, only the indices of the visible items are tracked.
+#[derive(Debug, Default)]
+pub struct ListFilter {
+  query: String,
+  editing: bool,
+  // Searching ranks matches best first rather than keeping the list order
+  ranked: bool,
+  filtered_indices: Vec<usize>,
+}
+
+impl ListFilter {
+  pub fn is_active(&self) -> bool {
+    !self.query.is_empty()
+  }
+
+  pub fn query(&self) -> &str {
+    &self.query
+  }
+
+  pub fn is_editing(&self) -> bool {
+    self.editing
+  }
+
+  pub fn start_editing(&mut self) {
+    self.editing = true;
+  }
+
+  /// Replace the query with a fuzzy search, `apply` must be called to rank the items.
+  pub fn search(&mut self, query: &str) {
+    self.query = String::from(query);
+    self.editing = false;
+    self.ranked = true;
+  }
+
+  pub fn clear(&mut self) {
+    self.query.clear();
+    self.editing = false;
+    self.ranked = false;
+    self.filtered_indices.clear();
+  }
+
+  /// Recompute the visible items, `haystacks` returns the strings an item can be matched on.
+  pub fn apply<T, F: Fn(&T) -> Vec<&str>>(&mut self, items: &[T], haystacks: F) {
+    if self.ranked {
+      let mut scored: Vec<(usize, u32)> = items
+        .iter()
+        .enumerate()
+        .filter_map(|(index, item)| {
+          Some((index, haystacks(item).iter().filter_map(|h| fuzzy_score(&self.query, h)).max()?))
+        })
+        .collect();
+      // Stable, so equally good matches keep their list order
+      scored.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+      self.filtered_indices = scored.into_iter().map(|(index, _)| index).collect();
+      return;
+    }
+    let query = self.query.to_lowercase();
+    self.filtered_indices = items
+      .iter()
+      .enumerate()
+      .filter(|(_, item)| haystacks(item).iter().any(|haystack| haystack.to_lowercase().contains(&query)))
+      .map(|(index, _)| index)
+      .collect();
+  }
+
+  /// Indices into the full item list of the items that should be shown.
+  pub fn visible_indices(&self, item_count: usize) -> Vec<usize> {
+    if !self.is_active() {
+      return (0..item_count).collect();
+    }
+    self.filtered_indices.clone()
+  }
+
+  pub fn title(&self, title: &str) -> String {
+    if !self.is_active() {
+      return String::from(title);
+    }
+    let kind = if self.ranked { "search" } else { "filter" };
+    format!("{} [{}: {}]", title, kind, self.query)
+  }
+
+  /// Edit the query, `Enter` keeps the filter and `Esc` clears it.
+  pub fn handle_key_event(&mut self, key: KeyEvent) -> Option<Action> {
+    match key {
+      KeyEvent { code: KeyCode::Esc, modifiers: _, kind: _, state: _ } => {
+        self.clear();
+        Some(Action::EndInputMode)
+      },
+      KeyEvent { code: KeyCode::Enter, modifiers: _, kind: _, state: _ } => {
+        self.editing = false;
+        Some(Action::EndInputMode)
+      },
+      KeyEvent { code: KeyCode::Backspace, modifiers: _, kind: _, state: _ } => {
+        self.query.pop();
+        None
+      },
+      KeyEvent { code: KeyCode::Char(c), modifiers: KeyModifiers::NONE | KeyModifiers::SHIFT, kind: _, state: _ } => {
+        self.query.push(c);
+        None
+      },
+      _ => None,
+    }
+  }
+}
@@ -0,0 +1,135 @@
+use std::time::{Duration, Instant};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Rect;
+
+use crate::action::Action;
+
+// A second click on the same item within this is a double click
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// What a click on the list did.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ListClick {
+  /// The item at the position was clicked.
+  Select(usize),
+  /// The selected item was clicked again, lists run their main action for it.
+  Activate,
+}
+
+/// Vim style motions shared by the lists, `gg` and `G` jump to the first and last item and page up and down move the
+/// selection by half the list's height. Clicking an item selects it.
+#[derive(Debug, Default)]
+pub struct ListNavigation {
+  // A `g` was pressed, another jumps to the top
+  pending_g: bool,
+  last_list_area: Option<Rect>,
+  last_click: Option<(Instant, usize)>,
+}
+
+impl ListNavigation {
+  /// The motion `key` completes, if any. Any other key cancels a pending `g`.
+  pub fn handle_key_event(&mut self, key: &KeyEvent) -> Option<Action> {
+    let pending_g = std::mem::take(&mut self.pending_g);
+    match (key.code, key.modifiers) {
+      (KeyCode::Char('g'), KeyModifiers::NONE) => {
+        self.pending_g = !pending_g;
+        pending_g.then_some(Action::SelectFirst)
+      },
+      (KeyCode::Char('G'), KeyModifiers::NONE | KeyModifiers::SHIFT) => Some(Action::SelectLast),
+      (KeyCode::PageDown, KeyModifiers::NONE) => Some(Action::PageDown),
+      (KeyCode::PageUp, KeyModifiers::NONE) => Some(Action::PageUp),
+      _ => None,
+    }
+  }
+
+  /// The item a left click landed on, given the list scrolled to `offset`. Clicks outside the list's items are ignored.
+  pub fn handle_mouse_event(&mut self, mouse: &MouseEvent, offset: usize) -> Option<ListClick> {
+    if mouse.kind != MouseEventKind::Down(MouseButton::Left) {
+      return None;
+    }
+    let area = self.last_list_area?;
+    // The borders take a row from the top and bottom
+    let first_row = area.y + 1;
+    let last_row = (area.y + area.height).checked_sub(2)?;
+    if mouse.row < first_row || mouse.row > last_row || mouse.column < area.x || mouse.column >= area.x + area.width {
+      return None;
+    }
+    let position = offset + usize::from(mouse.row - first_row);
+    let now = Instant::now();
+    let double_click = self.last_click.is_some_and(|(time, last_position)| {
+      last_position == position && now.duration_since(time) < DOUBLE_CLICK_INTERVAL
+    });
+    if double_click {
+      self.last_click = None;
+      return Some(ListClick::Activate);
+    }
+    self.last_click = Some((now, position));
+    Some(ListClick::Select(position))
+  }
+
+  /// Called with the list's area, including its borders, each time it is rendered.
+  pub fn set_rendered_area(&mut self, area: Rect) {
+    self.last_list_area = Some(area);
+  }
+
+  /// Where `action` moves the selection from `position` in a list of `len` items, `None` for other actions.
+  pub fn target_position(&self, action: &Action, position: usize, len: usize) -> Option<usize> {
+    let last = len.checked_sub(1)?;
+    let height = self.last_list_area.map_or(0, |area| area.height);
+    // The borders take a row from the top and bottom
+    let half_page = (usize::from(height.saturating_sub(2)) / 2).max(1);
+    match action {
+      Action::SelectFirst => Some(0),
+      Action::SelectLast => Some(last),
+      Action::SelectPosition(target) => Some((*target).min(last)),
+      Action::PageDown => Some((position + half_page).min(last)),
+      Action::PageUp => Some(position.saturating_sub(half_page)),
+      _ => None,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // 20 items in a list showing 10 of them, with its borders
+  fn navigation() -> ListNavigation {
+    let mut navigation = ListNavigation::default();
+    navigation.set_rendered_area(Rect::new(0, 0, 40, 12));
+    navigation
+  }
+
+  #[test]
+  fn gg_and_shift_g_jump_to_the_ends() {
+    let mut navigation = navigation();
+    assert_eq!(navigation.handle_key_event(&KeyEvent::from(KeyCode::Char('g'))), None);
+    let select_first = navigation.handle_key_event(&KeyEvent::from(KeyCode::Char('g'))).unwrap();
+    assert_eq!(navigation.target_position(&select_first, 7, 20), Some(0));
+    let select_last = navigation.handle_key_event(&KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT)).unwrap();
+    assert_eq!(navigation.target_position(&select_last, 7, 20), Some(19));
+  }
+
+  #[test]
+  fn another_key_cancels_a_pending_g() {
+    let mut navigation = navigation();
+    navigation.handle_key_event(&KeyEvent::from(KeyCode::Char('g')));
+    navigation.handle_key_event(&KeyEvent::from(KeyCode::Char('j')));
+    assert_eq!(navigation.handle_key_event(&KeyEvent::from(KeyCode::Char('g'))), None);
+  }
+
+  #[test]
+  fn paging_moves_half_the_visible_height() {
+    let navigation = navigation();
+    assert_eq!(navigation.target_position(&Action::PageDown, 0, 20), Some(5));
+    assert_eq!(navigation.target_position(&Action::PageDown, 17, 20), Some(19));
+    assert_eq!(navigation.target_position(&Action::PageUp, 12, 20), Some(7));
+    assert_eq!(navigation.target_position(&Action::PageUp, 3, 20), Some(0));
+  }
+
+  #[test]
+  fn nothing_to_move_to_in_an_empty_list() {
+    assert_eq!(navigation().target_position(&Action::SelectLast, 0, 0), None);
+  }
+}
@@ -0,0 +1,71 @@
+use ratatui::{
+  buffer::{Buffer, Cell},
+  layout::{Constraint, Rect},
+  style::{Color, Style},
+  widgets::{List, ListItem, Row, Table, Widget},
+};
+
+/// The visible state of a list, so tests can assert on it in one go.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ListSnapshot {
+  /// Display names of the visible items, in order.
+  pub items: Vec<String>,
+  pub selected_index: usize,
+  pub has_staged_items: bool,
+  pub filter_active: bool,
+  pub staged_count: usize,
+}
+
+/// Renders `item` into a buffer `width` columns wide and describes it as text, each row followed by the styled runs
+/// in it like `  0..4 fg=Red DIM`, so a test can snapshot the whole rendering.
+pub fn item_snapshot(item: ListItem<'_>, width: u16) -> String {
+  let height = u16::try_from(item.height()).unwrap_or(u16::MAX);
+  let area = Rect::new(0, 0, width, height);
+  let mut buffer = Buffer::empty(area);
+  Widget::render(List::new([item]), area, &mut buffer);
+  describe_buffer(&buffer)
+}
+
+/// Like `item_snapshot` for a table row, laid out in columns of `widths`.
+pub fn row_snapshot(row: Row<'_>, widths: Vec<Constraint>, width: u16) -> String {
+  let area = Rect::new(0, 0, width, 1);
+  let mut buffer = Buffer::empty(area);
+  Widget::render(Table::new([row], widths), area, &mut buffer);
+  describe_buffer(&buffer)
+}
+
+fn describe_buffer(buffer: &Buffer) -> String {
+  let Rect { width, height, .. } = buffer.area;
+  let blank = Cell::default().style();
+  let mut snapshot = String::new();
+  for y in 0..height {
+    let row: Vec<&Cell> = (0..width).map(|x| &buffer[(x, y)]).collect();
+    let text: String = row.iter().map(|cell| cell.symbol()).collect();
+    snapshot.push_str(text.trim_end());
+    snapshot.push('\n');
+    let mut start = 0;
+    while start < row.len() {
+      let style = row[start].style();
+      let end = (start..row.len()).find(|&x| row[x].style() != style).unwrap_or(row.len());
+      if style != blank {
+        snapshot.push_str(&format!("  {}..{} {}\n", start, end, describe_style(style)));
+      }
+      start = end;
+    }
+  }
+  snapshot
+}
+
+fn describe_style(style: Style) -> String {
+  let mut parts = Vec::new();
+  if let Some(fg) = style.fg.filter(|&fg| fg != Color::Reset) {
+    parts.push(format!("fg={}", fg));
+  }
+  if let Some(bg) = style.bg.filter(|&bg| bg != Color::Reset) {
+    parts.push(format!("bg={}", bg));
+  }
+  if !style.add_modifier.is_empty() {
+    parts.push(format!("{:?}", style.add_modifier));
+  }
+  parts.join(" ")
+}
@@ -0,0 +1,53 @@
+use std::time::Instant;
+
+const SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// A slow operation a list runs in the background, the list shows it is busy until the operation finishes.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum LoadingOperation {
+  #[default]
+  None,
+  /// Running the operation, described like `Fetching`, since the given time.
+  Processing(&'static str, Instant),
+}
+
+impl LoadingOperation {
+  pub fn processing(description: &'static str) -> Self {
+    LoadingOperation::Processing(description, Instant::now())
+  }
+
+  /// The spinner frame after `frame`, wrapping back to the first.
+  pub fn next_spinner_frame(frame: usize) -> usize {
+    (frame + 1) % SPINNER_FRAMES.len()
+  }
+
+  /// A suffix for the list title, e.g. ` (Fetching… ⠙ 3s)`, empty when nothing is running.
+  pub fn title_suffix(&self, spinner_frame: usize) -> String {
+    match self {
+      LoadingOperation::None => String::new(),
+      LoadingOperation::Processing(description, started_at) => {
+        let spinner = SPINNER_FRAMES[spinner_frame % SPINNER_FRAMES.len()];
+        format!(" ({}… {} {}s)", description, spinner, started_at.elapsed().as_secs())
+      },
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn the_spinner_wraps_after_the_last_frame() {
+    let frames: Vec<usize> =
+      std::iter::successors(Some(0), |&frame| Some(LoadingOperation::next_spinner_frame(frame))).take(12).collect();
+    assert_eq!(frames, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 1]);
+  }
+
+  #[test]
+  fn the_title_shows_the_spinner_and_elapsed_time() {
+    assert_eq!(LoadingOperation::None.title_suffix(1), "");
+    assert_eq!(LoadingOperation::processing("Fetching").title_suffix(1), " (Fetching… ⠙ 0s)");
+    assert_eq!(LoadingOperation::processing("Fetching").title_suffix(11), " (Fetching… ⠙ 0s)");
+  }
+}
@@ -0,0 +1,247 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent};
+use ratatui::{
+  layout::{Alignment, Constraint, Direction, Layout, Rect},
+  style::{Color, Modifier, Style},
+  text::Text,
+  widgets::{block::Title, Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+};
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::error;
+
+use crate::{
+  action::Action,
+  components::{
+    list_navigation::{ListClick, ListNavigation},
+    remote_branch_list::remote_branch_data_source::RemoteBranchDataSource,
+    traits::list_data_source::ListDataSource,
+    ui::{self, page_indicator::page_indicator, search::best_match},
+    Component,
+  },
+  error::Error,
+  git::git_repo::{GitRemoteBranch, GitRepo},
+  tui::Frame,
+};
+
+mod remote_branch_data_source;
+
+/// Lists remote tracking branches, checking one out creates or switches to the local branch tracking it.
+pub struct RemoteBranchList {
+  repo: Box<dyn GitRepo>,
+  action_tx: Option<UnboundedSender<Action>>,
+  error: Option<String>,
+  data_source: RemoteBranchDataSource,
+  branches: Vec<GitRemoteBranch>,
+  list_state: ListState,
+  selected_index: usize,
+  navigation: ListNavigation,
+}
+
+impl RemoteBranchList {
+  pub fn new(repo: Box<dyn GitRepo>) -> Self {
+    let mut remote_branch_list = RemoteBranchList {
+      repo,
+      action_tx: None,
+      error: None,
+      data_source: RemoteBranchDataSource::default(),
+      branches: Vec::new(),
+      list_state: ListState::default(),
+      selected_index: 0,
+      navigation: ListNavigation::default(),
+    };
+    let result = remote_branch_list.refresh();
+    remote_branch_list.maybe_handle_git_error(result.err());
+    remote_branch_list
+  }
+
+  fn refresh(&mut self) -> Result<(), Error> {
+    self.branches = self.data_source.load(&*self.repo)?;
+    self.selected_index = self.selected_index.min(self.branches.len().saturating_sub(1));
+    Ok(())
+  }
+
+  fn select_previous(&mut self) {
+    if self.branches.is_empty() {
+      return;
+    }
+    self.selected_index = self.selected_index.checked_sub(1).unwrap_or(self.branches.len() - 1);
+  }
+
+  fn select_next(&mut self) {
+    if self.selected_index + 1 >= self.branches.len() {
+      self.selected_index = 0;
+      return;
+    }
+    self.selected_index += 1;
+  }
+
+  fn checkout_selected(&mut self) -> Result<(), Error> {
+    let Some(selected) = self.branches.get(self.selected_index) else {
+      return Ok(());
+    };
+    // git creates a local branch tracking the remote one when checking out its short name
+    self.repo.checkout_branch_from_name(selected.short_name())?;
+    self.send_actions(vec![Action::Refresh]);
+    Ok(())
+  }
+
+  /// Creates the local branch tracking the selected one then shows it in the branch list.
+  fn checkout_tracking_selected(&mut self) -> Result<(), Error> {
+    let Some(selected) = self.branches.get(self.selected_index) else {
+      return Ok(());
+    };
+    self.repo.checkout_tracking_branch(selected)?;
+    let local_name = String::from(selected.short_name());
+    self.send_actions(vec![Action::Refresh, Action::ShowBranches, Action::SelectItemWithName(local_name)]);
+    Ok(())
+  }
+
+  fn send_actions(&self, actions: Vec<Action>) {
+    let Some(action_tx) = &self.action_tx else {
+      return;
+    };
+    for action in actions {
+      if let Err(err) = action_tx.send(action) {
+        error!("Failed to send {}: {}", err.0, err);
+      }
+    }
+  }
+
+  fn maybe_handle_git_error(&mut self, err: Option<Error>) {
+    if let Some(error) = err {
+      error!("{}", error);
+      self.error = Some(error.to_string());
+    }
+  }
+
+  fn render_list(&mut self, f: &mut Frame<'_>, area: Rect) {
+    self.navigation.set_rendered_area(area);
+    self.list_state.select(Some(self.selected_index));
+    let render_items: Vec<ListItem> = self.branches.iter().map(|branch| ListItem::new(branch.name.as_str())).collect();
+    let mut block = Block::default().title(self.data_source.title()).borders(Borders::ALL);
+    // The borders take a row from the top and bottom
+    let visible_height = usize::from(area.height.saturating_sub(2));
+    if let Some(page) = page_indicator(self.selected_index, render_items.len(), visible_height) {
+      block = block.title(Title::from(page).alignment(Alignment::Right));
+    }
+    let list = List::new(render_items)
+      .block(block)
+      .style(Style::default().fg(Color::White))
+      .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+      .highlight_symbol("→")
+      .repeat_highlight_symbol(true);
+    f.render_stateful_widget(list, area, &mut self.list_state);
+  }
+}
+
+impl Component for RemoteBranchList {
+  fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> color_eyre::Result<()> {
+    self.action_tx = Some(tx);
+    Ok(())
+  }
+
+  fn handle_key_events(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+    self.error = None;
+    if let Some(action) = self.navigation.handle_key_event(&key) {
+      return Ok(Some(action));
+    }
+    match key {
+      KeyEvent { code: KeyCode::Down, modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        Ok(Some(Action::SelectNextBranch))
+      },
+      KeyEvent { code: KeyCode::Up, modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        Ok(Some(Action::SelectPreviousBranch))
+      },
+      KeyEvent { code: KeyCode::Char('c' | 'C'), modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        Ok(Some(Action::CheckoutSelectedBranch))
+      },
+      KeyEvent { code: KeyCode::Enter, modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        Ok(Some(Action::CheckoutTrackingBranch))
+      },
+      KeyEvent { code: KeyCode::Char('r' | 'R'), modifiers: KeyModifiers::CONTROL, kind: _, state: _ } => {
+        Ok(Some(Action::Refresh))
+      },
+      _ => Ok(None),
+    }
+  }
+
+  fn handle_mouse_events(&mut self, mouse: MouseEvent) -> color_eyre::Result<Option<Action>> {
+    match self.navigation.handle_mouse_event(&mouse, self.list_state.offset()) {
+      Some(ListClick::Select(position)) => Ok(Some(Action::SelectPosition(position))),
+      Some(ListClick::Activate) => Ok(Some(Action::CheckoutTrackingBranch)),
+      None => Ok(None),
+    }
+  }
+
+  fn update(&mut self, action: Action) -> color_eyre::Result<Option<Action>> {
+    match action {
+      Action::SearchQueryChanged(query) => {
+        if let Some(best) = best_match(&query, self.branches.iter().map(|branch| branch.name.as_str())) {
+          self.selected_index = best;
+        }
+      },
+      Action::SelectNextBranch => self.select_next(),
+      Action::SelectPreviousBranch => self.select_previous(),
+      Action::SelectFirst | Action::SelectLast | Action::SelectPosition(_) | Action::PageDown | Action::PageUp => {
+        if let Some(index) = self.navigation.target_position(&action, self.selected_index, self.branches.len()) {
+          self.selected_index = index;
+        }
+      },
+      Action::CheckoutSelectedBranch => {
+        let result = self.checkout_selected();
+        self.maybe_handle_git_error(result.err());
+      },
+      Action::CheckoutTrackingBranch => {
+        let result = self.checkout_tracking_selected();
+        self.maybe_handle_git_error(result.err());
+      },
+      Action::Refresh | Action::FetchCompleted(Ok(())) => {
+        let result = self.refresh();
+        self.maybe_handle_git_error(result.err());
+      },
+      _ => {},
+    }
+    Ok(None)
+  }
+
+  fn title(&self) -> &str {
+    "Remote Branches"
+  }
+
+  fn item_counts(&self) -> (usize, usize) {
+    (self.branches.len(), self.branches.len())
+  }
+
+  fn instructions(&self) -> Vec<&'static str> {
+    let mut instructions = vec!["esc: Quit", "tab: Switch view"];
+    if !self.branches.is_empty() {
+      instructions.push("c: Checkout");
+      instructions.push("enter: Checkout and track");
+    }
+    instructions.push("^ + r: Refresh");
+    instructions
+  }
+
+  fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> color_eyre::Result<()> {
+    let instructions = self.instructions();
+    // The layout margin takes a column from each side
+    let footer_height = ui::instruction_footer::preferred_height(&instructions, area.width.saturating_sub(2));
+    let error_height = self.error.as_ref().map_or(0, |error| error.lines().count() + 2);
+    let layout = Layout::new(Direction::Vertical, [
+      Constraint::Min(1),
+      Constraint::Length(u16::try_from(error_height)?),
+      Constraint::Length(footer_height),
+    ])
+    .margin(1)
+    .split(area);
+    self.render_list(f, layout[0]);
+    if let Some(error) = &self.error {
+      let component = Paragraph::new(Text::from(error.as_str()))
+        .block(Block::bordered().title("Error"))
+        .style(Style::from(Color::Red))
+        .wrap(Wrap { trim: true });
+      f.render_widget(component, layout[1]);
+    }
+    ui::instruction_footer::render(f, layout[2], &instructions);
+    Ok(())
+  }
+}
@@ -0,0 +1,21 @@
+use crate::{
+  components::traits::list_data_source::ListDataSource,
+  error::Error,
+  git::git_repo::{GitRemoteBranch, GitRepo},
+};
+
+/// Loads remote tracking branches sorted by name.
+#[derive(Debug, Default)]
+pub struct RemoteBranchDataSource {}
+
+impl ListDataSource<GitRemoteBranch> for RemoteBranchDataSource {
+  fn title(&self) -> &str {
+    "Remote Branches"
+  }
+
+  fn fetch_items(&mut self, repo: &dyn GitRepo) -> Result<Vec<GitRemoteBranch>, Error> {
+    let mut branches = repo.remote_branches()?;
+    branches.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(branches)
+  }
+}
@@ -0,0 +1,329 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent};
+use ratatui::{
+  layout::{Alignment, Constraint, Direction, Layout, Rect},
+  style::{Color, Modifier, Style},
+  text::Text,
+  widgets::{block::Title, Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+};
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::error;
+
+use crate::{
+  action::Action,
+  components::{
+    list_navigation::{ListClick, ListNavigation},
+    remote_list::{
+      remote_data_source::RemoteDataSource,
+      remote_input_handler::{RemoteInput, RemoteInputHandler},
+    },
+    traits::list_data_source::ListDataSource,
+    ui::{self, page_indicator::page_indicator, search::best_match},
+    Component,
+  },
+  config::Config,
+  error::Error,
+  git::git_repo::{GitRemote, GitRepo},
+  tui::Frame,
+  utils::browser,
+};
+
+mod remote_data_source;
+mod remote_input_handler;
+mod remote_item;
+
+/// Lists the repository's remotes so they can be added, renamed, removed or opened in the browser.
+pub struct RemoteList {
+  repo: Box<dyn GitRepo>,
+  action_tx: Option<UnboundedSender<Action>>,
+  error: Option<String>,
+  data_source: RemoteDataSource,
+  remotes: Vec<GitRemote>,
+  list_state: ListState,
+  selected_index: usize,
+  navigation: ListNavigation,
+  input: RemoteInput,
+  editing: bool,
+}
+
+impl RemoteList {
+  pub fn new(repo: Box<dyn GitRepo>, config: &Config) -> Self {
+    let mut remote_list = RemoteList {
+      repo,
+      action_tx: None,
+      error: None,
+      data_source: RemoteDataSource::default(),
+      remotes: Vec::new(),
+      list_state: ListState::default(),
+      selected_index: 0,
+      navigation: ListNavigation::default(),
+      input: RemoteInput::new(RemoteInputHandler::default(), "remote_input", config.theme.clone()),
+      editing: false,
+    };
+    let result = remote_list.refresh();
+    remote_list.maybe_handle_git_error(result.err());
+    remote_list
+  }
+
+  fn refresh(&mut self) -> Result<(), Error> {
+    self.remotes = self.data_source.load(&*self.repo)?;
+    self.selected_index = self.selected_index.min(self.remotes.len().saturating_sub(1));
+    Ok(())
+  }
+
+  fn select_previous(&mut self) {
+    if self.remotes.is_empty() {
+      return;
+    }
+    self.selected_index = self.selected_index.checked_sub(1).unwrap_or(self.remotes.len() - 1);
+  }
+
+  fn select_next(&mut self) {
+    if self.selected_index + 1 >= self.remotes.len() {
+      self.selected_index = 0;
+      return;
+    }
+    self.selected_index += 1;
+  }
+
+  fn existing_names(&self) -> Vec<String> {
+    self.remotes.iter().map(|remote| remote.name.clone()).collect()
+  }
+
+  fn init_add_input(&mut self) -> Option<Action> {
+    let existing_names = self.existing_names();
+    self.input.handler_mut().set_adding(existing_names);
+    self.input.init_style();
+    self.editing = true;
+    Some(Action::StartInputMode)
+  }
+
+  fn init_rename_input(&mut self) -> Option<Action> {
+    let old_name = self.remotes.get(self.selected_index)?.name.clone();
+    let existing_names = self.existing_names();
+    self.input.handler_mut().set_renaming(old_name.clone(), existing_names);
+    self.input.init_style();
+    self.input.set_text(old_name);
+    self.editing = true;
+    Some(Action::StartInputMode)
+  }
+
+  fn confirm_remove(&self) -> Option<Action> {
+    let selected = self.remotes.get(self.selected_index)?;
+    let message = format!("Remove remote {} and its remote branches?", selected.name);
+    Some(Action::RequestConfirmation(message, Box::new(Action::RemoveRemote)))
+  }
+
+  fn remove_selected(&mut self) -> Result<(), Error> {
+    let Some(selected) = self.remotes.get(self.selected_index) else {
+      return Ok(());
+    };
+    self.repo.remove_remote(&selected.name)?;
+    self.remote_branches_changed();
+    self.refresh()
+  }
+
+  fn add_remote(&mut self, name: &str, url: &str) -> Result<(), Error> {
+    self.repo.add_remote(name, url)?;
+    self.refresh()?;
+    if let Some(index) = self.remotes.iter().position(|remote| remote.name == name) {
+      self.selected_index = index;
+    }
+    Ok(())
+  }
+
+  fn rename_remote(&mut self, old_name: &str, new_name: &str) -> Result<(), Error> {
+    self.repo.rename_remote(old_name, new_name)?;
+    self.remote_branches_changed();
+    self.refresh()
+  }
+
+  /// Removing or renaming a remote renames or removes its remote branches, and the upstreams tracking them.
+  fn remote_branches_changed(&self) {
+    if let Some(action_tx) = &self.action_tx {
+      if let Err(err) = action_tx.send(Action::Refresh) {
+        error!("Failed to request a refresh: {}", err);
+      }
+    }
+  }
+
+  fn open_selected_in_browser(&mut self) {
+    let Some(selected) = self.remotes.get(self.selected_index) else {
+      return;
+    };
+    let Some(url) = browser::remote_web_url(&selected.fetch_url) else {
+      self.error = Some(format!("{} is not hosted on the web", selected.fetch_url));
+      return;
+    };
+    if let Err(err) = browser::open(&url) {
+      error!("Could not open {}: {}", url, err);
+      self.error = Some(format!("Could not open {}: {}", url, err));
+    }
+  }
+
+  fn maybe_handle_git_error(&mut self, err: Option<Error>) {
+    if let Some(error) = err {
+      error!("{}", error);
+      self.error = Some(error.to_string());
+    }
+  }
+
+  fn render_list(&mut self, f: &mut Frame<'_>, area: Rect) {
+    self.navigation.set_rendered_area(area);
+    self.list_state.select(Some(self.selected_index));
+    let render_items: Vec<ListItem> = self.remotes.iter().map(remote_item::render).collect();
+    let mut block = Block::default().title(self.data_source.title()).borders(Borders::ALL);
+    // The borders take a row from the top and bottom
+    let visible_height = usize::from(area.height.saturating_sub(2));
+    if let Some(page) = page_indicator(self.selected_index, render_items.len(), visible_height) {
+      block = block.title(Title::from(page).alignment(Alignment::Right));
+    }
+    let list = List::new(render_items)
+      .block(block)
+      .style(Style::default().fg(Color::White))
+      .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+      .highlight_symbol("→")
+      .repeat_highlight_symbol(true);
+    f.render_stateful_widget(list, area, &mut self.list_state);
+  }
+}
+
+impl Component for RemoteList {
+  fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> color_eyre::Result<()> {
+    self.action_tx = Some(tx);
+    Ok(())
+  }
+
+  fn handle_key_events(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+    self.error = None;
+    if self.editing {
+      return Ok(self.input.handle_key_event(key, &*self.repo));
+    }
+    if let Some(action) = self.navigation.handle_key_event(&key) {
+      return Ok(Some(action));
+    }
+    match key {
+      KeyEvent { code: KeyCode::Down, modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        Ok(Some(Action::SelectNextRemote))
+      },
+      KeyEvent { code: KeyCode::Up, modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        Ok(Some(Action::SelectPreviousRemote))
+      },
+      KeyEvent { code: KeyCode::Enter, modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        self.open_selected_in_browser();
+        Ok(None)
+      },
+      KeyEvent { code: KeyCode::Char('a' | 'A'), modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        Ok(self.init_add_input())
+      },
+      KeyEvent { code: KeyCode::Char('r' | 'R'), modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        Ok(self.init_rename_input())
+      },
+      KeyEvent { code: KeyCode::Char('d' | 'D'), modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        Ok(self.confirm_remove())
+      },
+      KeyEvent { code: KeyCode::Char('r' | 'R'), modifiers: KeyModifiers::CONTROL, kind: _, state: _ } => {
+        Ok(Some(Action::Refresh))
+      },
+      _ => Ok(None),
+    }
+  }
+
+  fn handle_mouse_events(&mut self, mouse: MouseEvent) -> color_eyre::Result<Option<Action>> {
+    match self.navigation.handle_mouse_event(&mouse, self.list_state.offset()) {
+      Some(ListClick::Select(position)) => Ok(Some(Action::SelectPosition(position))),
+      Some(ListClick::Activate) => {
+        self.open_selected_in_browser();
+        Ok(None)
+      },
+      None => Ok(None),
+    }
+  }
+
+  fn update(&mut self, action: Action) -> color_eyre::Result<Option<Action>> {
+    match action {
+      Action::SearchQueryChanged(query) => {
+        if let Some(best) = best_match(&query, self.remotes.iter().map(|remote| remote.name.as_str())) {
+          self.selected_index = best;
+        }
+      },
+      Action::SelectNextRemote => self.select_next(),
+      Action::SelectPreviousRemote => self.select_previous(),
+      Action::SelectFirst | Action::SelectLast | Action::SelectPosition(_) | Action::PageDown | Action::PageUp => {
+        if let Some(index) = self.navigation.target_position(&action, self.selected_index, self.remotes.len()) {
+          self.selected_index = index;
+        }
+      },
+      Action::EndInputMode => self.editing = false,
+      Action::AddRemote(name, url) => {
+        self.editing = false;
+        let result = self.add_remote(&name, &url);
+        self.maybe_handle_git_error(result.err());
+        return Ok(Some(Action::EndInputMode));
+      },
+      Action::RenameRemote(old_name, new_name) => {
+        self.editing = false;
+        let result = self.rename_remote(&old_name, &new_name);
+        self.maybe_handle_git_error(result.err());
+        return Ok(Some(Action::EndInputMode));
+      },
+      Action::RemoveRemote => {
+        let result = self.remove_selected();
+        self.maybe_handle_git_error(result.err());
+      },
+      Action::Refresh | Action::FetchCompleted(Ok(())) => {
+        let result = self.refresh();
+        self.maybe_handle_git_error(result.err());
+      },
+      _ => {},
+    }
+    Ok(None)
+  }
+
+  fn title(&self) -> &str {
+    "Remotes"
+  }
+
+  fn item_counts(&self) -> (usize, usize) {
+    (self.remotes.len(), self.remotes.len())
+  }
+
+  fn instructions(&self) -> Vec<&'static str> {
+    if self.editing {
+      return vec!["esc: Cancel", "enter: Submit"];
+    }
+    let mut instructions = vec!["esc: Quit", "tab: Switch view", "a: Add"];
+    if !self.remotes.is_empty() {
+      instructions.push("enter: Open in browser");
+      instructions.push("r: Rename");
+      instructions.push("d: Remove");
+    }
+    instructions.push("^ + r: Refresh");
+    instructions
+  }
+
+  fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> color_eyre::Result<()> {
+    let instructions = self.instructions();
+    // The layout margin takes a column from each side
+    let footer_height = ui::instruction_footer::preferred_height(&instructions, area.width.saturating_sub(2));
+    let middle_height = if self.editing { 3 } else { self.error.as_ref().map_or(0, |error| error.lines().count() + 2) };
+    let layout = Layout::new(Direction::Vertical, [
+      Constraint::Min(1),
+      Constraint::Length(u16::try_from(middle_height)?),
+      Constraint::Length(footer_height),
+    ])
+    .margin(1)
+    .split(area);
+    self.render_list(f, layout[0]);
+    if self.editing {
+      self.input.render(f, layout[1]);
+    } else if let Some(error) = &self.error {
+      let component = Paragraph::new(Text::from(error.as_str()))
+        .block(Block::bordered().title("Error"))
+        .style(Style::from(Color::Red))
+        .wrap(Wrap { trim: true });
+      f.render_widget(component, layout[1]);
+    }
+    ui::instruction_footer::render(f, layout[2], &instructions);
+    Ok(())
+  }
+}
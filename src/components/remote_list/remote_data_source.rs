@@ -0,0 +1,21 @@
+use crate::{
+  components::traits::list_data_source::ListDataSource,
+  error::Error,
+  git::git_repo::{GitRemote, GitRepo},
+};
+
+/// Loads remotes sorted by name.
+#[derive(Debug, Default)]
+pub struct RemoteDataSource {}
+
+impl ListDataSource<GitRemote> for RemoteDataSource {
+  fn title(&self) -> &str {
+    "Remotes"
+  }
+
+  fn fetch_items(&mut self, repo: &dyn GitRepo) -> Result<Vec<GitRemote>, Error> {
+    let mut remotes = repo.list_remotes()?;
+    remotes.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(remotes)
+  }
+}
@@ -0,0 +1,60 @@
+use crate::{
+  action::Action,
+  components::{generic_input::GenericInput, traits::input_handler::InputHandler},
+  git::git_repo::{GitRemote, GitRepo},
+};
+
+pub type RemoteInput = GenericInput<GitRemote, RemoteInputHandler>;
+
+/// Adds a remote from `<name> <url>`, or renames the remote that was selected when the input was opened.
+#[derive(Debug, Default)]
+pub struct RemoteInputHandler {
+  // The remote being renamed, `None` when adding one
+  renaming: Option<String>,
+  // Names already taken, set when the input is opened
+  existing_names: Vec<String>,
+}
+
+impl RemoteInputHandler {
+  pub fn set_adding(&mut self, existing_names: Vec<String>) {
+    self.renaming = None;
+    self.existing_names = existing_names;
+  }
+
+  pub fn set_renaming(&mut self, old_name: String, existing_names: Vec<String>) {
+    self.renaming = Some(old_name);
+    self.existing_names = existing_names;
+  }
+
+  fn is_new_name(&self, name: &str) -> bool {
+    !name.is_empty() && !self.existing_names.iter().any(|existing| existing == name)
+  }
+}
+
+impl InputHandler<GitRemote> for RemoteInputHandler {
+  fn validate_input(&self, _repo: &dyn GitRepo, input: &str) -> bool {
+    let mut parts = input.split_whitespace();
+    match (&self.renaming, parts.next(), parts.next(), parts.next()) {
+      (Some(_), Some(name), None, None) => self.is_new_name(name),
+      (None, Some(name), Some(_url), None) => self.is_new_name(name),
+      _ => false,
+    }
+  }
+
+  fn create_submit_action(&self, input: String) -> Action {
+    match &self.renaming {
+      Some(old_name) => Action::RenameRemote(old_name.clone(), input),
+      None => {
+        let (name, url) = input.split_once(char::is_whitespace).unwrap_or((&input, ""));
+        Action::AddRemote(String::from(name), String::from(url.trim()))
+      },
+    }
+  }
+
+  fn get_input_prompt(&self) -> Option<&str> {
+    match self.renaming {
+      Some(_) => Some("Rename remote:"),
+      None => Some("Add remote (<name> <url>):"),
+    }
+  }
+}
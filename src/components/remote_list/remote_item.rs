@@ -0,0 +1,16 @@
+use ratatui::{
+  style::{Modifier, Style},
+  text::{Line, Span},
+  widgets::ListItem,
+};
+
+use crate::git::git_repo::GitRemote;
+
+pub fn render(remote: &GitRemote) -> ListItem<'_> {
+  let dim = Style::default().add_modifier(Modifier::DIM);
+  let mut parts = vec![Span::raw(remote.name.as_str()), Span::styled(format!(" {}", remote.fetch_url), dim)];
+  if remote.push_url != remote.fetch_url {
+    parts.push(Span::styled(format!(" (push {})", remote.push_url), dim));
+  }
+  ListItem::from(Line::from(parts))
+}
@@ -1,69 +1,1035 @@
+use std::{
+  collections::HashMap,
+  sync::Arc,
+  time::{Duration, Instant},
+};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent};
 use ratatui::{
-  layout::Rect,
+  layout::{Alignment, Constraint, Direction, Layout, Rect},
   style::{Color, Modifier, Style},
-  text::{Line, Span},
-  widgets::{Block, Borders, List, ListItem, ListState},
-  Frame,
+  text::{Line, Text},
+  widgets::{block::Title, Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
 };
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::error;
 
 use crate::{
-  components::Component,
+  action::Action,
+  app::undo_stack::UndoEntry,
+  components::{
+    list_filter::ListFilter,
+    list_navigation::{ListClick, ListNavigation},
+    stash_list::{
+      instruction_footer::InstructionFooter,
+      pathspec_input_handler::{PathspecInput, PathspecInputHandler},
+      stash_branch_input_handler::{StashBranchInput, StashBranchInputHandler},
+      stash_data_source::StashDataSource,
+      stash_input_handler::{StashInput, StashInputHandler},
+      stash_item::StashItem,
+      target_branch_selector::TargetBranchSelector,
+    },
+    traits::{list_data_source::ListDataSource, managed_item::ManagedItem},
+    ui::{self, ansi::styled_lines, page_indicator::page_indicator},
+    Component,
+  },
+  config::{theme::Theme, Config},
+  error::{failed_deletions, Error},
   git::git_repo::{GitRepo, GitStash},
+  tui::Frame,
+  utils::accessibility,
 };
 
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
-struct StashItem {
-  git_stash: GitStash,
+mod instruction_footer;
+mod pathspec_input_handler;
+mod stash_branch_input_handler;
+mod stash_data_source;
+mod stash_input_handler;
+mod stash_item;
+mod target_branch_selector;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Mode {
+  Selection,
+  Input,
+  BranchInput,
+  PathspecInput,
+}
+
+pub struct StashList {
+  mode: Mode,
+  repo: Box<dyn GitRepo>,
+  action_tx: Option<UnboundedSender<Action>>,
+  error: Option<String>,
+  // List state
+  data_source: StashDataSource,
+  stashes: Vec<StashItem>,
+  list_state: ListState,
+  // Index into the visible stashes, which may be filtered
+  selected_index: usize,
+  navigation: ListNavigation,
+  filter: ListFilter,
+  // Conflict prone files for a stash, keyed by stash id
+  conflict_files: HashMap<String, Vec<String>>,
+  refresh_debounce: Duration,
+  last_refresh: Option<Instant>,
+  // From `get_stash_count` when the stashes were last loaded, they are only reloaded when it changes
+  stash_count: Option<usize>,
+  last_announced: Option<String>,
+  // File stats summaries, keyed by stash id and loaded when first shown
+  stash_stats: HashMap<String, String>,
+  // Shows the selected stash's patch beside the list
+  show_diff: bool,
+  diff_scroll: u16,
+  // Styled patch lines keyed by stash id, cleared on refresh
+  stash_diffs: HashMap<String, Vec<(String, Style)>>,
+  // Components
+  stash_input: StashInput,
+  branch_input: StashBranchInput,
+  pathspec_input: PathspecInput,
+  target_branch_selector: Option<TargetBranchSelector>,
+  instruction_footer: InstructionFooter,
+  theme: Arc<Theme>,
 }
 
-impl StashItem {
-  pub fn new(git_stash: GitStash) -> Self {
-    StashItem { git_stash }
+impl StashList {
+  pub fn new(repo: Box<dyn GitRepo>, config: &Config) -> Self {
+    let mut stash_list = StashList {
+      mode: Mode::Selection,
+      repo,
+      action_tx: None,
+      error: None,
+      data_source: StashDataSource::default(),
+      stashes: Vec::new(),
+      list_state: ListState::default(),
+      selected_index: 0,
+      navigation: ListNavigation::default(),
+      filter: ListFilter::default(),
+      conflict_files: HashMap::new(),
+      refresh_debounce: Duration::from_millis(config.refresh_debounce_ms),
+      last_refresh: None,
+      stash_count: None,
+      last_announced: None,
+      stash_stats: HashMap::new(),
+      show_diff: false,
+      diff_scroll: 0,
+      stash_diffs: HashMap::new(),
+      stash_input: StashInput::new(
+        StashInputHandler { no_verify: config.no_verify_stash, ..Default::default() },
+        "stash_input",
+        config.theme.clone(),
+      ),
+      branch_input: StashBranchInput::new(
+        StashBranchInputHandler::default(),
+        "stash_branch_input",
+        config.theme.clone(),
+      ),
+      pathspec_input: PathspecInput::new(PathspecInputHandler::default(), "pathspec_input", config.theme.clone()),
+      theme: config.theme.clone(),
+      target_branch_selector: None,
+      instruction_footer: InstructionFooter::default(),
+    };
+    let result = stash_list.refresh();
+    stash_list.maybe_handle_git_error(result.err());
+    stash_list
   }
 
-  pub fn render(&self) -> ListItem {
-    let mut text = Line::default();
-    let mut parts = Vec::new();
-    let index = Span::styled(self.git_stash.index.to_string(), Style::default());
-    parts.push(index);
+  pub fn clear_error(&mut self) {
+    self.error = None;
+  }
 
-    let message =
-      Span::styled(format!(" {}", self.git_stash.message.clone()), Style::default().add_modifier(Modifier::DIM));
-    parts.push(message);
+  fn visible_indices(&self) -> Vec<usize> {
+    self.filter.visible_indices(self.stashes.len())
+  }
 
-    let id =
-      Span::styled(format!(" ({})", self.git_stash.stash_id.clone()), Style::default().add_modifier(Modifier::DIM));
-    parts.push(id);
+  pub fn select_previous(&mut self) {
+    let visible_count = self.visible_indices().len();
+    if visible_count == 0 {
+      return;
+    }
+    if self.selected_index == 0 || self.selected_index >= visible_count {
+      self.selected_index = visible_count - 1;
+      return;
+    }
+    self.selected_index -= 1;
+  }
 
-    text = text.spans(parts);
-    ListItem::from(text)
+  pub fn select_next(&mut self) {
+    if self.selected_index + 1 >= self.visible_indices().len() {
+      self.selected_index = 0;
+      return;
+    }
+    self.selected_index += 1;
   }
-}
 
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
-pub struct StashList {
-  stashes: Vec<StashItem>,
-  list_state: ListState,
-}
+  fn apply_motion(&mut self, action: &Action) {
+    let visible_count = self.visible_indices().len();
+    if let Some(position) = self.navigation.target_position(action, self.selected_index, visible_count) {
+      self.selected_index = position;
+    }
+  }
 
-impl StashList {
-  pub fn new(mut repo: Box<dyn GitRepo>) -> Self {
-    let stashes: Vec<StashItem> =
-      repo.stashes().unwrap().iter().map(|git_stash| StashItem::new(git_stash.clone())).collect();
-    StashList { stashes, list_state: ListState::default() }
+  fn announce_selection(&mut self) {
+    let Some(selected) = self.get_selected_stash() else {
+      return;
+    };
+    let announcement = format!(
+      "Selected stash {} of {}: {}",
+      self.selected_index + 1,
+      self.visible_indices().len(),
+      selected.git_stash.display_name()
+    );
+    if self.last_announced.as_ref() != Some(&announcement) {
+      accessibility::announce(&announcement);
+      self.last_announced = Some(announcement);
+    }
   }
-}
 
-impl Component for StashList {
-  fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> color_eyre::Result<()> {
-    let render_items: Vec<ListItem> = self.stashes.iter().map(|stash| stash.render()).collect();
+  fn get_selected_stash(&self) -> Option<&StashItem> {
+    let index = *self.visible_indices().get(self.selected_index)?;
+    self.stashes.get(index)
+  }
+
+  fn apply_filter(&mut self) {
+    // Match on the human readable parts of the stash rather than its id
+    self.filter.apply(&self.stashes, |item| vec![item.git_stash.display_name(), item.git_stash.branch_name.as_str()]);
+    self.selected_index = self.selected_index.min(self.visible_indices().len().saturating_sub(1));
+  }
+
+  fn clear_filter(&mut self) {
+    // The selection indexes the visible stashes, keep it on the chosen one once they all show again
+    let selected = self.visible_indices().get(self.selected_index).copied();
+    self.filter.clear();
+    self.selected_index = selected.unwrap_or(0);
+  }
+
+  /// Coalesces refresh requests that arrive straight after another refresh, e.g. from a watcher and an operation.
+  fn refreshed_recently(&self) -> bool {
+    self.last_refresh.is_some_and(|last_refresh| last_refresh.elapsed() < self.refresh_debounce)
+  }
+
+  /// Reloads the stashes when their count has changed, as it does for every stash, drop and pop.
+  fn refresh(&mut self) -> Result<(), Error> {
+    self.last_refresh = Some(Instant::now());
+    let count = self.repo.get_stash_count()?;
+    if self.stash_count == Some(count) {
+      return Ok(());
+    }
+    // Most of the time there are no stashes, so avoid parsing the list when there is nothing in it
+    let stashes = if count == 0 { Vec::new() } else { self.data_source.load(&*self.repo)? };
+    self.set_stashes(stashes)
+  }
+
+  /// Reloads the stashes whatever their count, they may have been changed outside the app.
+  fn reload(&mut self) -> Result<(), Error> {
+    self.last_refresh = Some(Instant::now());
+    let stashes = self.data_source.load(&*self.repo)?;
+    self.set_stashes(stashes)
+  }
+
+  fn set_stashes(&mut self, stashes: Vec<GitStash>) -> Result<(), Error> {
+    self.stash_count = Some(stashes.len());
+    let previous_key = self.get_selected_stash().map(|s| String::from(s.git_stash.unique_key()));
+    // Indexes shift as stashes are dropped, the ids don't
+    let staged: Vec<String> =
+      self.stashes.iter().filter(|s| s.staged_for_deletion).map(|s| String::from(s.git_stash.unique_key())).collect();
+    self.stashes = stashes.into_iter().map(StashItem::new).collect();
+    for stash in self.stashes.iter_mut() {
+      stash.staged_for_deletion = staged.iter().any(|key| key == stash.git_stash.unique_key());
+    }
+    self.apply_filter();
+    // Keep the selected stash selected when others are added or dropped, otherwise apply_filter clamps the position
+    if let Some(key) = previous_key {
+      let stashes = &self.stashes;
+      let visible = self.visible_indices();
+      if let Some(position) = visible.iter().position(|&index| stashes[index].git_stash.unique_key() == key) {
+        self.selected_index = position;
+      }
+    }
+    self.conflict_files.clear();
+    self.stash_diffs.clear();
+    self.load_selected_diff()
+  }
+
+  fn toggle_diff(&mut self) -> Result<(), Error> {
+    self.show_diff = !self.show_diff;
+    self.diff_scroll = 0;
+    self.load_selected_diff()
+  }
+
+  fn load_selected_diff(&mut self) -> Result<(), Error> {
+    if !self.show_diff {
+      return Ok(());
+    }
+    let Some(selected) = self.get_selected_stash() else {
+      return Ok(());
+    };
+    let stash = selected.git_stash.clone();
+    if !self.stash_diffs.contains_key(&stash.stash_id) {
+      let diff = self.repo.stash_diff(&stash)?;
+      self.stash_diffs.insert(stash.stash_id, styled_lines(&diff));
+    }
+    Ok(())
+  }
+
+  fn selected_diff(&self) -> Option<&Vec<(String, Style)>> {
+    self.stash_diffs.get(&self.get_selected_stash()?.git_stash.stash_id)
+  }
+
+  fn scroll_diff_down(&mut self) {
+    let line_count = self.selected_diff().map_or(0, Vec::len);
+    let max_scroll = u16::try_from(line_count.saturating_sub(1)).unwrap_or(u16::MAX);
+    self.diff_scroll = self.diff_scroll.saturating_add(1).min(max_scroll);
+  }
+
+  /// Wraps `action` in a confirmation if applying the selected stash is likely to conflict with local changes.
+  fn confirm_if_conflicting(&mut self, action: Action, verb: &str) -> Option<Action> {
+    let selected = self.get_selected_stash()?.git_stash.clone();
+    let files = self.conflict_files.entry(String::from(selected.unique_key())).or_insert_with(|| {
+      self.repo.get_stash_conflict_files(&selected).unwrap_or_else(|err| {
+        error!("Failed to check stash for conflicts: {}", err);
+        Vec::new()
+      })
+    });
+    if files.is_empty() {
+      return Some(action);
+    }
+    let message = format!("{} may conflict with {} modified files: {}. Continue?", verb, files.len(), files.join(", "));
+    Some(Action::RequestConfirmation(message, Box::new(action)))
+  }
+
+  fn toggle_selected_stat(&mut self) -> Result<(), Error> {
+    let Some(&index) = self.visible_indices().get(self.selected_index) else {
+      return Ok(());
+    };
+    let item = &mut self.stashes[index];
+    if item.stat.is_some() {
+      item.stat = None;
+      return Ok(());
+    }
+    let key = String::from(item.git_stash.unique_key());
+    if !self.stash_stats.contains_key(&key) {
+      let stat = self.repo.stash_show_stat(&item.git_stash)?;
+      self.stash_stats.insert(key.clone(), stat);
+    }
+    item.stat = self.stash_stats.get(&key).cloned();
+    Ok(())
+  }
+
+  fn create_stash(&mut self, message: &str, include_untracked: bool, include_ignored: bool) -> Result<(), Error> {
+    self.repo.create_stash(message, include_untracked, include_ignored)?;
+    self.refresh()?;
+    self.selected_index = 0;
+    Ok(())
+  }
+
+  fn create_stash_no_verify(&mut self, message: &str) -> Result<(), Error> {
+    if !self.repo.stash_no_verify(message)? {
+      self.error = Some(String::from("No local changes to stash"));
+      return Ok(());
+    }
+    self.refresh()?;
+    self.selected_index = 0;
+    Ok(())
+  }
+
+  fn create_stash_with_pathspec(&mut self, message: &str, pathspec: &[String]) -> Result<(), Error> {
+    let pathspec: Vec<&str> = pathspec.iter().map(String::as_str).collect();
+    if !self.repo.stash_push_pathspec(message, &pathspec)? {
+      self.error = Some(format!("No changes to stash in {}", pathspec.join(" ")));
+      return Ok(());
+    }
+    self.refresh()?;
+    self.selected_index = 0;
+    Ok(())
+  }
+
+  fn render_stash_options(&self, f: &mut Frame<'_>, area: Rect) {
+    let handler = self.stash_input.handler();
+    if !handler.pathspec.is_empty() {
+      let options = format!(" Stashing only {}", handler.pathspec.join(" "));
+      f.render_widget(Paragraph::new(options).style(Style::default().add_modifier(Modifier::DIM)), area);
+      return;
+    }
+    let checkbox = |checked: bool| if checked { "[x]" } else { "[ ]" };
+    let options = format!(
+      " {} Include untracked  {} Include ignored",
+      checkbox(handler.include_untracked),
+      checkbox(handler.include_ignored)
+    );
+    f.render_widget(Paragraph::new(options).style(Style::default().add_modifier(Modifier::DIM)), area);
+  }
+
+  fn create_branch_from_selected(&mut self, branch_name: &str) -> Result<(), Error> {
+    let Some(selected) = self.get_selected_stash() else {
+      return Ok(());
+    };
+    let conflicts = self.repo.create_branch_from_stash(&selected.git_stash, branch_name)?;
+    if !conflicts.is_empty() {
+      self.error = Some(format!(
+        "Created {} but the stash conflicted, it was kept. Resolve the conflicts in: {}",
+        branch_name,
+        conflicts.join(", ")
+      ));
+    }
+    self.refresh()
+  }
+
+  fn stage_selected_for_deletion(&mut self, stage: bool) {
+    let Some(&index) = self.visible_indices().get(self.selected_index) else {
+      return;
+    };
+    self.stashes[index].staged_for_deletion = stage;
+  }
+
+  fn delete_selected(&mut self) -> Result<(), Error> {
+    let Some(selected) = self.get_selected_stash() else {
+      return Ok(());
+    };
+    self.repo.drop_stash(&selected.git_stash)?;
+    self.record_undo(&selected.git_stash);
+    self.refresh()
+  }
+
+  /// Remembers a dropped stash so it can be brought back with undo.
+  fn record_undo(&self, stash: &GitStash) {
+    let entry = UndoEntry::DroppedStash { message: stash.message.clone() };
+    if let Err(err) = self.send_action(Action::RecordUndo(entry)) {
+      error!("Failed to record the dropped stash: {}", err);
+    }
+  }
+
+  /// Drops the staged stashes, returning how many were dropped and the names of those that failed with why.
+  fn delete_staged_stashes(&mut self) -> (usize, Vec<(String, String)>) {
+    // Drop from the highest index down so the remaining indexes stay valid
+    let mut staged: Vec<&StashItem> = self.stashes.iter().filter(|s| s.staged_for_deletion).collect();
+    staged.sort_by_key(|s| std::cmp::Reverse(s.git_stash.index));
+    let mut deleted = 0;
+    let mut failures = Vec::new();
+    for stash in staged {
+      match self.repo.drop_stash(&stash.git_stash) {
+        Ok(()) => {
+          self.record_undo(&stash.git_stash);
+          deleted += 1;
+        },
+        Err(err) => failures.push((String::from(stash.git_stash.display_name()), err.reason())),
+      }
+    }
+    (deleted, failures)
+  }
+
+  fn apply_selected(&mut self) -> Result<(), Error> {
+    let Some(selected) = self.get_selected_stash() else {
+      return Ok(());
+    };
+    self.repo.apply_stash(&selected.git_stash)?;
+    // The working tree has changed so any conflict checks are stale
+    self.conflict_files.clear();
+    Ok(())
+  }
+
+  fn open_target_branch_selector(&mut self) -> Result<Option<Action>, Error> {
+    let Some(selected) = self.get_selected_stash() else {
+      return Ok(None);
+    };
+    let stash = selected.git_stash.clone();
+    let branches = self
+      .repo
+      .local_branches()?
+      .into_iter()
+      .map(|branch| branch.name)
+      .filter(|name| *name != stash.branch_name)
+      .collect();
+    self.target_branch_selector = Some(TargetBranchSelector::new(stash.stash_id, branches));
+    Ok(Some(Action::StartInputMode))
+  }
+
+  /// Checks out `branch_name` and applies the stash there. The branch stays checked out if applying fails.
+  fn apply_stash_to_branch(&mut self, stash_id: &str, branch_name: &str) -> Result<(), Error> {
+    let Some(stash) = self.stashes.iter().find(|s| s.git_stash.stash_id == stash_id) else {
+      return Ok(());
+    };
+    self.repo.checkout_branch_from_name(branch_name)?;
+    // HEAD has moved so both lists are stale, even if applying fails
+    if let Err(err) = self.send_action(Action::Refresh) {
+      error!("Failed to request a refresh: {}", err);
+    }
+    self.repo.apply_stash(&stash.git_stash)?;
+    self.conflict_files.clear();
+    Ok(())
+  }
+
+  fn pop_selected(&mut self) -> Result<(), Error> {
+    let Some(selected) = self.get_selected_stash() else {
+      return Ok(());
+    };
+    self.repo.pop_stash(&selected.git_stash)?;
+    self.refresh()
+  }
+
+  fn send_action(&self, action: Action) -> color_eyre::Result<()> {
+    if let Some(action_tx) = &self.action_tx {
+      action_tx.send(action)?;
+    }
+    Ok(())
+  }
+
+  fn maybe_handle_git_error(&mut self, err: Option<Error>) {
+    if let Some(error) = err {
+      error!("{}", error);
+      self.error = Some(error.to_string());
+    }
+  }
+
+  fn list_title(&self) -> String {
+    if self.stashes.is_empty() {
+      return format!("{} (empty)", self.data_source.title());
+    }
+    self.filter.title(self.data_source.title())
+  }
+
+  #[cfg(test)]
+  pub fn snapshot(&self) -> crate::components::list_snapshot::ListSnapshot {
+    let visible = self.visible_indices();
+    let staged_count = self.stashes.iter().filter(|s| s.staged_for_deletion).count();
+    crate::components::list_snapshot::ListSnapshot {
+      items: visible.iter().map(|&index| String::from(self.stashes[index].git_stash.display_name())).collect(),
+      selected_index: self.selected_index,
+      has_staged_items: staged_count > 0,
+      filter_active: visible.len() != self.stashes.len(),
+      staged_count,
+    }
+  }
+
+  fn render_list(&mut self, f: &mut Frame<'_>, area: Rect) {
+    self.navigation.set_rendered_area(area);
+    self.list_state.select(Some(self.selected_index));
+    // Leave room for the borders and highlight symbol
+    let item_width = area.width.saturating_sub(3);
+    let render_items: Vec<ListItem> =
+      self.visible_indices().into_iter().map(|index| self.stashes[index].render(item_width, &self.theme)).collect();
+    let mut block = Block::default().title(self.list_title()).borders(Borders::ALL);
+    // The borders take a row from the top and bottom
+    let visible_height = usize::from(area.height.saturating_sub(2));
+    if let Some(page) = page_indicator(self.selected_index, render_items.len(), visible_height) {
+      block = block.title(Title::from(page).alignment(Alignment::Right));
+    }
     let list = List::new(render_items)
-      .block(Block::default().title("Stashes").borders(Borders::ALL))
+      .block(block)
       .style(Style::default().fg(Color::White))
       .highlight_style(Style::default().add_modifier(Modifier::BOLD))
       .highlight_symbol("→")
       .repeat_highlight_symbol(true);
     f.render_stateful_widget(list, area, &mut self.list_state);
+  }
+
+  /// The list, with the selected stash's diff to its right when shown.
+  fn render_content(&mut self, f: &mut Frame<'_>, area: Rect) {
+    if !self.show_diff {
+      self.render_list(f, area);
+      return;
+    }
+    let [list_area, diff_area] =
+      Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(area);
+    self.render_list(f, list_area);
+    let lines: Vec<Line> = self
+      .selected_diff()
+      .map(|diff| diff.iter().map(|(line, style)| Line::styled(line.as_str(), *style)).collect())
+      .unwrap_or_default();
+    let diff = Paragraph::new(Text::from(lines)).block(Block::bordered().title("Diff")).scroll((self.diff_scroll, 0));
+    f.render_widget(diff, diff_area);
+  }
+
+  fn render_error(&mut self, f: &mut Frame<'_>, area: Rect) {
+    let Some(error_message) = self.error.clone() else {
+      return;
+    };
+    let component = Paragraph::new(Text::from(error_message))
+      .block(Block::bordered().title("Error"))
+      .style(Style::from(Color::Red))
+      .wrap(Wrap { trim: true });
+    f.render_widget(component, area);
+  }
+}
+
+impl Component for StashList {
+  fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> color_eyre::Result<()> {
+    self.action_tx = Some(tx);
     Ok(())
   }
+
+  fn handle_key_events(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+    self.clear_error();
+
+    match self.mode {
+      Mode::Input if !self.stash_input.handler().pathspec.is_empty() => {
+        // Untracked and ignored files can't be picked out by a pathspec
+        return Ok(self.stash_input.handle_key_event(key, &*self.repo));
+      },
+      Mode::Input => {
+        let handler = self.stash_input.handler_mut();
+        match key {
+          KeyEvent { code: KeyCode::Char('u' | 'U'), modifiers: KeyModifiers::ALT, kind: _, state: _ } => {
+            handler.include_untracked = !handler.include_untracked;
+            return Ok(None);
+          },
+          KeyEvent { code: KeyCode::Char('i' | 'I'), modifiers: KeyModifiers::ALT, kind: _, state: _ } => {
+            handler.include_ignored = !handler.include_ignored;
+            return Ok(None);
+          },
+          _ => return Ok(self.stash_input.handle_key_event(key, &*self.repo)),
+        }
+      },
+      Mode::BranchInput => return Ok(self.branch_input.handle_key_event(key, &*self.repo)),
+      Mode::PathspecInput => return Ok(self.pathspec_input.handle_key_event(key, &*self.repo)),
+      Mode::Selection => {},
+    }
+
+    if let Some(target_branch_selector) = self.target_branch_selector.as_mut() {
+      return target_branch_selector.handle_key_events(key);
+    }
+
+    if self.filter.is_editing() {
+      let action = self.filter.handle_key_event(key);
+      self.apply_filter();
+      return Ok(action);
+    }
+
+    if key.code == KeyCode::Esc && self.filter.is_active() {
+      self.clear_filter();
+      return Ok(None);
+    }
+
+    if let Some(action) = self.navigation.handle_key_event(&key) {
+      return Ok(Some(action));
+    }
+    match key {
+      KeyEvent { code: KeyCode::Down, modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        Ok(Some(Action::SelectNextStash))
+      },
+      KeyEvent { code: KeyCode::Up, modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        Ok(Some(Action::SelectPreviousStash))
+      },
+      KeyEvent { code: KeyCode::Char('a' | 'A'), modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        Ok(self.confirm_if_conflicting(Action::ApplySelectedStash, "Applying"))
+      },
+      KeyEvent { code: KeyCode::Char('p' | 'P'), modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        Ok(self.confirm_if_conflicting(Action::PopSelectedStash, "Popping"))
+      },
+      KeyEvent { code: KeyCode::Char('s' | 'S'), modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        Ok(Some(Action::InitNewStash))
+      },
+      KeyEvent { code: KeyCode::Char('s' | 'S'), modifiers: KeyModifiers::ALT, kind: _, state: _ } => {
+        Ok(Some(Action::InitPathspecStash))
+      },
+      KeyEvent { code: KeyCode::Char('d' | 'D'), modifiers: KeyModifiers::SHIFT, kind: _, state: _ } => {
+        Ok(Some(Action::UnstageStashForDeletion))
+      },
+      KeyEvent { code: KeyCode::Char('d' | 'D'), modifiers: KeyModifiers::CONTROL, kind: _, state: _ } => {
+        Ok(Some(Action::DeleteStagedStashes))
+      },
+      KeyEvent { code: KeyCode::Char('d' | 'D'), modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        let Some(selected) = self.get_selected_stash() else {
+          return Ok(None);
+        };
+        if selected.staged_for_deletion {
+          return Ok(Some(Action::DeleteStash));
+        }
+        Ok(Some(Action::StageStashForDeletion))
+      },
+      KeyEvent { code: KeyCode::Char('c' | 'C'), modifiers: KeyModifiers::SHIFT, kind: _, state: _ } => {
+        let result = self.open_target_branch_selector();
+        let action = result.as_ref().ok().cloned().flatten();
+        self.maybe_handle_git_error(result.err());
+        Ok(action)
+      },
+      KeyEvent { code: KeyCode::Char('b' | 'B'), modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        Ok(self.get_selected_stash().map(|_| Action::InitBranchFromStash))
+      },
+      KeyEvent { code: KeyCode::Char('i' | 'I'), modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        Ok(Some(Action::ToggleSelectedStashStat))
+      },
+      KeyEvent { code: KeyCode::Char('v' | 'V'), modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        Ok(Some(Action::ToggleStashDiff))
+      },
+      KeyEvent { code: KeyCode::Char('j' | 'J'), modifiers: KeyModifiers::NONE, kind: _, state: _ }
+        if self.show_diff =>
+      {
+        Ok(Some(Action::ScrollDiffDown))
+      },
+      KeyEvent { code: KeyCode::Char('k' | 'K'), modifiers: KeyModifiers::NONE, kind: _, state: _ }
+        if self.show_diff =>
+      {
+        Ok(Some(Action::ScrollDiffUp))
+      },
+      KeyEvent { code: KeyCode::Char('/'), modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        self.filter.start_editing();
+        Ok(Some(Action::StartInputMode))
+      },
+      _ => Ok(None),
+    }
+  }
+
+  fn handle_mouse_events(&mut self, mouse: MouseEvent) -> color_eyre::Result<Option<Action>> {
+    match self.navigation.handle_mouse_event(&mouse, self.list_state.offset()) {
+      Some(ListClick::Select(position)) => Ok(Some(Action::SelectPosition(position))),
+      Some(ListClick::Activate) => Ok(self.confirm_if_conflicting(Action::ApplySelectedStash, "Applying")),
+      None => Ok(None),
+    }
+  }
+
+  fn update(&mut self, action: Action) -> color_eyre::Result<Option<Action>> {
+    match action {
+      Action::InitNewStash => {
+        self.mode = Mode::Input;
+        self.stash_input.handler_mut().reset();
+        self.stash_input.init_style();
+        return Ok(Some(Action::StartInputMode));
+      },
+      Action::InitPathspecStash => {
+        self.mode = Mode::PathspecInput;
+        self.pathspec_input.init_style();
+        return Ok(Some(Action::StartInputMode));
+      },
+      Action::InitNewStashWithPathspec(pathspec) => {
+        // Still in input mode, the message is asked for next
+        self.mode = Mode::Input;
+        self.stash_input.handler_mut().reset();
+        self.stash_input.handler_mut().pathspec = pathspec;
+        self.stash_input.init_style();
+      },
+      Action::InitBranchFromStash => {
+        self.mode = Mode::BranchInput;
+        self.branch_input.init_style();
+        return Ok(Some(Action::StartInputMode));
+      },
+      Action::EndInputMode => {
+        self.mode = Mode::Selection;
+        self.target_branch_selector = None;
+      },
+      Action::ApplyStashToBranch(stash_id, branch_name) => {
+        self.target_branch_selector = None;
+        let result = self.apply_stash_to_branch(&stash_id, &branch_name);
+        self.maybe_handle_git_error(result.err());
+        return Ok(Some(Action::EndInputMode));
+      },
+      Action::CreateBranchFromStash(branch_name) => {
+        self.mode = Mode::Selection;
+        let result = self.create_branch_from_selected(&branch_name);
+        let created = result.is_ok();
+        self.maybe_handle_git_error(result.err());
+        if created {
+          // The new branch is checked out so the branch list needs to reload too
+          self.send_action(Action::Refresh)?;
+        }
+        return Ok(Some(Action::EndInputMode));
+      },
+      Action::CreateStash(message, include_untracked, include_ignored) => {
+        self.mode = Mode::Selection;
+        let result = self.create_stash(&message, include_untracked, include_ignored);
+        self.maybe_handle_git_error(result.err());
+        return Ok(Some(Action::EndInputMode));
+      },
+      Action::CreateStashNoVerify(message) => {
+        self.mode = Mode::Selection;
+        let result = self.create_stash_no_verify(&message);
+        self.maybe_handle_git_error(result.err());
+        return Ok(Some(Action::EndInputMode));
+      },
+      Action::CreateStashWithPathspec(message, pathspec) => {
+        self.mode = Mode::Selection;
+        let result = self.create_stash_with_pathspec(&message, &pathspec);
+        self.maybe_handle_git_error(result.err());
+        return Ok(Some(Action::EndInputMode));
+      },
+      Action::SelectPreviousStash => {
+        self.select_previous();
+        self.announce_selection();
+        self.diff_scroll = 0;
+        let result = self.load_selected_diff();
+        self.maybe_handle_git_error(result.err());
+      },
+      Action::SelectFirst | Action::SelectLast | Action::SelectPosition(_) | Action::PageDown | Action::PageUp => {
+        self.apply_motion(&action);
+        self.announce_selection();
+        self.diff_scroll = 0;
+        let result = self.load_selected_diff();
+        self.maybe_handle_git_error(result.err());
+      },
+      Action::SelectNextStash => {
+        self.select_next();
+        self.announce_selection();
+        self.diff_scroll = 0;
+        let result = self.load_selected_diff();
+        self.maybe_handle_git_error(result.err());
+      },
+      Action::SearchQueryChanged(query) => {
+        // Searching jumps to the best match
+        self.filter.search(&query);
+        self.apply_filter();
+        self.selected_index = 0;
+        let result = self.load_selected_diff();
+        self.maybe_handle_git_error(result.err());
+      },
+      Action::EndSearch => self.clear_filter(),
+      Action::ToggleStashDiff => {
+        let result = self.toggle_diff();
+        self.maybe_handle_git_error(result.err());
+      },
+      Action::ScrollDiffDown => self.scroll_diff_down(),
+      Action::ScrollDiffUp => self.diff_scroll = self.diff_scroll.saturating_sub(1),
+      Action::ApplySelectedStash => {
+        let result = self.apply_selected();
+        self.maybe_handle_git_error(result.err());
+      },
+      Action::PopSelectedStash => {
+        let result = self.pop_selected();
+        self.maybe_handle_git_error(result.err());
+      },
+      Action::Refresh => {
+        if !self.refreshed_recently() {
+          let result = self.reload();
+          self.maybe_handle_git_error(result.err());
+        }
+      },
+      Action::StageStashForDeletion => self.stage_selected_for_deletion(true),
+      Action::UnstageStashForDeletion => self.stage_selected_for_deletion(false),
+      Action::DeleteStash => {
+        let result = self.delete_selected();
+        self.maybe_handle_git_error(result.err());
+      },
+      Action::DeleteStagedStashes => {
+        let (deleted, failures) = self.delete_staged_stashes();
+        let result = self.refresh();
+        self.maybe_handle_git_error(failed_deletions(("stash", "stashes"), &failures).or(result.err()));
+        return Ok(Some(Action::ItemsLoaded { deleted, failed: failures.len() }));
+      },
+      Action::ToggleSelectedStashStat => {
+        let result = self.toggle_selected_stat();
+        self.maybe_handle_git_error(result.err());
+      },
+      _ => {},
+    }
+    Ok(None)
+  }
+
+  fn staged_items(&self) -> Vec<String> {
+    self.stashes.iter().filter(|s| s.staged_for_deletion).map(|s| format!("stash@{{{}}}", s.git_stash.index)).collect()
+  }
+
+  fn title(&self) -> &str {
+    "Stashes"
+  }
+
+  fn item_counts(&self) -> (usize, usize) {
+    (self.visible_indices().len(), self.stashes.len())
+  }
+
+  fn handles_escape(&self) -> bool {
+    self.filter.is_active()
+  }
+
+  fn instructions(&self) -> Vec<&'static str> {
+    let input_submit = match self.mode {
+      Mode::Input => Some("enter: Stash changes"),
+      Mode::BranchInput => Some("enter: Create branch"),
+      Mode::PathspecInput => Some("enter: Choose files"),
+      Mode::Selection if self.target_branch_selector.is_some() => Some("enter: Apply to branch"),
+      Mode::Selection => None,
+    };
+    let mut instructions = self.instruction_footer.instructions(
+      &self.stashes,
+      self.get_selected_stash(),
+      self.filter.is_editing(),
+      self.filter.is_active(),
+      self.show_diff,
+      input_submit,
+    );
+    if self.mode == Mode::Input && self.stash_input.handler().pathspec.is_empty() {
+      instructions.extend(["alt + u: Toggle untracked", "alt + i: Toggle ignored"]);
+    }
+    instructions
+  }
+
+  fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> color_eyre::Result<()> {
+    let instructions = self.instructions();
+    // The layout margin takes a column from each side
+    let footer_height = ui::instruction_footer::preferred_height(&instructions, area.width.saturating_sub(2));
+
+    if self.mode != Mode::Selection {
+      let options_height = if self.mode == Mode::Input { 1 } else { 0 };
+      let layout = Layout::new(Direction::Vertical, [
+        Constraint::Min(1),
+        Constraint::Length(3),
+        Constraint::Length(options_height),
+        Constraint::Length(footer_height),
+      ])
+      .margin(1)
+      .split(area);
+      self.render_content(f, layout[0]);
+      if self.mode == Mode::Input {
+        self.stash_input.render(f, layout[1]);
+        self.render_stash_options(f, layout[2]);
+      } else if self.mode == Mode::PathspecInput {
+        self.pathspec_input.render(f, layout[1]);
+      } else {
+        self.branch_input.render(f, layout[1]);
+      }
+      ui::instruction_footer::render(f, layout[3], &instructions);
+      return Ok(());
+    }
+
+    if let Some(error) = &self.error {
+      let err_size = error.lines().count() + 2;
+      let layout = Layout::new(Direction::Vertical, [
+        Constraint::Min(1),
+        Constraint::Length(u16::try_from(err_size)?),
+        Constraint::Length(footer_height),
+      ])
+      .margin(1)
+      .split(area);
+      self.render_content(f, layout[0]);
+      self.render_error(f, layout[1]);
+      ui::instruction_footer::render(f, layout[2], &instructions);
+      return Ok(());
+    }
+
+    let layout =
+      Layout::new(Direction::Vertical, [Constraint::Min(1), Constraint::Length(footer_height)]).margin(1).split(area);
+    self.render_content(f, layout[0]);
+    ui::instruction_footer::render(f, layout[1], &instructions);
+    if let Some(target_branch_selector) = self.target_branch_selector.as_mut() {
+      target_branch_selector.draw(f, layout[0])?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::atomic::{AtomicUsize, Ordering};
+
+  use super::*;
+  use crate::{
+    components::list_snapshot::ListSnapshot,
+    git::{git_repo::GitStash, mock_git_repo::MockGitRepo},
+  };
+
+  fn stash_list() -> StashList {
+    let stashes = ["first", "second", "third"]
+      .iter()
+      .enumerate()
+      .map(|(index, message)| GitStash::new(index, format!("On main: {}", message), format!("id-{}", index)))
+      .collect();
+    StashList::new(Box::new(MockGitRepo { stashes, stash_count: 3, ..Default::default() }), &Config::default())
+  }
+
+  #[test]
+  fn snapshot_reflects_selection_and_staging() {
+    let mut list = stash_list();
+    list.update(Action::SelectNextStash).unwrap();
+    list.update(Action::StageStashForDeletion).unwrap();
+    assert_eq!(list.snapshot(), ListSnapshot {
+      items: vec![String::from("On main: first"), String::from("On main: second"), String::from("On main: third")],
+      selected_index: 1,
+      has_staged_items: true,
+      filter_active: false,
+      staged_count: 1,
+    });
+  }
+
+  #[test]
+  fn snapshot_of_empty_list() {
+    let list = StashList::new(Box::new(MockGitRepo::default()), &Config::default());
+    assert_eq!(list.snapshot(), ListSnapshot::default());
+  }
+
+  #[test]
+  fn an_empty_count_skips_loading_stashes() {
+    let stash_loads = Arc::new(AtomicUsize::new(0));
+    let stashes = vec![GitStash::new(0, String::from("On main: stale"), String::from("id-0"))];
+    let repo = MockGitRepo { stashes, stash_loads: stash_loads.clone(), ..Default::default() };
+    let list = StashList::new(Box::new(repo), &Config::default());
+    assert_eq!(list.snapshot(), ListSnapshot::default());
+    assert_eq!(stash_loads.load(Ordering::Relaxed), 0);
+  }
+
+  #[test]
+  fn stashes_are_only_reloaded_when_the_count_changes() {
+    let stash_loads = Arc::new(AtomicUsize::new(0));
+    let stashes = vec![GitStash::new(0, String::from("On main: first"), String::from("id-0"))];
+    let repo = MockGitRepo { stashes, stash_count: 1, stash_loads: stash_loads.clone(), ..Default::default() };
+    let mut list = StashList::new(Box::new(repo), &Config::default());
+    assert_eq!(stash_loads.load(Ordering::Relaxed), 1);
+    // The mock keeps its stash, so popping it leaves the count where it was
+    list.update(Action::PopSelectedStash).unwrap();
+    assert_eq!(stash_loads.load(Ordering::Relaxed), 1);
+    // Refreshing on request always reloads, the stashes may have changed outside the app
+    list.update(Action::Refresh).unwrap();
+    assert_eq!(stash_loads.load(Ordering::Relaxed), 2);
+  }
+
+  #[test]
+  fn a_conflicting_stash_branch_lists_the_conflicts() {
+    let stashes = vec![GitStash::new(0, String::from("On main: conflict"), String::from("id-0"))];
+    let repo = MockGitRepo { stashes, stash_count: 1, ..Default::default() };
+    let mut list = StashList::new(Box::new(repo), &Config::default());
+    let action = list.update(Action::CreateBranchFromStash(String::from("topic"))).unwrap();
+    assert_eq!(action, Some(Action::EndInputMode));
+    assert_eq!(
+      list.error.as_deref(),
+      Some("Created topic but the stash conflicted, it was kept. Resolve the conflicts in: src/main.rs")
+    );
+  }
+
+  #[test]
+  fn applying_a_stash_that_may_conflict_asks_first() {
+    let stashes = vec![
+      GitStash::new(0, String::from("On main: clean"), String::from("id-0")),
+      GitStash::new(1, String::from("On main: overlapping"), String::from("id-1")),
+    ];
+    let stash_conflict_files =
+      HashMap::from([(String::from("id-1"), vec![String::from("foo.rs"), String::from("bar.rs")])]);
+    let repo = MockGitRepo { stashes, stash_count: 2, stash_conflict_files, ..Default::default() };
+    let mut list = StashList::new(Box::new(repo), &Config::default());
+    let apply = KeyEvent::from(KeyCode::Char('a'));
+    assert_eq!(list.handle_key_events(apply).unwrap(), Some(Action::ApplySelectedStash));
+    list.update(Action::SelectNextStash).unwrap();
+    let message = String::from("Applying may conflict with 2 modified files: foo.rs, bar.rs. Continue?");
+    assert_eq!(
+      list.handle_key_events(apply).unwrap(),
+      Some(Action::RequestConfirmation(message, Box::new(Action::ApplySelectedStash)))
+    );
+  }
+
+  #[tokio::test]
+  async fn rapid_refreshes_load_the_stashes_once() {
+    let stash_loads = Arc::new(AtomicUsize::new(0));
+    let stashes = vec![GitStash::new(0, String::from("On main: first"), String::from("id-0"))];
+    let repo = MockGitRepo { stashes, stash_count: 1, stash_loads: stash_loads.clone(), ..Default::default() };
+    let config = Config { refresh_debounce_ms: 100, ..Config::default() };
+    let mut list = StashList::new(Box::new(repo), &config);
+    assert_eq!(stash_loads.load(Ordering::Relaxed), 1);
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    list.update(Action::Refresh).unwrap();
+    list.update(Action::Refresh).unwrap();
+    assert_eq!(stash_loads.load(Ordering::Relaxed), 2);
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    list.update(Action::Refresh).unwrap();
+    assert_eq!(stash_loads.load(Ordering::Relaxed), 3);
+  }
+
+  #[test]
+  fn esc_clears_a_kept_filter() {
+    let mut list = stash_list();
+    for key in [KeyCode::Char('/'), KeyCode::Char('t'), KeyCode::Char('h'), KeyCode::Enter] {
+      list.handle_key_events(KeyEvent::from(key)).unwrap();
+    }
+    let filtered = list.item_counts();
+    assert!(list.handles_escape());
+    assert_eq!(list.handle_key_events(KeyEvent::from(KeyCode::Esc)).unwrap(), None);
+    assert!(!list.handles_escape());
+    assert_eq!(filtered, (1, 3));
+    assert_eq!(list.item_counts(), (3, 3));
+  }
 }
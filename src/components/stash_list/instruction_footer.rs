@@ -0,0 +1,55 @@
+use crate::components::stash_list::stash_item::StashItem;
+
+#[derive(Debug, Default)]
+pub struct InstructionFooter {}
+
+impl InstructionFooter {
+  pub fn instructions(
+    &self,
+    stashes: &[StashItem],
+    selected: Option<&StashItem>,
+    filter_editing: bool,
+    filter_active: bool,
+    diff_visible: bool,
+    input_submit: Option<&'static str>,
+  ) -> Vec<&'static str> {
+    if let Some(input_submit) = input_submit {
+      return vec!["esc: Cancel", input_submit];
+    }
+
+    if filter_editing {
+      return vec!["esc: Clear filter", "enter: Keep filter"];
+    }
+
+    let mut commands = vec![if filter_active { "esc: Clear filter" } else { "esc: Quit" }];
+    commands.push("tab: Switch view");
+    commands.push("s: Stash changes");
+    commands.push("alt + s: Stash some files");
+    commands.push("/: Filter");
+
+    if selected.is_some() {
+      commands.push("a: Apply");
+      commands.push("p: Pop");
+      commands.push("b: Branch from stash");
+      commands.push("⇧ + c: Apply to branch");
+      commands.push("i: Toggle stats");
+      commands.push("v: Toggle diff");
+    }
+
+    if diff_visible {
+      commands.push("j/k: Scroll diff");
+    }
+
+    if selected.is_some_and(|s| s.staged_for_deletion) {
+      commands.push("d: Delete");
+      commands.push("⇧ + d: Unstage for deletion");
+    } else if selected.is_some() {
+      commands.push("d: Stage for deletion");
+    }
+
+    if stashes.iter().any(|s| s.staged_for_deletion) {
+      commands.push("^ + d: Delete all staged stashes");
+    }
+    commands
+  }
+}
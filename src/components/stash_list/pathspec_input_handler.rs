@@ -0,0 +1,27 @@
+use crate::{
+  action::Action,
+  components::{generic_input::GenericInput, traits::input_handler::InputHandler},
+  git::git_repo::{GitRepo, GitStash},
+};
+
+pub type PathspecInput = GenericInput<GitStash, PathspecInputHandler>;
+
+/// Collects the space separated globs that limit which files a new stash takes.
+#[derive(Debug, Default)]
+pub struct PathspecInputHandler {}
+
+impl InputHandler<GitStash> for PathspecInputHandler {
+  fn validate_input(&self, _repo: &dyn GitRepo, input: &str) -> bool {
+    let input = input.trim();
+    // A doubled space would leave an empty glob between them
+    !input.is_empty() && input.split(' ').all(|glob| !glob.is_empty())
+  }
+
+  fn create_submit_action(&self, input: String) -> Action {
+    Action::InitNewStashWithPathspec(input.split_whitespace().map(String::from).collect())
+  }
+
+  fn get_input_prompt(&self) -> Option<&str> {
+    Some("Files to stash:")
+  }
+}
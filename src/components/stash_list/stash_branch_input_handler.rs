@@ -0,0 +1,25 @@
+use crate::{
+  action::Action,
+  components::{generic_input::GenericInput, traits::input_handler::InputHandler},
+  git::git_repo::{GitBranch, GitRepo},
+};
+
+pub type StashBranchInput = GenericInput<GitBranch, StashBranchInputHandler>;
+
+/// Names the branch created from the selected stash.
+#[derive(Debug, Default)]
+pub struct StashBranchInputHandler {}
+
+impl InputHandler<GitBranch> for StashBranchInputHandler {
+  fn validate_input(&self, repo: &dyn GitRepo, input: &str) -> bool {
+    repo.validate_branch_name(input).unwrap_or(false)
+  }
+
+  fn create_submit_action(&self, input: String) -> Action {
+    Action::CreateBranchFromStash(input)
+  }
+
+  fn get_input_prompt(&self) -> Option<&str> {
+    Some("New branch from stash:")
+  }
+}
@@ -0,0 +1,19 @@
+use crate::{
+  components::traits::list_data_source::ListDataSource,
+  error::Error,
+  git::git_repo::{GitRepo, GitStash},
+};
+
+/// Loads stashes newest first, the order git keeps them in.
+#[derive(Debug, Default)]
+pub struct StashDataSource {}
+
+impl ListDataSource<GitStash> for StashDataSource {
+  fn title(&self) -> &str {
+    "Stashes"
+  }
+
+  fn fetch_items(&mut self, repo: &dyn GitRepo) -> Result<Vec<GitStash>, Error> {
+    repo.stashes()
+  }
+}
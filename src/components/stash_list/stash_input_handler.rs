@@ -0,0 +1,71 @@
+use crate::{
+  action::Action,
+  components::{generic_input::GenericInput, traits::input_handler::InputHandler},
+  git::git_repo::{GitRepo, GitStash},
+};
+
+const MAX_MESSAGE_LENGTH: usize = 512;
+
+pub type StashInput = GenericInput<GitStash, StashInputHandler>;
+
+/// Collects the stash message, along with whether untracked and ignored files should be stashed too. With a pathspec
+/// only the matching files are stashed.
+#[derive(Debug, Default)]
+pub struct StashInputHandler {
+  pub include_untracked: bool,
+  pub include_ignored: bool,
+  pub pathspec: Vec<String>,
+  // From `Config::no_verify_stash`, plain stashes are made with hooks turned off
+  pub no_verify: bool,
+}
+
+impl StashInputHandler {
+  pub fn reset(&mut self) {
+    self.include_untracked = false;
+    self.include_ignored = false;
+    self.pathspec.clear();
+  }
+}
+
+impl InputHandler<GitStash> for StashInputHandler {
+  fn validate_input(&self, _repo: &dyn GitRepo, input: &str) -> bool {
+    let message = input.trim();
+    !message.is_empty() && message.chars().count() <= MAX_MESSAGE_LENGTH
+  }
+
+  fn create_submit_action(&self, input: String) -> Action {
+    if !self.pathspec.is_empty() {
+      return Action::CreateStashWithPathspec(input, self.pathspec.clone());
+    }
+    if self.no_verify && !self.include_untracked && !self.include_ignored {
+      return Action::CreateStashNoVerify(input);
+    }
+    Action::CreateStash(input, self.include_untracked, self.include_ignored)
+  }
+
+  fn get_input_prompt(&self) -> Option<&str> {
+    Some("Stash message:")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use crossterm::event::{KeyCode, KeyEvent};
+
+  use super::*;
+  use crate::{config::theme::Theme, git::mock_git_repo::MockGitRepo};
+
+  #[test]
+  fn blank_message_is_not_submitted() {
+    let repo = MockGitRepo::default();
+    let mut input = StashInput::new(StashInputHandler::default(), "stash_input", Arc::new(Theme::default()));
+    for _ in 0..3 {
+      input.handle_key_event(KeyEvent::from(KeyCode::Char(' ')), &repo);
+    }
+    let action = input.handle_key_event(KeyEvent::from(KeyCode::Enter), &repo);
+    assert!(!matches!(action, Some(Action::CreateStash(..))));
+    assert_eq!(input.input_state.is_valid, Some(false));
+  }
+}
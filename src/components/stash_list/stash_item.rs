@@ -0,0 +1,105 @@
+use ratatui::{
+  style::{Modifier, Style},
+  text::{Line, Span, Text},
+  widgets::ListItem,
+};
+
+use crate::{
+  components::traits::managed_item::ManagedItem, config::theme::Theme, git::git_repo::GitStash,
+  utils::time::format_relative_time,
+};
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StashItem {
+  pub git_stash: GitStash,
+  // The file stats summary, shown below the message when present
+  pub stat: Option<String>,
+  pub staged_for_deletion: bool,
+}
+
+impl StashItem {
+  pub fn new(git_stash: GitStash) -> Self {
+    StashItem { git_stash, stat: None, staged_for_deletion: false }
+  }
+
+  /// Render the stash, `width` is the space available for the item so the creation time can be right aligned.
+  pub fn render(&self, width: u16, theme: &Theme) -> ListItem<'_> {
+    let mut text = Line::default();
+    let mut parts = Vec::new();
+    let index = Span::styled(self.git_stash.index.to_string(), Style::default());
+    parts.push(index);
+
+    let mut message =
+      Span::styled(format!(" {}", self.git_stash.display_name()), Style::default().add_modifier(Modifier::DIM));
+    if self.staged_for_deletion {
+      message = message.style(Style::default().fg(theme.staged_deletion_fg));
+    }
+    parts.push(message);
+
+    let id =
+      Span::styled(format!(" ({})", self.git_stash.stash_id.clone()), Style::default().add_modifier(Modifier::DIM));
+    parts.push(id);
+
+    if let Some(created_at) = self.git_stash.created_at {
+      let relative_time = format_relative_time(created_at);
+      let used_width: usize = parts.iter().map(|part| part.width()).sum();
+      let padding = usize::from(width).saturating_sub(used_width + relative_time.len() + 1);
+      parts.push(Span::styled(
+        format!(" {}{}", " ".repeat(padding), relative_time),
+        Style::default().add_modifier(Modifier::DIM),
+      ));
+    }
+
+    text = text.spans(parts);
+    let Some(stat) = &self.stat else {
+      return ListItem::from(text);
+    };
+    let stat_line = Line::styled(format!("  {}", stat), Style::default().add_modifier(Modifier::DIM));
+    ListItem::from(Text::from(vec![text, stat_line]))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use insta::assert_snapshot;
+
+  use super::*;
+  use crate::components::list_snapshot::item_snapshot;
+
+  fn snapshot(item: &StashItem) -> String {
+    item_snapshot(item.render(60, &Theme::default()), 60)
+  }
+
+  fn stash(message: &str, stash_id: &str, staged_for_deletion: bool) -> StashItem {
+    let mut item = StashItem::new(GitStash::new(0, String::from(message), String::from(stash_id)));
+    item.staged_for_deletion = staged_for_deletion;
+    item
+  }
+
+  #[test]
+  fn stash_with_a_message() {
+    assert_snapshot!(snapshot(&stash("On main: Half done", "1a2b3c4", false)));
+  }
+
+  #[test]
+  fn work_in_progress_stash() {
+    assert_snapshot!(snapshot(&stash("WIP on feature/a: 8fb5d9b Commit subject", "8fb5d9b", false)));
+  }
+
+  #[test]
+  fn stash_with_an_empty_message() {
+    assert_snapshot!(snapshot(&stash("", "", false)));
+  }
+
+  #[test]
+  fn stash_staged_for_deletion() {
+    assert_snapshot!(snapshot(&stash("On main: Half done", "1a2b3c4", true)));
+  }
+
+  #[test]
+  fn stash_with_stats() {
+    let mut item = stash("On main: Half done", "1a2b3c4", false);
+    item.stat = Some(String::from("2 files changed, 3 insertions(+)"));
+    assert_snapshot!(snapshot(&item));
+  }
+}
@@ -0,0 +1,50 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+  layout::{Constraint, Flex, Layout, Rect},
+  style::{Color, Modifier, Style},
+  widgets::{Block, Clear, List, ListState},
+};
+
+use crate::{action::Action, components::Component, tui::Frame};
+
+/// Picks the branch a stash should be applied to, the branch it was created on is left out.
+pub struct TargetBranchSelector {
+  stash_id: String,
+  branches: Vec<String>,
+  list_state: ListState,
+}
+
+impl TargetBranchSelector {
+  pub fn new(stash_id: String, branches: Vec<String>) -> Self {
+    TargetBranchSelector { stash_id, branches, list_state: ListState::default().with_selected(Some(0)) }
+  }
+}
+
+impl Component for TargetBranchSelector {
+  fn handle_key_events(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+    match key.code {
+      KeyCode::Down => self.list_state.select_next(),
+      KeyCode::Up => self.list_state.select_previous(),
+      KeyCode::Enter => {
+        let selected = self.list_state.selected().and_then(|index| self.branches.get(index));
+        return Ok(selected.map(|branch| Action::ApplyStashToBranch(self.stash_id.clone(), branch.clone())));
+      },
+      KeyCode::Esc => return Ok(Some(Action::EndInputMode)),
+      _ => {},
+    }
+    Ok(None)
+  }
+
+  fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> color_eyre::Result<()> {
+    let [popup] = Layout::horizontal([Constraint::Percentage(50)]).flex(Flex::Center).areas(area);
+    let [popup] = Layout::vertical([Constraint::Percentage(60)]).flex(Flex::Center).areas(popup);
+    let list = List::new(self.branches.iter().map(String::as_str))
+      .block(Block::bordered().title("Apply stash to branch").title_bottom("esc: Cancel | enter: Apply"))
+      .style(Style::default().fg(Color::White))
+      .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+      .highlight_symbol("→");
+    f.render_widget(Clear, popup);
+    f.render_stateful_widget(list, popup, &mut self.list_state);
+    Ok(())
+  }
+}
@@ -0,0 +1,260 @@
+use std::path::Path;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent};
+use ratatui::{
+  layout::{Alignment, Constraint, Direction, Layout, Rect},
+  style::{Color, Modifier, Style},
+  text::Text,
+  widgets::{block::Title, Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+};
+use tracing::error;
+
+use crate::{
+  action::Action,
+  components::{
+    list_navigation::{ListClick, ListNavigation},
+    status_list::status_data_source::StatusDataSource,
+    traits::list_data_source::ListDataSource,
+    ui::{self, blame_view::BlameView, page_indicator::page_indicator, search::best_match},
+    Component,
+  },
+  error::Error,
+  git::git_repo::{FileStatus, GitRepo},
+  tui::Frame,
+};
+
+mod status_data_source;
+mod status_item;
+
+/// Lists the working tree's changes so files can be staged, unstaged, blamed or opened in an editor.
+pub struct StatusList {
+  repo: Box<dyn GitRepo>,
+  error: Option<String>,
+  data_source: StatusDataSource,
+  files: Vec<FileStatus>,
+  list_state: ListState,
+  selected_index: usize,
+  navigation: ListNavigation,
+  blame_view: Option<BlameView>,
+}
+
+impl StatusList {
+  pub fn new(repo: Box<dyn GitRepo>) -> Self {
+    let mut status_list = StatusList {
+      repo,
+      error: None,
+      data_source: StatusDataSource::default(),
+      files: Vec::new(),
+      list_state: ListState::default(),
+      selected_index: 0,
+      navigation: ListNavigation::default(),
+      blame_view: None,
+    };
+    let result = status_list.refresh();
+    status_list.maybe_handle_git_error(result.err());
+    status_list
+  }
+
+  fn refresh(&mut self) -> Result<(), Error> {
+    self.files = self.data_source.load(&*self.repo)?;
+    self.selected_index = self.selected_index.min(self.files.len().saturating_sub(1));
+    Ok(())
+  }
+
+  fn select_previous(&mut self) {
+    if self.files.is_empty() {
+      return;
+    }
+    self.selected_index = self.selected_index.checked_sub(1).unwrap_or(self.files.len() - 1);
+  }
+
+  fn select_next(&mut self) {
+    if self.selected_index + 1 >= self.files.len() {
+      self.selected_index = 0;
+      return;
+    }
+    self.selected_index += 1;
+  }
+
+  fn selected_path(&self) -> Option<String> {
+    self.files.get(self.selected_index).map(|file| file.path.to_string_lossy().into_owned())
+  }
+
+  fn open_selected(&mut self) -> Option<Action> {
+    let selected = self.files.get(self.selected_index)?;
+    match self.repo.work_dir() {
+      Ok(work_dir) => Some(Action::OpenEditor(work_dir.join(&selected.path))),
+      Err(err) => {
+        self.maybe_handle_git_error(Some(err));
+        None
+      },
+    }
+  }
+
+  fn show_blame(&mut self, path: &str) -> Result<(), Error> {
+    let lines = self.repo.blame(Path::new(path), None)?;
+    self.blame_view = Some(BlameView::new(format!("Blame: {}", path), lines));
+    Ok(())
+  }
+
+  fn maybe_handle_git_error(&mut self, err: Option<Error>) {
+    if let Some(error) = err {
+      error!("{}", error);
+      self.error = Some(error.to_string());
+    }
+  }
+
+  fn render_list(&mut self, f: &mut Frame<'_>, area: Rect) {
+    self.navigation.set_rendered_area(area);
+    self.list_state.select(Some(self.selected_index));
+    let render_items: Vec<ListItem> = self.files.iter().map(status_item::render).collect();
+    let title = if self.files.is_empty() {
+      format!("{} (nothing to commit)", self.data_source.title())
+    } else {
+      String::from(self.data_source.title())
+    };
+    let mut block = Block::default().title(title).borders(Borders::ALL);
+    // The borders take a row from the top and bottom
+    let visible_height = usize::from(area.height.saturating_sub(2));
+    if let Some(page) = page_indicator(self.selected_index, render_items.len(), visible_height) {
+      block = block.title(Title::from(page).alignment(Alignment::Right));
+    }
+    let list = List::new(render_items)
+      .block(block)
+      .style(Style::default().fg(Color::White))
+      .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+      .highlight_symbol("→")
+      .repeat_highlight_symbol(true);
+    f.render_stateful_widget(list, area, &mut self.list_state);
+  }
+}
+
+impl Component for StatusList {
+  fn handle_key_events(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+    self.error = None;
+    if let Some(blame_view) = self.blame_view.as_mut() {
+      if key.code == KeyCode::Esc {
+        self.blame_view = None;
+        return Ok(Some(Action::EndInputMode));
+      }
+      return blame_view.handle_key_events(key);
+    }
+    if let Some(action) = self.navigation.handle_key_event(&key) {
+      return Ok(Some(action));
+    }
+    let selected = self.files.get(self.selected_index);
+    match key {
+      KeyEvent { code: KeyCode::Down, modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        Ok(Some(Action::SelectNextFile))
+      },
+      KeyEvent { code: KeyCode::Up, modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        Ok(Some(Action::SelectPreviousFile))
+      },
+      KeyEvent { code: KeyCode::Enter, modifiers: KeyModifiers::NONE, kind: _, state: _ } => Ok(self.open_selected()),
+      KeyEvent { code: KeyCode::Char('s'), modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        Ok(selected.filter(|file| !file.staged).and_then(|_| self.selected_path()).map(Action::StageFile))
+      },
+      KeyEvent { code: KeyCode::Char('u' | 'U'), modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        Ok(selected.filter(|file| file.staged).and_then(|_| self.selected_path()).map(Action::UnstageFile))
+      },
+      KeyEvent { code: KeyCode::Char('b' | 'B'), modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        Ok(self.selected_path().map(Action::ShowBlame))
+      },
+      KeyEvent { code: KeyCode::Char('r' | 'R'), modifiers: KeyModifiers::CONTROL, kind: _, state: _ } => {
+        Ok(Some(Action::Refresh))
+      },
+      _ => Ok(None),
+    }
+  }
+
+  fn handle_mouse_events(&mut self, mouse: MouseEvent) -> color_eyre::Result<Option<Action>> {
+    match self.navigation.handle_mouse_event(&mouse, self.list_state.offset()) {
+      Some(ListClick::Select(position)) => Ok(Some(Action::SelectPosition(position))),
+      Some(ListClick::Activate) => Ok(self.open_selected()),
+      None => Ok(None),
+    }
+  }
+
+  fn update(&mut self, action: Action) -> color_eyre::Result<Option<Action>> {
+    match action {
+      Action::SearchQueryChanged(query) => {
+        if let Some(best) = best_match(&query, self.files.iter().map(|file| file.path.to_str().unwrap_or_default())) {
+          self.selected_index = best;
+        }
+      },
+      Action::SelectNextFile => self.select_next(),
+      Action::SelectPreviousFile => self.select_previous(),
+      Action::SelectFirst | Action::SelectLast | Action::SelectPosition(_) | Action::PageDown | Action::PageUp => {
+        if let Some(index) = self.navigation.target_position(&action, self.selected_index, self.files.len()) {
+          self.selected_index = index;
+        }
+      },
+      Action::StageFile(path) => {
+        let result = self.repo.stage_file(&path).and_then(|_| self.refresh());
+        self.maybe_handle_git_error(result.err());
+      },
+      Action::UnstageFile(path) => {
+        let result = self.repo.unstage_file(&path).and_then(|_| self.refresh());
+        self.maybe_handle_git_error(result.err());
+      },
+      Action::Refresh | Action::FetchCompleted(Ok(())) => {
+        let result = self.refresh();
+        self.maybe_handle_git_error(result.err());
+      },
+      Action::ShowBlame(path) => {
+        let result = self.show_blame(&path);
+        let opened = result.is_ok();
+        self.maybe_handle_git_error(result.err());
+        return Ok(opened.then_some(Action::StartInputMode));
+      },
+      _ => {},
+    }
+    Ok(None)
+  }
+
+  fn title(&self) -> &str {
+    "Status"
+  }
+
+  fn item_counts(&self) -> (usize, usize) {
+    (self.files.len(), self.files.len())
+  }
+
+  fn instructions(&self) -> Vec<&'static str> {
+    let mut instructions = vec!["esc: Quit", "tab: Switch view"];
+    if let Some(selected) = self.files.get(self.selected_index) {
+      instructions.push("enter: Open in editor");
+      instructions.push(if selected.staged { "u: Unstage" } else { "s: Stage" });
+      instructions.push("b: Blame");
+    }
+    instructions.push("^ + r: Refresh");
+    instructions
+  }
+
+  fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> color_eyre::Result<()> {
+    let instructions = self.instructions();
+    // The layout margin takes a column from each side
+    let footer_height = ui::instruction_footer::preferred_height(&instructions, area.width.saturating_sub(2));
+    let error_height = self.error.as_ref().map_or(0, |error| error.lines().count() + 2);
+    let layout = Layout::new(Direction::Vertical, [
+      Constraint::Min(1),
+      Constraint::Length(u16::try_from(error_height)?),
+      Constraint::Length(footer_height),
+    ])
+    .margin(1)
+    .split(area);
+    self.render_list(f, layout[0]);
+    if let Some(error) = &self.error {
+      let component = Paragraph::new(Text::from(error.as_str()))
+        .block(Block::bordered().title("Error"))
+        .style(Style::from(Color::Red))
+        .wrap(Wrap { trim: true });
+      f.render_widget(component, layout[1]);
+    }
+    ui::instruction_footer::render(f, layout[2], &instructions);
+    if let Some(blame_view) = self.blame_view.as_mut() {
+      blame_view.draw(f, area)?;
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,21 @@
+use crate::{
+  components::traits::list_data_source::ListDataSource,
+  error::Error,
+  git::git_repo::{FileStatus, GitRepo},
+};
+
+/// Loads the working tree's changes, staged ones first and each group sorted by path.
+#[derive(Debug, Default)]
+pub struct StatusDataSource {}
+
+impl ListDataSource<FileStatus> for StatusDataSource {
+  fn title(&self) -> &str {
+    "Status"
+  }
+
+  fn fetch_items(&mut self, repo: &dyn GitRepo) -> Result<Vec<FileStatus>, Error> {
+    let mut files = repo.working_status()?;
+    files.sort_by(|a, b| b.staged.cmp(&a.staged).then_with(|| a.path.cmp(&b.path)));
+    Ok(files)
+  }
+}
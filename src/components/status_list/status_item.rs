@@ -0,0 +1,24 @@
+use ratatui::{
+  style::{Color, Modifier, Style},
+  text::{Line, Span},
+  widgets::ListItem,
+};
+
+use crate::git::git_repo::{FileStatus, StatusKind};
+
+pub fn render(file: &FileStatus) -> ListItem<'_> {
+  let code = match file.status {
+    StatusKind::Modified => "M",
+    StatusKind::Added => "A",
+    StatusKind::Deleted => "D",
+    StatusKind::Renamed => "R",
+    StatusKind::Untracked => "?",
+  };
+  // Coloured like `git status`, green once staged
+  let colour = if file.staged { Color::Green } else { Color::Red };
+  let mut parts = vec![Span::styled(code, Style::default().fg(colour)), Span::raw(format!(" {}", file.path.display()))];
+  if file.staged {
+    parts.push(Span::styled(" (staged)", Style::default().add_modifier(Modifier::DIM)));
+  }
+  ListItem::from(Line::from(parts))
+}
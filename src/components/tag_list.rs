@@ -0,0 +1,213 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent};
+use ratatui::{
+  layout::{Alignment, Constraint, Direction, Layout, Rect},
+  style::{Color, Modifier, Style},
+  text::Text,
+  widgets::{block::Title, Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+};
+use tracing::error;
+
+use crate::{
+  action::Action,
+  components::{
+    list_navigation::{ListClick, ListNavigation},
+    tag_list::tag_data_source::TagDataSource,
+    traits::list_data_source::ListDataSource,
+    ui::{self, page_indicator::page_indicator, search::best_match},
+    Component,
+  },
+  error::Error,
+  git::git_repo::{GitRepo, GitTag},
+  tui::Frame,
+};
+
+mod tag_data_source;
+mod tag_item;
+
+/// Lists the repository's tags so stale ones can be deleted.
+pub struct TagList {
+  repo: Box<dyn GitRepo>,
+  error: Option<String>,
+  data_source: TagDataSource,
+  tags: Vec<GitTag>,
+  list_state: ListState,
+  selected_index: usize,
+  navigation: ListNavigation,
+}
+
+impl TagList {
+  pub fn new(repo: Box<dyn GitRepo>) -> Self {
+    let mut tag_list = TagList {
+      repo,
+      error: None,
+      data_source: TagDataSource::default(),
+      tags: Vec::new(),
+      list_state: ListState::default(),
+      selected_index: 0,
+      navigation: ListNavigation::default(),
+    };
+    let result = tag_list.refresh();
+    tag_list.maybe_handle_git_error(result.err());
+    tag_list
+  }
+
+  fn refresh(&mut self) -> Result<(), Error> {
+    self.tags = self.data_source.load(&*self.repo)?;
+    self.selected_index = self.selected_index.min(self.tags.len().saturating_sub(1));
+    Ok(())
+  }
+
+  fn select_previous(&mut self) {
+    if self.tags.is_empty() {
+      return;
+    }
+    self.selected_index = self.selected_index.checked_sub(1).unwrap_or(self.tags.len() - 1);
+  }
+
+  fn select_next(&mut self) {
+    if self.selected_index + 1 >= self.tags.len() {
+      self.selected_index = 0;
+      return;
+    }
+    self.selected_index += 1;
+  }
+
+  fn confirm_delete(&self) -> Option<Action> {
+    let selected = self.tags.get(self.selected_index)?;
+    let message = format!("Delete tag {}?", selected.name);
+    Some(Action::RequestConfirmation(message, Box::new(Action::DeleteTag)))
+  }
+
+  fn delete_selected(&mut self) -> Result<(), Error> {
+    let Some(selected) = self.tags.get(self.selected_index) else {
+      return Ok(());
+    };
+    self.repo.delete_tag(selected)?;
+    self.refresh()
+  }
+
+  fn maybe_handle_git_error(&mut self, err: Option<Error>) {
+    if let Some(error) = err {
+      error!("{}", error);
+      self.error = Some(error.to_string());
+    }
+  }
+
+  fn render_list(&mut self, f: &mut Frame<'_>, area: Rect) {
+    self.navigation.set_rendered_area(area);
+    self.list_state.select(Some(self.selected_index));
+    let render_items: Vec<ListItem> = self.tags.iter().map(tag_item::render).collect();
+    let mut block = Block::default().title(self.data_source.title()).borders(Borders::ALL);
+    // The borders take a row from the top and bottom
+    let visible_height = usize::from(area.height.saturating_sub(2));
+    if let Some(page) = page_indicator(self.selected_index, render_items.len(), visible_height) {
+      block = block.title(Title::from(page).alignment(Alignment::Right));
+    }
+    let list = List::new(render_items)
+      .block(block)
+      .style(Style::default().fg(Color::White))
+      .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+      .highlight_symbol("→")
+      .repeat_highlight_symbol(true);
+    f.render_stateful_widget(list, area, &mut self.list_state);
+  }
+}
+
+impl Component for TagList {
+  fn handle_key_events(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+    self.error = None;
+    if let Some(action) = self.navigation.handle_key_event(&key) {
+      return Ok(Some(action));
+    }
+    match key {
+      KeyEvent { code: KeyCode::Down, modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        Ok(Some(Action::SelectNextTag))
+      },
+      KeyEvent { code: KeyCode::Up, modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        Ok(Some(Action::SelectPreviousTag))
+      },
+      KeyEvent { code: KeyCode::Char('d' | 'D'), modifiers: KeyModifiers::NONE, kind: _, state: _ } => {
+        Ok(self.confirm_delete())
+      },
+      KeyEvent { code: KeyCode::Char('r' | 'R'), modifiers: KeyModifiers::CONTROL, kind: _, state: _ } => {
+        Ok(Some(Action::Refresh))
+      },
+      _ => Ok(None),
+    }
+  }
+
+  fn handle_mouse_events(&mut self, mouse: MouseEvent) -> color_eyre::Result<Option<Action>> {
+    match self.navigation.handle_mouse_event(&mouse, self.list_state.offset()) {
+      Some(ListClick::Select(position)) => Ok(Some(Action::SelectPosition(position))),
+      Some(ListClick::Activate) | None => Ok(None),
+    }
+  }
+
+  fn update(&mut self, action: Action) -> color_eyre::Result<Option<Action>> {
+    match action {
+      Action::SearchQueryChanged(query) => {
+        if let Some(best) = best_match(&query, self.tags.iter().map(|tag| tag.name.as_str())) {
+          self.selected_index = best;
+        }
+      },
+      Action::SelectNextTag => self.select_next(),
+      Action::SelectPreviousTag => self.select_previous(),
+      Action::SelectFirst | Action::SelectLast | Action::SelectPosition(_) | Action::PageDown | Action::PageUp => {
+        if let Some(index) = self.navigation.target_position(&action, self.selected_index, self.tags.len()) {
+          self.selected_index = index;
+        }
+      },
+      Action::DeleteTag => {
+        let result = self.delete_selected();
+        self.maybe_handle_git_error(result.err());
+      },
+      Action::Refresh | Action::FetchCompleted(Ok(())) => {
+        let result = self.refresh();
+        self.maybe_handle_git_error(result.err());
+      },
+      _ => {},
+    }
+    Ok(None)
+  }
+
+  fn title(&self) -> &str {
+    "Tags"
+  }
+
+  fn item_counts(&self) -> (usize, usize) {
+    (self.tags.len(), self.tags.len())
+  }
+
+  fn instructions(&self) -> Vec<&'static str> {
+    let mut instructions = vec!["esc: Quit", "tab: Switch view"];
+    if !self.tags.is_empty() {
+      instructions.push("d: Delete");
+    }
+    instructions.push("^ + r: Refresh");
+    instructions
+  }
+
+  fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> color_eyre::Result<()> {
+    let instructions = self.instructions();
+    // The layout margin takes a column from each side
+    let footer_height = ui::instruction_footer::preferred_height(&instructions, area.width.saturating_sub(2));
+    let error_height = self.error.as_ref().map_or(0, |error| error.lines().count() + 2);
+    let layout = Layout::new(Direction::Vertical, [
+      Constraint::Min(1),
+      Constraint::Length(u16::try_from(error_height)?),
+      Constraint::Length(footer_height),
+    ])
+    .margin(1)
+    .split(area);
+    self.render_list(f, layout[0]);
+    if let Some(error) = &self.error {
+      let component = Paragraph::new(Text::from(error.as_str()))
+        .block(Block::bordered().title("Error"))
+        .style(Style::from(Color::Red))
+        .wrap(Wrap { trim: true });
+      f.render_widget(component, layout[1]);
+    }
+    ui::instruction_footer::render(f, layout[2], &instructions);
+    Ok(())
+  }
+}
@@ -0,0 +1,21 @@
+use crate::{
+  components::traits::list_data_source::ListDataSource,
+  error::Error,
+  git::git_repo::{GitRepo, GitTag},
+};
+
+/// Loads tags sorted by name.
+#[derive(Debug, Default)]
+pub struct TagDataSource {}
+
+impl ListDataSource<GitTag> for TagDataSource {
+  fn title(&self) -> &str {
+    "Tags"
+  }
+
+  fn fetch_items(&mut self, repo: &dyn GitRepo) -> Result<Vec<GitTag>, Error> {
+    let mut tags = repo.list_tags()?;
+    tags.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(tags)
+  }
+}
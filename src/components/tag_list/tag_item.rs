@@ -0,0 +1,20 @@
+use ratatui::{
+  style::{Modifier, Style},
+  text::{Line, Span},
+  widgets::ListItem,
+};
+
+use crate::{components::traits::managed_item::ManagedItem, git::git_repo::GitTag};
+
+const SHORT_SHA_LENGTH: usize = 7;
+
+pub fn render(tag: &GitTag) -> ListItem<'_> {
+  let dim = Style::default().add_modifier(Modifier::DIM);
+  let mut parts = vec![Span::raw(tag.display_name())];
+  let short_sha = tag.commit_sha.get(..SHORT_SHA_LENGTH).unwrap_or(&tag.commit_sha);
+  parts.push(Span::styled(format!(" {}", short_sha), dim));
+  if tag.is_annotated {
+    parts.push(Span::styled(" (annotated)", dim));
+  }
+  ListItem::from(Line::from(parts))
+}
@@ -0,0 +1,4 @@
+pub mod has_columns;
+pub mod input_handler;
+pub mod list_data_source;
+pub mod managed_item;
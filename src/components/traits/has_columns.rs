@@ -0,0 +1,11 @@
+use ratatui::{layout::Constraint, widgets::Row};
+
+/// Items a list shows as an aligned table, a cell per column, rather than a line each.
+pub trait HasColumns {
+  /// Everything besides the item that decides how it's shown, e.g. which columns are turned on.
+  type Context;
+
+  fn column_widths(context: &Self::Context) -> Vec<Constraint>;
+
+  fn to_row(&self, context: &Self::Context) -> Row<'static>;
+}
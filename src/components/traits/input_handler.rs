@@ -0,0 +1,10 @@
+use crate::{action::Action, git::git_repo::GitRepo};
+
+/// Decides what a `GenericInput` accepts and what it dispatches, `T` is the kind of item the input creates.
+pub trait InputHandler<T> {
+  fn validate_input(&self, repo: &dyn GitRepo, input: &str) -> bool;
+  fn create_submit_action(&self, input: String) -> Action;
+  fn get_input_prompt(&self) -> Option<&str> {
+    None
+  }
+}
@@ -0,0 +1,26 @@
+use crate::{error::Error, git::git_repo::GitRepo};
+
+/// Loads the items shown by a list, `T` is the kind of item loaded.
+pub trait ListDataSource<T> {
+  fn fetch_items(&mut self, repo: &dyn GitRepo) -> Result<Vec<T>, Error>;
+
+  /// The title of the list showing the items.
+  fn title(&self) -> &str {
+    "Items"
+  }
+
+  /// Called before every fetch.
+  fn before_fetch(&mut self) {
+  }
+
+  /// Called with the fetched items so they can be post processed before being returned from `load`.
+  fn after_fetch(&mut self, _items: &mut Vec<T>) {
+  }
+
+  fn load(&mut self, repo: &dyn GitRepo) -> Result<Vec<T>, Error> {
+    self.before_fetch();
+    let mut items = self.fetch_items(repo)?;
+    self.after_fetch(&mut items);
+    Ok(items)
+  }
+}
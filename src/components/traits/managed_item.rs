@@ -0,0 +1,39 @@
+use crate::git::git_repo::{GitBranch, GitStash, GitTag};
+
+/// An item shown in one of the list views.
+pub trait ManagedItem {
+  /// The text shown for the item in the list.
+  fn display_name(&self) -> &str;
+  /// Identifies the item across refreshes, used to find it again after the list reloads.
+  fn unique_key(&self) -> &str;
+}
+
+impl ManagedItem for GitBranch {
+  fn display_name(&self) -> &str {
+    &self.name
+  }
+
+  fn unique_key(&self) -> &str {
+    &self.name
+  }
+}
+
+impl ManagedItem for GitStash {
+  fn display_name(&self) -> &str {
+    &self.message
+  }
+
+  fn unique_key(&self) -> &str {
+    &self.stash_id
+  }
+}
+
+impl ManagedItem for GitTag {
+  fn display_name(&self) -> &str {
+    &self.name
+  }
+
+  fn unique_key(&self) -> &str {
+    &self.name
+  }
+}
@@ -0,0 +1,10 @@
+pub mod ansi;
+pub mod blame_view;
+pub mod confirm_dialog;
+pub mod diff_popup;
+pub mod help_overlay;
+pub mod instruction_footer;
+pub mod notification;
+pub mod page_indicator;
+pub mod search;
+pub mod status_bar;
@@ -0,0 +1,114 @@
+use ratatui::style::{Color, Modifier, Style};
+
+use crate::components::ui::diff_popup::line_style;
+
+const COLORS: [Color; 8] =
+  [Color::Black, Color::Red, Color::Green, Color::Yellow, Color::Blue, Color::Magenta, Color::Cyan, Color::Gray];
+const BRIGHT_COLORS: [Color; 8] = [
+  Color::DarkGray,
+  Color::LightRed,
+  Color::LightGreen,
+  Color::LightYellow,
+  Color::LightBlue,
+  Color::LightMagenta,
+  Color::LightCyan,
+  Color::White,
+];
+
+/// Splits coloured git output into lines with their escape sequences removed, each styled as it was when its first
+/// visible character was printed. Lines git left uncoloured are styled by their diff prefix instead.
+pub fn styled_lines(text: &str) -> Vec<(String, Style)> {
+  let mut current = Style::default();
+  text
+    .lines()
+    .map(|line| {
+      let mut plain = String::with_capacity(line.len());
+      let mut line_style_at_start = None;
+      let mut coloured = false;
+      let mut chars = line.chars().peekable();
+      while let Some(c) = chars.next() {
+        if c == '\x1b' && chars.peek() == Some(&'[') {
+          chars.next();
+          let params: String = chars.by_ref().take_while(|&c| !c.is_ascii_alphabetic()).collect();
+          current = apply_sgr(current, &params);
+          coloured = true;
+          continue;
+        }
+        line_style_at_start.get_or_insert(current);
+        plain.push(c);
+      }
+      let style = match line_style_at_start {
+        Some(style) if coloured => style,
+        _ if coloured => current,
+        _ => line_style(&plain),
+      };
+      (plain, style)
+    })
+    .collect()
+}
+
+/// Applies the parameters of a select graphic rendition sequence, `ESC [ params m`, to `style`.
+fn apply_sgr(style: Style, params: &str) -> Style {
+  let mut codes = params.split(';').map(|code| code.parse::<u8>().unwrap_or(0));
+  let mut style = style;
+  while let Some(code) = codes.next() {
+    style = match code {
+      0 => Style::default(),
+      1 => style.add_modifier(Modifier::BOLD),
+      2 => style.add_modifier(Modifier::DIM),
+      3 => style.add_modifier(Modifier::ITALIC),
+      4 => style.add_modifier(Modifier::UNDERLINED),
+      7 => style.add_modifier(Modifier::REVERSED),
+      22 => style.remove_modifier(Modifier::BOLD | Modifier::DIM),
+      23 => style.remove_modifier(Modifier::ITALIC),
+      24 => style.remove_modifier(Modifier::UNDERLINED),
+      27 => style.remove_modifier(Modifier::REVERSED),
+      30..=37 => style.fg(COLORS[usize::from(code - 30)]),
+      38 => style.fg(extended_color(&mut codes).unwrap_or(Color::Reset)),
+      39 => style.fg(Color::Reset),
+      40..=47 => style.bg(COLORS[usize::from(code - 40)]),
+      48 => style.bg(extended_color(&mut codes).unwrap_or(Color::Reset)),
+      49 => style.bg(Color::Reset),
+      90..=97 => style.fg(BRIGHT_COLORS[usize::from(code - 90)]),
+      100..=107 => style.bg(BRIGHT_COLORS[usize::from(code - 100)]),
+      _ => style,
+    };
+  }
+  style
+}
+
+/// Reads the rest of a 256 colour, `5;n`, or true colour, `2;r;g;b`, parameter.
+fn extended_color(codes: &mut impl Iterator<Item = u8>) -> Option<Color> {
+  match codes.next()? {
+    5 => Some(Color::Indexed(codes.next()?)),
+    2 => Some(Color::Rgb(codes.next()?, codes.next()?, codes.next()?)),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn styles_lines_by_their_escape_codes() {
+    let text =
+      "\x1b[1mdiff --git a/a b/a\x1b[m\n\x1b[36m@@ -1 +1 @@\x1b[m\n context\n\x1b[31m-old\x1b[m\n\x1b[32m+new\x1b[m";
+    assert_eq!(styled_lines(text), vec![
+      (String::from("diff --git a/a b/a"), Style::default().add_modifier(Modifier::BOLD)),
+      (String::from("@@ -1 +1 @@"), Style::default().fg(Color::Cyan)),
+      (String::from(" context"), Style::default()),
+      (String::from("-old"), Style::default().fg(Color::Red)),
+      (String::from("+new"), Style::default().fg(Color::Green)),
+    ]);
+  }
+
+  #[test]
+  fn falls_back_to_diff_prefixes_without_escape_codes() {
+    assert_eq!(styled_lines("@@ -1 +1 @@\n+new\n-old"), vec![
+      (String::from("@@ -1 +1 @@"), Style::default().fg(Color::Cyan)),
+      (String::from("+new"), Style::default().fg(Color::Green)),
+      (String::from("-old"), Style::default().fg(Color::Red)),
+    ]);
+  }
+}
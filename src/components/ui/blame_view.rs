@@ -0,0 +1,103 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+  layout::{Constraint, Flex, Layout, Rect},
+  style::{Modifier, Style},
+  text::{Line, Text},
+  widgets::{Block, Clear, Paragraph},
+};
+
+use crate::{action::Action, components::Component, git::git_repo::BlameLine, tui::Frame};
+
+const PAGE_SIZE: u16 = 10;
+const HORIZONTAL_STEP: u16 = 8;
+const SHORT_SHA_LENGTH: usize = 7;
+const MAX_AUTHOR_WIDTH: usize = 20;
+
+/// A scrollable popup showing who last changed each line of a file, closing it is left to the owning component. Long
+/// lines aren't wrapped so they stay aligned with their author, they scroll sideways instead.
+pub struct BlameView {
+  title: String,
+  lines: Vec<BlameLine>,
+  scroll: u16,
+  horizontal_scroll: u16,
+}
+
+impl BlameView {
+  pub fn new(title: String, lines: Vec<BlameLine>) -> Self {
+    BlameView { title, lines, scroll: 0, horizontal_scroll: 0 }
+  }
+
+  fn max_scroll(&self) -> u16 {
+    u16::try_from(self.lines.len().saturating_sub(1)).unwrap_or(u16::MAX)
+  }
+
+  /// The sha, author and date for each line, left blank on lines from the same commit as the line above.
+  fn annotations(&self) -> Vec<String> {
+    let mut previous_sha = None;
+    self
+      .lines
+      .iter()
+      .map(|line| {
+        if previous_sha.replace(&line.sha) == Some(&line.sha) {
+          return String::new();
+        }
+        let short_sha = line.sha.get(..SHORT_SHA_LENGTH).unwrap_or(&line.sha);
+        let author: String = line.author.chars().take(MAX_AUTHOR_WIDTH).collect();
+        format!("{} {} {}", short_sha, author, line.date)
+      })
+      .collect()
+  }
+}
+
+impl Component for BlameView {
+  fn handle_key_events(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+    match key.code {
+      KeyCode::Down => self.scroll = self.scroll.saturating_add(1).min(self.max_scroll()),
+      KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+      KeyCode::PageDown => self.scroll = self.scroll.saturating_add(PAGE_SIZE).min(self.max_scroll()),
+      KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(PAGE_SIZE),
+      KeyCode::Char('>') | KeyCode::Right => {
+        self.horizontal_scroll = self.horizontal_scroll.saturating_add(HORIZONTAL_STEP)
+      },
+      KeyCode::Char('<') | KeyCode::Left => {
+        self.horizontal_scroll = self.horizontal_scroll.saturating_sub(HORIZONTAL_STEP)
+      },
+      _ => {},
+    }
+    Ok(None)
+  }
+
+  fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> color_eyre::Result<()> {
+    let [popup] = Layout::horizontal([Constraint::Percentage(90)]).flex(Flex::Center).areas(area);
+    let [popup] = Layout::vertical([Constraint::Percentage(90)]).flex(Flex::Center).areas(popup);
+    let block =
+      Block::bordered().title(self.title.as_str()).title_bottom("esc: Close | ↑/↓: Scroll | </>: Scroll sideways");
+    let inner = block.inner(popup);
+    f.render_widget(Clear, popup);
+    f.render_widget(block, popup);
+
+    let annotations = self.annotations();
+    let annotation_width = annotations.iter().map(|annotation| annotation.chars().count()).max().unwrap_or(0);
+    let number_width = self.lines.len().to_string().len();
+    // Only the content scrolls sideways, the annotations and line numbers stay put
+    let [annotation_area, number_area, content_area] = Layout::horizontal([
+      Constraint::Length(u16::try_from(annotation_width + 1).unwrap_or(u16::MAX)),
+      Constraint::Length(u16::try_from(number_width + 1).unwrap_or(u16::MAX)),
+      Constraint::Fill(1),
+    ])
+    .areas(inner);
+    let dim = Style::default().add_modifier(Modifier::DIM);
+    let annotation_lines: Vec<Line> = annotations.into_iter().map(|annotation| Line::styled(annotation, dim)).collect();
+    f.render_widget(Paragraph::new(Text::from(annotation_lines)).scroll((self.scroll, 0)), annotation_area);
+    let number_lines: Vec<Line> = self
+      .lines
+      .iter()
+      .map(|line| Line::styled(format!("{:>width$}", line.line_number, width = number_width), dim))
+      .collect();
+    f.render_widget(Paragraph::new(Text::from(number_lines)).scroll((self.scroll, 0)), number_area);
+    let content_lines: Vec<Line> = self.lines.iter().map(|line| Line::from(line.content.as_str())).collect();
+    let content = Paragraph::new(Text::from(content_lines)).scroll((self.scroll, self.horizontal_scroll));
+    f.render_widget(content, content_area);
+    Ok(())
+  }
+}
@@ -0,0 +1,44 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+  layout::{Constraint, Flex, Layout, Rect},
+  style::{Color, Style},
+  text::{Line, Text},
+  widgets::{Block, Clear, Paragraph, Wrap},
+};
+
+use crate::{action::Action, components::Component, tui::Frame};
+
+/// A centered popup asking the user to confirm an action before it is dispatched.
+pub struct ConfirmDialog {
+  message: String,
+  on_confirm: Action,
+}
+
+impl ConfirmDialog {
+  pub fn new(message: String, on_confirm: Action) -> Self {
+    ConfirmDialog { message, on_confirm }
+  }
+}
+
+impl Component for ConfirmDialog {
+  fn handle_key_events(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+    match key.code {
+      KeyCode::Char('y' | 'Y') => Ok(Some(self.on_confirm.clone())),
+      KeyCode::Char('n' | 'N') | KeyCode::Esc | KeyCode::Enter => Ok(Some(Action::CloseConfirmDialog)),
+      _ => Ok(None),
+    }
+  }
+
+  fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> color_eyre::Result<()> {
+    let [popup] = Layout::horizontal([Constraint::Percentage(60)]).flex(Flex::Center).areas(area);
+    let [popup] = Layout::vertical([Constraint::Length(6)]).flex(Flex::Center).areas(popup);
+    let text = Text::from(vec![Line::from(self.message.clone()), Line::default(), Line::from("[y]es / [N]o")]);
+    let dialog = Paragraph::new(text)
+      .block(Block::bordered().title("Confirm"))
+      .style(Style::default().fg(Color::Yellow))
+      .wrap(Wrap { trim: true });
+    f.render_widget(Clear, popup);
+    f.render_widget(dialog, popup);
+    Ok(())
+  }
+}
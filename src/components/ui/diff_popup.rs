@@ -0,0 +1,72 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+  layout::{Constraint, Flex, Layout, Rect},
+  style::{Color, Style},
+  text::{Line, Text},
+  widgets::{Block, Clear, Paragraph},
+};
+
+use crate::{action::Action, components::Component, tui::Frame};
+
+const PAGE_SIZE: u16 = 10;
+
+/// A scrollable popup showing a diff, closing it is left to the owning component.
+pub struct DiffPopup {
+  title: String,
+  diff: String,
+  scroll: u16,
+}
+
+impl DiffPopup {
+  pub fn new(title: String, diff: String) -> Self {
+    DiffPopup { title, diff, scroll: 0 }
+  }
+
+  fn max_scroll(&self) -> u16 {
+    u16::try_from(self.diff.lines().count().saturating_sub(1)).unwrap_or(u16::MAX)
+  }
+}
+
+/// Colours a line of `git show` or `git diff` output.
+pub fn line_style(line: &str) -> Style {
+  if line.starts_with("@@") {
+    Style::default().fg(Color::Cyan)
+  } else if line.starts_with("commit ")
+    || line.starts_with("Author:")
+    || line.starts_with("Date:")
+    || line.starts_with("Merge:")
+  {
+    Style::default().fg(Color::Yellow)
+  } else if line.starts_with('+') {
+    Style::default().fg(Color::Green)
+  } else if line.starts_with('-') {
+    Style::default().fg(Color::Red)
+  } else {
+    Style::default()
+  }
+}
+
+impl Component for DiffPopup {
+  fn handle_key_events(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+    self.scroll = match key.code {
+      KeyCode::Down => self.scroll.saturating_add(1).min(self.max_scroll()),
+      KeyCode::Up => self.scroll.saturating_sub(1),
+      KeyCode::PageDown => self.scroll.saturating_add(PAGE_SIZE).min(self.max_scroll()),
+      KeyCode::PageUp => self.scroll.saturating_sub(PAGE_SIZE),
+      _ => self.scroll,
+    };
+    Ok(None)
+  }
+
+  fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> color_eyre::Result<()> {
+    let [popup] = Layout::horizontal([Constraint::Percentage(90)]).flex(Flex::Center).areas(area);
+    let [popup] = Layout::vertical([Constraint::Percentage(90)]).flex(Flex::Center).areas(popup);
+    let lines: Vec<Line> = self.diff.lines().map(|line| Line::styled(line, line_style(line))).collect();
+    let diff = Paragraph::new(Text::from(lines))
+      .block(Block::bordered().title(self.title.as_str()).title_bottom("esc: Close | ↑/↓: Scroll"))
+      .scroll((self.scroll, 0));
+    f.render_widget(Clear, popup);
+    f.render_widget(diff, popup);
+    Ok(())
+  }
+}
@@ -0,0 +1,91 @@
+use crossterm::event::KeyEvent;
+use ratatui::{
+  layout::{Constraint, Flex, Layout, Rect},
+  style::{Modifier, Style},
+  widgets::{Block, Cell, Clear, Row, Table},
+};
+
+use crate::{action::Action, components::Component, tui::Frame};
+
+/// A centered popup listing every key the active view and the app respond to, any key closes it.
+pub struct HelpOverlay {
+  title: String,
+  // `key: Description` pairs, the view's keys first
+  instructions: Vec<&'static str>,
+}
+
+impl HelpOverlay {
+  /// `global` keys already listed by the view are only shown once.
+  pub fn new(view_title: &str, view: Vec<&'static str>, global: &[&'static str]) -> Self {
+    let mut instructions = view;
+    for instruction in global {
+      if !instructions.contains(instruction) {
+        instructions.push(instruction);
+      }
+    }
+    HelpOverlay { title: format!("Help: {}", view_title), instructions }
+  }
+
+  fn rows(&self) -> Vec<Row<'static>> {
+    self
+      .instructions
+      .iter()
+      .map(|instruction| {
+        let (key, description) = instruction.split_once(": ").unwrap_or((instruction, ""));
+        Row::new([Cell::from(key).style(Style::default().add_modifier(Modifier::BOLD)), Cell::from(description)])
+      })
+      .collect()
+  }
+}
+
+impl Component for HelpOverlay {
+  fn handle_key_events(&mut self, _key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+    Ok(Some(Action::CloseHelp))
+  }
+
+  fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> color_eyre::Result<()> {
+    // The borders take a row from the top and bottom
+    let height = u16::try_from(self.instructions.len() + 2)?;
+    let [popup] = Layout::horizontal([Constraint::Percentage(60)]).flex(Flex::Center).areas(area);
+    let [popup] = Layout::vertical([Constraint::Length(height)]).flex(Flex::Center).areas(popup);
+    let key_width = self
+      .instructions
+      .iter()
+      .map(|instruction| instruction.split_once(": ").map_or(*instruction, |(key, _)| key).chars().count())
+      .max();
+    let table =
+      Table::new(self.rows(), [Constraint::Length(u16::try_from(key_width.unwrap_or(0))?), Constraint::Fill(1)])
+        .column_spacing(2)
+        .block(Block::bordered().title(self.title.as_str()).title_bottom("Press any key to close"));
+    f.render_widget(Clear, popup);
+    f.render_widget(table, popup);
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ratatui::{backend::TestBackend, Terminal};
+
+  use super::*;
+
+  #[test]
+  fn lists_the_view_keys_then_the_global_ones() {
+    let mut overlay = HelpOverlay::new("Branches", vec!["c: Checkout", "?: Help"], &["?: Help", "esc: Quit"]);
+    let mut terminal = Terminal::new(TestBackend::new(50, 7)).unwrap();
+    terminal.draw(|f| overlay.draw(f, f.area()).unwrap()).unwrap();
+    let buffer = terminal.backend().buffer();
+    let lines: Vec<String> = (0..buffer.area.height)
+      .map(|y| (0..buffer.area.width).map(|x| buffer[(x, y)].symbol()).collect::<String>().trim_end().to_string())
+      .collect();
+    assert_eq!(lines, [
+      "",
+      "          ┌Help: Branches──────────────┐",
+      "          │c    Checkout               │",
+      "          │?    Help                   │",
+      "          │esc  Quit                   │",
+      "          └Press any key to close──────┘",
+      "",
+    ]);
+  }
+}
@@ -0,0 +1,105 @@
+use ratatui::{
+  layout::Rect,
+  text::Line,
+  widgets::{Paragraph, Wrap},
+};
+
+use crate::tui::Frame;
+
+const SEPARATOR: &str = " | ";
+const MIN_HEIGHT: u16 = 1;
+const MAX_HEIGHT: u16 = 3;
+
+/// The number of lines the instructions need when wrapped to `area_width`, clamped so the footer never takes over the
+/// list.
+pub fn preferred_height(instructions: &[&str], area_width: u16) -> u16 {
+  // A single instruction wider than the area wraps onto extra rows
+  let width = usize::from(area_width.max(1));
+  let rows: usize =
+    wrap_instructions(instructions, area_width).iter().map(|line| line.chars().count().div_ceil(width).max(1)).sum();
+  u16::try_from(rows).unwrap_or(MAX_HEIGHT).clamp(MIN_HEIGHT, MAX_HEIGHT)
+}
+
+pub fn render(f: &mut Frame<'_>, area: Rect, instructions: &[&str]) {
+  let lines: Vec<Line> = wrap_instructions(instructions, area.width).into_iter().map(Line::from).collect();
+  f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: true }), area);
+}
+
+/// Packs instructions greedily into lines so an instruction is never split across two lines.
+fn wrap_instructions(instructions: &[&str], area_width: u16) -> Vec<String> {
+  let width = usize::from(area_width);
+  let mut lines: Vec<String> = vec![];
+  let mut current = String::new();
+  for instruction in instructions {
+    if current.is_empty() {
+      current.push_str(instruction);
+    } else if current.chars().count() + SEPARATOR.len() + instruction.chars().count() <= width {
+      current.push_str(SEPARATOR);
+      current.push_str(instruction);
+    } else {
+      lines.push(std::mem::take(&mut current));
+      current.push_str(instruction);
+    }
+  }
+  if !current.is_empty() {
+    lines.push(current);
+  }
+  lines
+}
+
+#[cfg(test)]
+mod tests {
+  use ratatui::{backend::TestBackend, Terminal};
+
+  use super::*;
+
+  const INSTRUCTIONS: [&str; 8] = [
+    "c: Checkout",
+    "d: Stage for deletion",
+    "shift + c: Create branch",
+    "^ + d: Delete staged branches",
+    "r: Rename",
+    "p: Push",
+    "f: Fetch",
+    "^ + r: Refresh",
+  ];
+
+  #[test]
+  fn a_short_list_takes_one_line() {
+    assert_eq!(preferred_height(&INSTRUCTIONS[..3], 80), 1);
+  }
+
+  #[test]
+  fn a_long_list_takes_more_lines() {
+    assert_eq!(preferred_height(&INSTRUCTIONS, 80), 2);
+  }
+
+  #[test]
+  fn the_height_is_clamped() {
+    assert_eq!(preferred_height(&INSTRUCTIONS, 20), 3);
+    assert_eq!(preferred_height(&[], 80), 1);
+  }
+
+  fn rendered(instructions: &[&str], width: u16) -> Vec<String> {
+    let height = preferred_height(instructions, width);
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    terminal.draw(|f| render(f, f.area(), instructions)).unwrap();
+    let buffer = terminal.backend().buffer();
+    (0..height)
+      .map(|y| (0..width).map(|x| buffer[(x, y)].symbol()).collect::<String>().trim_end().to_string())
+      .collect()
+  }
+
+  #[test]
+  fn instructions_wrap_between_instructions() {
+    assert_eq!(rendered(&INSTRUCTIONS[..4], 60), [
+      "c: Checkout | d: Stage for deletion",
+      "shift + c: Create branch | ^ + d: Delete staged branches",
+    ]);
+  }
+
+  #[test]
+  fn an_instruction_wider_than_the_footer_wraps_without_clipping() {
+    assert_eq!(rendered(&["^ + d: Delete staged branches"], 20), ["^ + d: Delete staged", "branches"]);
+  }
+}
@@ -0,0 +1,69 @@
+use std::time::{Duration, Instant};
+
+use ratatui::{
+  layout::{Constraint, Flex, Layout, Rect},
+  style::{Color, Style},
+  widgets::{Block, Clear, Paragraph, Wrap},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{components::Component, tui::Frame};
+
+const DISPLAY_DURATION: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NotificationLevel {
+  Info,
+  Success,
+  Warning,
+}
+
+impl NotificationLevel {
+  fn title(&self) -> &'static str {
+    match self {
+      NotificationLevel::Info => "Info",
+      NotificationLevel::Success => "Success",
+      NotificationLevel::Warning => "Warning",
+    }
+  }
+
+  fn color(&self) -> Color {
+    match self {
+      NotificationLevel::Info => Color::Cyan,
+      NotificationLevel::Success => Color::Green,
+      NotificationLevel::Warning => Color::Yellow,
+    }
+  }
+}
+
+/// A short lived message shown in the bottom right corner, it is dismissed by the app once it expires.
+pub struct Notification {
+  message: String,
+  level: NotificationLevel,
+  shown_at: Instant,
+}
+
+impl Notification {
+  pub fn new(message: String, level: NotificationLevel) -> Self {
+    Notification { message, level, shown_at: Instant::now() }
+  }
+
+  pub fn is_expired(&self) -> bool {
+    self.shown_at.elapsed() >= DISPLAY_DURATION
+  }
+}
+
+impl Component for Notification {
+  fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> color_eyre::Result<()> {
+    let height = u16::try_from(self.message.lines().count() + 2)?;
+    let [popup] = Layout::horizontal([Constraint::Percentage(40)]).flex(Flex::End).areas(area);
+    let [popup] = Layout::vertical([Constraint::Length(height)]).flex(Flex::End).areas(popup);
+    let notification = Paragraph::new(self.message.clone())
+      .block(Block::bordered().title(self.level.title()))
+      .style(Style::default().fg(self.level.color()))
+      .wrap(Wrap { trim: true });
+    f.render_widget(Clear, popup);
+    f.render_widget(notification, popup);
+    Ok(())
+  }
+}
@@ -0,0 +1,36 @@
+/// Which page of the list the selection is on, e.g. `3/12`, or `None` when every item fits on one page.
+pub fn page_indicator(selected: usize, total: usize, visible: usize) -> Option<String> {
+  if visible == 0 {
+    return None;
+  }
+  let total_pages = total.div_ceil(visible);
+  if total_pages <= 1 {
+    return None;
+  }
+  let current_page = (selected / visible + 1).min(total_pages);
+  Some(format!("{}/{}", current_page, total_pages))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn first_and_last_pages() {
+    assert_eq!(page_indicator(0, 25, 10).as_deref(), Some("1/3"));
+    assert_eq!(page_indicator(24, 25, 10).as_deref(), Some("3/3"));
+  }
+
+  #[test]
+  fn an_exact_multiple_of_the_page_size_has_no_extra_page() {
+    assert_eq!(page_indicator(19, 20, 10).as_deref(), Some("2/2"));
+    assert_eq!(page_indicator(10, 20, 10).as_deref(), Some("2/2"));
+    assert_eq!(page_indicator(9, 10, 10), None);
+  }
+
+  #[test]
+  fn nothing_for_an_empty_list() {
+    assert_eq!(page_indicator(0, 0, 10), None);
+    assert_eq!(page_indicator(0, 0, 0), None);
+  }
+}
@@ -0,0 +1,76 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+  layout::{Constraint, Layout, Rect},
+  widgets::{Block, Clear, Paragraph},
+};
+
+use crate::{action::Action, components::Component, tui::Frame};
+
+/// A search bar shown over the active view, each change to the query is sent on for the view to rank its items.
+#[derive(Debug, Default)]
+pub struct SearchComponent {
+  query: String,
+}
+
+impl Component for SearchComponent {
+  fn handle_key_events(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+    match key {
+      KeyEvent { code: KeyCode::Esc | KeyCode::Enter, modifiers: _, kind: _, state: _ } => Ok(Some(Action::EndSearch)),
+      KeyEvent { code: KeyCode::Backspace, modifiers: _, kind: _, state: _ } => {
+        self.query.pop();
+        Ok(Some(Action::SearchQueryChanged(self.query.clone())))
+      },
+      KeyEvent { code: KeyCode::Char(c), modifiers: KeyModifiers::NONE | KeyModifiers::SHIFT, kind: _, state: _ } => {
+        self.query.push(c);
+        Ok(Some(Action::SearchQueryChanged(self.query.clone())))
+      },
+      _ => Ok(None),
+    }
+  }
+
+  fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> color_eyre::Result<()> {
+    let [_, bar] = Layout::vertical([Constraint::Fill(1), Constraint::Length(3)]).areas(area);
+    let search = Paragraph::new(self.query.as_str())
+      .block(Block::bordered().title("Search").title_bottom("↑/↓: Select | enter: Go to selection | esc: Close"));
+    f.render_widget(Clear, bar);
+    f.render_widget(search, bar);
+    Ok(())
+  }
+}
+
+/// Scores how well `candidate` matches `query`, `None` unless every character of the query appears in order. Runs of
+/// consecutive characters and matches at the start of words score higher.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<u32> {
+  let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+  let mut score = 0;
+  let mut position = 0;
+  let mut previous_match: Option<usize> = None;
+  for query_char in query.to_lowercase().chars() {
+    let offset = candidate[position..].iter().position(|&c| c == query_char)?;
+    let index = position + offset;
+    score += 1;
+    if previous_match.is_some_and(|previous| previous + 1 == index) {
+      score += 5;
+    }
+    if index == 0 || matches!(candidate[index - 1], '/' | '-' | '_' | ' ' | '.') {
+      score += 3;
+    }
+    previous_match = Some(index);
+    position = index + 1;
+  }
+  Some(score)
+}
+
+/// The index of the candidate that best matches `query`, the first one wins a tie.
+pub fn best_match<'a>(query: &str, candidates: impl Iterator<Item = &'a str>) -> Option<usize> {
+  candidates
+    .enumerate()
+    .filter_map(|(index, candidate)| Some((index, fuzzy_score(query, candidate)?)))
+    .fold(None, |best: Option<(usize, u32)>, (index, score)| {
+      match best {
+        Some((_, best_score)) if best_score >= score => best,
+        _ => Some((index, score)),
+      }
+    })
+    .map(|(index, _)| index)
+}
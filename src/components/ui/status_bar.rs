@@ -0,0 +1,76 @@
+use ratatui::{
+  layout::{Alignment, Constraint, Layout, Rect},
+  style::{Color, Modifier, Style},
+  text::{Line, Span},
+  widgets::Paragraph,
+};
+
+use crate::{components::Component, git::git_repo::RepoState, tui::Frame};
+
+/// A single line below the active view, where the repository is and what is checked out on the left and badges
+/// describing the repository on the right.
+#[derive(Debug, Default)]
+pub struct StatusBar {
+  repo_path: String,
+  // The checked out branch and its short sha, unknown in a repository without commits
+  head: Option<(String, String)>,
+  repo_state: RepoState,
+  // Visible and total items in the active view
+  item_counts: (usize, usize),
+  // Seconds between automatic refreshes, when they're on
+  auto_refresh_secs: Option<f64>,
+}
+
+impl StatusBar {
+  pub fn set_item_counts(&mut self, item_counts: (usize, usize)) {
+    self.item_counts = item_counts;
+  }
+
+  pub fn set_repo_path(&mut self, repo_path: String) {
+    self.repo_path = repo_path;
+  }
+
+  pub fn set_head(&mut self, head: Option<(String, String)>) {
+    self.head = head;
+  }
+
+  pub fn set_repo_state(&mut self, repo_state: RepoState) {
+    self.repo_state = repo_state;
+  }
+
+  pub fn set_auto_refresh(&mut self, auto_refresh_secs: Option<f64>) {
+    self.auto_refresh_secs = auto_refresh_secs;
+  }
+}
+
+impl Component for StatusBar {
+  fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> color_eyre::Result<()> {
+    let (visible, total) = self.item_counts;
+    let counts = if visible == total { format!("{} items", total) } else { format!("{}/{} items", visible, total) };
+    let mut spans = vec![Span::styled(counts, Style::default().add_modifier(Modifier::DIM))];
+    if let Some(secs) = self.auto_refresh_secs {
+      spans.push(Span::raw(" | "));
+      spans.push(Span::styled(format!("🔄 auto-refresh: {}s", secs), Style::default().add_modifier(Modifier::DIM)));
+    }
+    if self.repo_state != RepoState::Clean {
+      spans.push(Span::raw(" | "));
+      spans.push(Span::styled(
+        format!("[{}]", self.repo_state.label()),
+        Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+      ));
+    }
+    let badges = Line::from(spans);
+    let [location_area, badges_area] =
+      Layout::horizontal([Constraint::Fill(1), Constraint::Length(u16::try_from(badges.width())?)]).areas(area);
+    let mut location = vec![Span::styled(self.repo_path.as_str(), Style::default().add_modifier(Modifier::DIM))];
+    if let Some((branch, sha)) = &self.head {
+      let branch = if branch == "HEAD" { "(detached)" } else { branch.as_str() };
+      location.push(Span::raw(" "));
+      location.push(Span::styled(branch, Style::default().fg(Color::Green)));
+      location.push(Span::styled(format!(" @ {}", sha), Style::default().add_modifier(Modifier::DIM)));
+    }
+    f.render_widget(Paragraph::new(Line::from(location)), location_area);
+    f.render_widget(Paragraph::new(badges).alignment(Alignment::Right), badges_area);
+    Ok(())
+  }
+}
@@ -0,0 +1,19 @@
+use crate::{
+  components::traits::list_data_source::ListDataSource,
+  error::Error,
+  git::git_repo::{GitRepo, GitWorktree},
+};
+
+/// Loads worktrees in the order git lists them, the main worktree first.
+#[derive(Debug, Default)]
+pub struct WorktreeDataSource {}
+
+impl ListDataSource<GitWorktree> for WorktreeDataSource {
+  fn title(&self) -> &str {
+    "Worktrees"
+  }
+
+  fn fetch_items(&mut self, repo: &dyn GitRepo) -> Result<Vec<GitWorktree>, Error> {
+    repo.list_worktrees()
+  }
+}
@@ -0,0 +1,25 @@
+use ratatui::{
+  style::{Modifier, Style},
+  text::{Line, Span},
+  widgets::ListItem,
+};
+
+use crate::git::git_repo::GitWorktree;
+
+pub fn render(worktree: &GitWorktree) -> ListItem<'_> {
+  let dim = Style::default().add_modifier(Modifier::DIM);
+  let mut parts = vec![Span::raw(worktree.path.display().to_string())];
+  match &worktree.branch {
+    Some(branch) => parts.push(Span::styled(format!(" [{}]", branch), dim)),
+    None if !worktree.is_bare => parts.push(Span::styled(" (detached)", dim)),
+    None => {},
+  }
+  for (applies, label) in
+    [(worktree.is_main, " (main)"), (worktree.is_bare, " (bare)"), (worktree.is_locked, " (locked)")]
+  {
+    if applies {
+      parts.push(Span::styled(label, dim));
+    }
+  }
+  ListItem::from(Line::from(parts))
+}
@@ -1,8 +1,21 @@
-use std::path::PathBuf;
+use std::{collections::HashMap, ops::RangeInclusive, path::PathBuf, sync::Arc};
 
 use color_eyre::eyre::Result;
 use serde::Deserialize;
 
+use crate::{
+  action::Action,
+  config::{keybindings::KeyBindingConfig, theme::Theme},
+  git::any_git_repo::GitRepoBackend,
+  mode::Mode,
+};
+
+pub mod keybindings;
+pub mod theme;
+
+/// Frame and tick rates outside this many per second are rejected.
+pub const RATE_RANGE: RangeInclusive<f64> = 1.0..=120.0;
+
 #[derive(Clone, Debug, Deserialize, Default)]
 pub struct AppConfig {
   #[serde(default)]
@@ -11,10 +24,80 @@ pub struct AppConfig {
   pub _config_dir: PathBuf,
 }
 
+/// The optional columns of the branch list, the name is always shown.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct BranchListColumns {
+  pub upstream: bool,
+  pub ahead_behind: bool,
+  pub last_commit_date: bool,
+}
+
+impl Default for BranchListColumns {
+  fn default() -> Self {
+    BranchListColumns { upstream: true, ahead_behind: true, last_commit_date: false }
+  }
+}
+
 #[derive(Clone, Debug, Default, Deserialize)]
 pub struct Config {
   #[serde(default, flatten)]
   pub config: AppConfig,
+  /// Keep the selected item (and scroll position) when a list is refreshed, otherwise reset to the top.
+  #[serde(default)]
+  pub preserve_selection_on_refresh: bool,
+  /// Colour branch names by how long ago they were last committed to.
+  #[serde(default)]
+  pub show_branch_age: bool,
+  /// Which columns the branch list shows, e.g. `{ last_commit_date: true }`.
+  #[serde(default)]
+  pub branch_list_columns: BranchListColumns,
+  /// Refresh requests arriving within this many milliseconds of the last refresh are skipped.
+  #[serde(default)]
+  pub refresh_debounce_ms: u64,
+  /// The branch others are compared against in the branch details panel.
+  #[serde(default)]
+  pub main_branch: String,
+  /// Branches that can't be deleted from their remote.
+  #[serde(default)]
+  pub protected_branches: Vec<String>,
+  /// Only show this many branches, the checked out branch is always shown. No limit when unset.
+  #[serde(default)]
+  pub max_branches_displayed: Option<usize>,
+  /// App wide keys per mode, e.g. `{ Default: { "ctrl+q": "Quit" } }`, added to the default bindings.
+  #[serde(default)]
+  pub keybindings: HashMap<Mode, HashMap<String, Action>>,
+  /// Branch list keys, read from `keybindings.toml` rather than the main config file.
+  #[serde(skip)]
+  pub branch_keybindings: KeyBindingConfig,
+  /// Colours, read from `theme.toml` rather than the main config file.
+  #[serde(skip)]
+  pub theme: Arc<Theme>,
+  /// Announce the selected item as it changes, for screen readers.
+  #[serde(default)]
+  pub accessibility_mode: bool,
+  /// Where announcements are written, a file or pipe. Defaults to `announcements` in the state directory.
+  #[serde(default)]
+  pub announcement_file: Option<PathBuf>,
+  /// The git to run, a path or a name looked up on `PATH`.
+  #[serde(default)]
+  pub git_executable: PathBuf,
+  /// Frames rendered per second, lower values use less CPU on slow terminals.
+  #[serde(default)]
+  pub frame_rate: f64,
+  /// Ticks per second.
+  #[serde(default)]
+  pub tick_rate: f64,
+  /// Refresh every view this often, in seconds. No auto refresh when unset.
+  #[serde(default)]
+  pub refresh_interval_secs: Option<f64>,
+  /// Stash with git's hooks turned off. This intentionally bypasses every hook, for repositories whose hooks break
+  /// stashing, so only enable it if that is what you want.
+  #[serde(default)]
+  pub no_verify_stash: bool,
+  /// The git backend to try first, `cli` or `git2`. The other is used if it can't open the repository.
+  #[serde(default)]
+  pub preferred: GitRepoBackend,
 }
 
 impl Config {
@@ -23,7 +106,14 @@ impl Config {
     let config_dir = crate::utils::get_config_dir();
     let mut builder = config::Config::builder()
       .set_default("_data_dir", data_dir.to_str().unwrap())?
-      .set_default("_config_dir", config_dir.to_str().unwrap())?;
+      .set_default("_config_dir", config_dir.to_str().unwrap())?
+      .set_default("preserve_selection_on_refresh", true)?
+      .set_default("refresh_debounce_ms", 100)?
+      .set_default("main_branch", "main")?
+      .set_default("protected_branches", vec!["main", "master", "develop"])?
+      .set_default("git_executable", "git")?
+      .set_default("frame_rate", 30.0)?
+      .set_default("tick_rate", 10.0)?;
 
     let config_files = [
       ("config.json5", config::FileFormat::Json5),
@@ -43,8 +133,25 @@ impl Config {
       log::error!("No configuration file found. Application may not behave as expected");
     }
 
-    let cfg: Self = builder.build()?.try_deserialize()?;
+    let mut cfg: Self = builder.build()?.try_deserialize()?;
+    cfg.branch_keybindings = KeyBindingConfig::load(&config_dir);
+    cfg.theme = Arc::new(Theme::load(&config_dir));
 
     Ok(cfg)
   }
+
+  pub fn validate(&self) -> Result<(), String> {
+    if !RATE_RANGE.contains(&self.frame_rate) {
+      return Err(format!("frame_rate must be between 1 and 120 fps, got {}", self.frame_rate));
+    }
+    if !RATE_RANGE.contains(&self.tick_rate) {
+      return Err(format!("tick_rate must be between 1 and 120 Hz, got {}", self.tick_rate));
+    }
+    if let Some(interval) = self.refresh_interval_secs {
+      if !(interval.is_finite() && interval > 0.0) {
+        return Err(format!("refresh_interval_secs must be greater than 0, got {}", interval));
+      }
+    }
+    Ok(())
+  }
 }
@@ -0,0 +1,163 @@
+use std::{collections::HashMap, path::Path};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Deserializer};
+use tracing::error;
+
+/// The branch list's actions with their default keys. Names match the `Action` the key leads to, except
+/// `ForcePushBranch` which is `PushBranch(true)`.
+const DEFAULT_BINDINGS: &[(&str, &[&str])] = &[
+  ("SelectNextBranch", &["down"]),
+  ("SelectPreviousBranch", &["up"]),
+  ("InitNewBranch", &["shift+c"]),
+  ("CheckoutSelectedBranch", &["c"]),
+  ("StageBranchForDeletion", &["d"]),
+  ("UnstageBranchForDeletion", &["shift+d"]),
+  ("DeleteStagedBranches", &["ctrl+d"]),
+  ("Refresh", &["ctrl+r"]),
+  ("InitExportBranches", &["ctrl+e"]),
+  ("PullAllBranches", &["shift+f"]),
+  ("PruneGoneBranches", &["ctrl+g"]),
+  ("PushBranch", &["p"]),
+  ("ForcePushBranch", &["ctrl+p"]),
+  ("PullCurrentBranch", &["shift+p"]),
+  ("FetchRemote", &["f"]),
+  ("ToggleBranchDetails", &["shift+i"]),
+  ("ToggleBranchGrouping", &["b"]),
+  ("ShowSelectedCommitDiff", &["v"]),
+  ("ShowCommitLog", &["shift+l"]),
+  ("ShowBranchDiff", &["shift+v"]),
+  ("CopyBranchName", &["y"]),
+  ("DeleteRemoteBranch", &["shift+x"]),
+  ("InitRenameBranch", &["r"]),
+  ("InitSetUpstream", &["u"]),
+  ("ActivateFilter", &["/"]),
+  ("InitNewWorktree", &["w"]),
+  ("CycleSortOrder", &["o"]),
+  ("MergeSelectedBranch", &["m"]),
+  ("RebaseOntoSelected", &["shift+r"]),
+  ("ToggleMultiSelect", &["space"]),
+  ("SelectAll", &["ctrl+a"]),
+];
+
+/// Keys for the branch list by action name, loaded from `keybindings.toml` in the config directory, e.g.
+/// `CheckoutSelectedBranch = ["c", "enter"]`. Actions left out keep their default keys. `ctrl+c` always quits so it
+/// can't be bound.
+#[derive(Clone, Debug)]
+pub struct KeyBindingConfig {
+  bindings: HashMap<&'static str, Vec<KeyEvent>>,
+}
+
+impl KeyBindingConfig {
+  /// The defaults overlaid with `keybindings.toml` from `config_dir`, when there is one.
+  pub fn load(config_dir: &Path) -> Self {
+    let path = config_dir.join("keybindings.toml");
+    if !path.exists() {
+      return KeyBindingConfig::default();
+    }
+    let loaded = config::Config::builder()
+      .add_source(config::File::from(path).format(config::FileFormat::Toml))
+      .build()
+      .and_then(|loaded| loaded.try_deserialize());
+    loaded.unwrap_or_else(|err| {
+      error!("Ignoring keybindings.toml, it could not be read: {}", err);
+      KeyBindingConfig::default()
+    })
+  }
+
+  fn bind(&mut self, name: &str, keys: Vec<KeyEvent>) {
+    // The config loader lowercases keys so names are matched regardless of case
+    let Some(&(action_name, _)) = DEFAULT_BINDINGS.iter().find(|(action, _)| action.eq_ignore_ascii_case(name)) else {
+      error!("Ignoring keybinding for unknown action: {}", name);
+      return;
+    };
+    let (keys, quit_keys): (Vec<KeyEvent>, Vec<KeyEvent>) = keys.into_iter().partition(|key| !is_quit(key));
+    if !quit_keys.is_empty() {
+      error!("Ignoring ctrl+c for {}, it always quits", action_name);
+    }
+    self.bindings.insert(action_name, keys);
+  }
+
+  /// The name of the action bound to `key`, if any.
+  pub fn action_for(&self, key: &KeyEvent) -> Option<&'static str> {
+    self.bindings.iter().find(|(_, keys)| keys.iter().any(|bound| matches(bound, key))).map(|(&name, _)| name)
+  }
+}
+
+impl Default for KeyBindingConfig {
+  fn default() -> Self {
+    let bindings = DEFAULT_BINDINGS
+      .iter()
+      .map(|(name, keys)| {
+        let keys = keys.iter().filter_map(|key| parse_key(key)).map(|(code, modifiers)| KeyEvent::new(code, modifiers));
+        (*name, keys.collect())
+      })
+      .collect();
+    KeyBindingConfig { bindings }
+  }
+}
+
+impl<'de> Deserialize<'de> for KeyBindingConfig {
+  /// Reads action names mapped to key descriptions like `ctrl+d` over the defaults.
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let overrides = HashMap::<String, Vec<String>>::deserialize(deserializer)?;
+    let mut key_binding_config = KeyBindingConfig::default();
+    for (name, descriptions) in overrides {
+      let keys = descriptions
+        .iter()
+        .map(|description| {
+          let (code, modifiers) =
+            parse_key(description).ok_or_else(|| serde::de::Error::custom(format!("unknown key: {}", description)))?;
+          Ok(KeyEvent::new(code, modifiers))
+        })
+        .collect::<Result<_, D::Error>>()?;
+      key_binding_config.bind(&name, keys);
+    }
+    Ok(key_binding_config)
+  }
+}
+
+fn is_quit(key: &KeyEvent) -> bool {
+  normalize(key.code) == KeyCode::Char('c') && key.modifiers == KeyModifiers::CONTROL
+}
+
+fn matches(bound: &KeyEvent, key: &KeyEvent) -> bool {
+  normalize(bound.code) == normalize(key.code) && bound.modifiers == key.modifiers
+}
+
+/// Terminals report shifted letters as uppercase, bindings are stored lowercase so `shift+d` matches either way.
+pub fn normalize(code: KeyCode) -> KeyCode {
+  match code {
+    KeyCode::Char(c) => KeyCode::Char(c.to_ascii_lowercase()),
+    code => code,
+  }
+}
+
+/// Parses descriptions like `esc`, `tab`, `q` or `ctrl+shift+d`.
+pub fn parse_key(key: &str) -> Option<(KeyCode, KeyModifiers)> {
+  let lower = key.to_lowercase();
+  let mut parts: Vec<&str> = lower.split('+').collect();
+  let code = match parts.pop()? {
+    "esc" => KeyCode::Esc,
+    "tab" => KeyCode::Tab,
+    "enter" => KeyCode::Enter,
+    "backspace" => KeyCode::Backspace,
+    "space" => KeyCode::Char(' '),
+    "up" => KeyCode::Up,
+    "down" => KeyCode::Down,
+    "left" => KeyCode::Left,
+    "right" => KeyCode::Right,
+    c if c.chars().count() == 1 => KeyCode::Char(c.chars().next()?),
+    _ => return None,
+  };
+  let mut modifiers = KeyModifiers::NONE;
+  for part in parts {
+    modifiers |= match part {
+      "ctrl" => KeyModifiers::CONTROL,
+      "shift" => KeyModifiers::SHIFT,
+      "alt" => KeyModifiers::ALT,
+      _ => return None,
+    };
+  }
+  Some((code, modifiers))
+}
@@ -0,0 +1,55 @@
+use std::path::Path;
+
+use ratatui::style::Color;
+use serde::Deserialize;
+use tracing::error;
+
+/// Colours for list items and inputs, loaded from `theme.toml` in the config directory. Colours are names like
+/// `light_green` or hex like `#ff0000`, any left out keep their defaults.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+  /// The checked out branch's name, the terminal's colour when unset.
+  pub head_branch_fg: Option<Color>,
+  /// Branches and stashes staged for deletion.
+  pub staged_deletion_fg: Color,
+  /// Branches picked for a bulk operation and their check mark.
+  pub multi_selected_fg: Color,
+  /// Input text, and new branch names, that would be accepted.
+  pub valid_input_fg: Color,
+  /// Input text, and new branch names, that would be rejected.
+  pub invalid_input_fg: Color,
+  /// Input text before it has been validated.
+  pub input_fg: Color,
+}
+
+impl Theme {
+  /// The defaults overlaid with `theme.toml` from `config_dir`, when there is one.
+  pub fn load(config_dir: &Path) -> Self {
+    let path = config_dir.join("theme.toml");
+    if !path.exists() {
+      return Theme::default();
+    }
+    let loaded = config::Config::builder()
+      .add_source(config::File::from(path).format(config::FileFormat::Toml))
+      .build()
+      .and_then(|loaded| loaded.try_deserialize());
+    loaded.unwrap_or_else(|err| {
+      error!("Ignoring theme.toml, it could not be read: {}", err);
+      Theme::default()
+    })
+  }
+}
+
+impl Default for Theme {
+  fn default() -> Self {
+    Theme {
+      head_branch_fg: None,
+      staged_deletion_fg: Color::Red,
+      multi_selected_fg: Color::Yellow,
+      valid_input_fg: Color::LightGreen,
+      invalid_input_fg: Color::LightRed,
+      input_fg: Color::White,
+    }
+  }
+}
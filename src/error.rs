@@ -11,3 +11,24 @@ pub enum Error {
   #[error(transparent)]
   ParsingError(#[from] std::string::FromUtf8Error),
 }
+
+impl Error {
+  /// What went wrong without the kind of error, e.g. git's own output for `Error::Git`.
+  pub fn reason(&self) -> String {
+    match self {
+      Error::Git(reason) => reason.clone(),
+      other => other.to_string(),
+    }
+  }
+}
+
+/// One error listing every item that couldn't be deleted, with why, or `None` when they all were. `kind` is what
+/// was deleted, singular then plural, e.g. `("branch", "branches")`.
+pub fn failed_deletions(kind: (&str, &str), failures: &[(String, String)]) -> Option<Error> {
+  if failures.is_empty() {
+    return None;
+  }
+  let kind = if failures.len() == 1 { kind.0 } else { kind.1 };
+  let summary: Vec<String> = failures.iter().map(|(name, reason)| format!("{}: {}", name, reason.trim())).collect();
+  Some(Error::Git(format!("Failed to delete {} {}\n{}", failures.len(), kind, summary.join("\n"))))
+}
@@ -1,3 +1,8 @@
+pub mod any_git_repo;
 pub mod git2_repo;
 pub mod git_cli_repo;
 pub mod git_repo;
+#[cfg(test)]
+pub mod mock_git_repo;
+#[cfg(test)]
+pub mod temp_repo;
@@ -0,0 +1,602 @@
+use std::{
+  collections::HashMap,
+  path::{Path, PathBuf},
+  sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::{
+  error::Error,
+  git::{
+    git2_repo::Git2Repo,
+    git_cli_repo::GitCliRepo,
+    git_repo::{
+      BlameLine, BranchDetails, FileStatus, GitBranch, GitCommit, GitRemote, GitRemoteBranch, GitRepo, GitStash,
+      GitTag, GitWorktree, PullResult, RepoState,
+    },
+  },
+};
+
+/// Which implementation of `GitRepo` talks to the repository.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+pub enum GitRepoBackend {
+  /// Runs the git executable.
+  #[default]
+  #[serde(rename = "cli")]
+  Cli,
+  /// Uses libgit2, for when git isn't installed. Some operations aren't supported.
+  #[serde(rename = "git2")]
+  #[value(name = "git2")]
+  Libgit2,
+}
+
+impl GitRepoBackend {
+  fn other(self) -> Self {
+    match self {
+      GitRepoBackend::Cli => GitRepoBackend::Libgit2,
+      GitRepoBackend::Libgit2 => GitRepoBackend::Cli,
+    }
+  }
+}
+
+/// A repository opened with whichever backend worked, cheap to clone so every view can share it.
+#[derive(Clone)]
+pub struct AnyGitRepo(Arc<dyn GitRepo + Send + Sync>);
+
+impl AnyGitRepo {
+  /// Opens the repository in the current directory with `preferred`, falling back to the other backend if it can't.
+  pub fn open(preferred: GitRepoBackend, git_executable: &Path) -> Result<Self, Error> {
+    open_with_fallback(preferred, |backend| {
+      match backend {
+        GitRepoBackend::Cli => Ok(AnyGitRepo(Arc::new(GitCliRepo::from_cwd(git_executable)?))),
+        GitRepoBackend::Libgit2 => Ok(AnyGitRepo(Arc::new(LockedGitRepo(Mutex::new(Git2Repo::from_cwd()?))))),
+      }
+    })
+  }
+}
+
+/// Calls `open` with `preferred` then, if git couldn't open the repository that way, with the other backend. The
+/// preferred backend's error is returned when both fail.
+fn open_with_fallback<T>(
+  preferred: GitRepoBackend,
+  open: impl Fn(GitRepoBackend) -> Result<T, Error>,
+) -> Result<T, Error> {
+  match open(preferred) {
+    Err(err @ (Error::Git(_) | Error::Git2(_))) => {
+      warn!("Could not open the repository with the {:?} backend, trying {:?}: {}", preferred, preferred.other(), err);
+      open(preferred.other()).map_err(|_| err)
+    },
+    result => result,
+  }
+}
+
+impl GitRepo for AnyGitRepo {
+  fn local_branches(&self) -> Result<Vec<GitBranch>, Error> {
+    self.0.local_branches()
+  }
+
+  fn current_branch(&self) -> Result<GitBranch, Error> {
+    self.0.current_branch()
+  }
+
+  fn branches_merged_into(&self, target: &GitBranch) -> Result<Vec<GitBranch>, Error> {
+    self.0.branches_merged_into(target)
+  }
+
+  fn remote_branches(&self) -> Result<Vec<GitRemoteBranch>, Error> {
+    self.0.remote_branches()
+  }
+
+  fn stashes(&self) -> Result<Vec<GitStash>, Error> {
+    self.0.stashes()
+  }
+
+  fn get_stash_count(&self) -> Result<usize, Error> {
+    self.0.get_stash_count()
+  }
+
+  fn checkout_branch_from_name(&self, branch_name: &str) -> Result<(), Error> {
+    self.0.checkout_branch_from_name(branch_name)
+  }
+
+  fn checkout_tracking_branch(&self, remote_branch: &GitRemoteBranch) -> Result<(), Error> {
+    self.0.checkout_tracking_branch(remote_branch)
+  }
+
+  fn checkout_branch(&self, branch: &GitBranch) -> Result<(), Error> {
+    self.0.checkout_branch(branch)
+  }
+
+  fn validate_branch_name(&self, name: &str) -> Result<bool, Error> {
+    self.0.validate_branch_name(name)
+  }
+
+  fn create_branch(&self, to_create: &GitBranch) -> Result<(), Error> {
+    self.0.create_branch(to_create)
+  }
+
+  fn create_branch_at(&self, name: &str, sha: &str) -> Result<(), Error> {
+    self.0.create_branch_at(name, sha)
+  }
+
+  fn delete_branch(&self, to_delete: &GitBranch) -> Result<(), Error> {
+    self.0.delete_branch(to_delete)
+  }
+
+  fn rename_branch(&self, branch: &GitBranch, new_name: &str) -> Result<(), Error> {
+    self.0.rename_branch(branch, new_name)
+  }
+
+  fn set_upstream(&self, branch: &GitBranch, upstream: &str) -> Result<(), Error> {
+    self.0.set_upstream(branch, upstream)
+  }
+
+  fn unset_upstream(&self, branch: &GitBranch) -> Result<(), Error> {
+    self.0.unset_upstream(branch)
+  }
+
+  fn delete_remote_branch(&self, remote: &str, branch: &GitRemoteBranch) -> Result<(), Error> {
+    self.0.delete_remote_branch(remote, branch)
+  }
+
+  fn list_tags(&self) -> Result<Vec<GitTag>, Error> {
+    self.0.list_tags()
+  }
+
+  fn delete_tag(&self, tag: &GitTag) -> Result<(), Error> {
+    self.0.delete_tag(tag)
+  }
+
+  fn create_stash(&self, message: &str, include_untracked: bool, include_ignored: bool) -> Result<(), Error> {
+    self.0.create_stash(message, include_untracked, include_ignored)
+  }
+
+  fn stash_no_verify(&self, message: &str) -> Result<bool, Error> {
+    self.0.stash_no_verify(message)
+  }
+
+  fn stash_push_pathspec(&self, message: &str, pathspec: &[&str]) -> Result<bool, Error> {
+    self.0.stash_push_pathspec(message, pathspec)
+  }
+
+  fn apply_stash(&self, stash: &GitStash) -> Result<(), Error> {
+    self.0.apply_stash(stash)
+  }
+
+  fn create_branch_from_stash(&self, stash: &GitStash, branch_name: &str) -> Result<Vec<String>, Error> {
+    self.0.create_branch_from_stash(stash, branch_name)
+  }
+
+  fn pop_stash(&self, stash: &GitStash) -> Result<(), Error> {
+    self.0.pop_stash(stash)
+  }
+
+  fn drop_stash(&self, stash: &GitStash) -> Result<(), Error> {
+    self.0.drop_stash(stash)
+  }
+
+  fn get_stash_conflict_files(&self, stash: &GitStash) -> Result<Vec<String>, Error> {
+    self.0.get_stash_conflict_files(stash)
+  }
+
+  fn stash_show_stat(&self, stash: &GitStash) -> Result<String, Error> {
+    self.0.stash_show_stat(stash)
+  }
+
+  fn stash_diff(&self, stash: &GitStash) -> Result<String, Error> {
+    self.0.stash_diff(stash)
+  }
+
+  fn fetch(&self, remote: Option<&str>) -> Result<(), Error> {
+    self.0.fetch(remote)
+  }
+
+  fn merge_branch(&self, from: &GitBranch) -> Result<(), Error> {
+    self.0.merge_branch(from)
+  }
+
+  fn rebase_branch(&self, onto: &GitBranch) -> Result<(), Error> {
+    self.0.rebase_branch(onto)
+  }
+
+  fn push_branch(&self, branch: &GitBranch, remote: Option<&str>, force: bool) -> Result<(), Error> {
+    self.0.push_branch(branch, remote, force)
+  }
+
+  fn pull(&self, remote: Option<&str>, rebase: bool) -> Result<(), Error> {
+    self.0.pull(remote, rebase)
+  }
+
+  fn prune_gone_branches(&self) -> Result<Vec<GitBranch>, Error> {
+    self.0.prune_gone_branches()
+  }
+
+  fn pull_all_branches(&self) -> Result<Vec<PullResult>, Error> {
+    self.0.pull_all_branches()
+  }
+
+  fn get_last_commit_times(&self) -> Result<HashMap<String, i64>, Error> {
+    self.0.get_last_commit_times()
+  }
+
+  fn get_ahead_behind_bulk(&self) -> Result<HashMap<String, (u32, u32)>, Error> {
+    self.0.get_ahead_behind_bulk()
+  }
+
+  fn get_branch_creation_time(&self, branch: &GitBranch) -> Result<Option<i64>, Error> {
+    self.0.get_branch_creation_time(branch)
+  }
+
+  fn show_commit_diff(&self, sha: &str) -> Result<String, Error> {
+    self.0.show_commit_diff(sha)
+  }
+
+  fn diff_branch(&self, from: &GitBranch, to: &GitBranch) -> Result<String, Error> {
+    self.0.diff_branch(from, to)
+  }
+
+  fn commit_log(&self, branch: &GitBranch, limit: usize) -> Result<Vec<GitCommit>, Error> {
+    self.0.commit_log(branch, limit)
+  }
+
+  fn cherry_pick(&self, commit_sha: &str) -> Result<(), Error> {
+    self.0.cherry_pick(commit_sha)
+  }
+
+  fn get_branch_details(&self, branch: &GitBranch, main_branch: &str) -> Result<BranchDetails, Error> {
+    self.0.get_branch_details(branch, main_branch)
+  }
+
+  fn get_repository_state(&self) -> Result<RepoState, Error> {
+    self.0.get_repository_state()
+  }
+
+  fn current_head(&self) -> Result<(String, String), Error> {
+    self.0.current_head()
+  }
+
+  fn work_dir(&self) -> Result<PathBuf, Error> {
+    self.0.work_dir()
+  }
+
+  fn working_status(&self) -> Result<Vec<FileStatus>, Error> {
+    self.0.working_status()
+  }
+
+  fn stage_file(&self, path: &str) -> Result<(), Error> {
+    self.0.stage_file(path)
+  }
+
+  fn unstage_file(&self, path: &str) -> Result<(), Error> {
+    self.0.unstage_file(path)
+  }
+
+  fn blame(&self, path: &Path, branch: Option<&GitBranch>) -> Result<Vec<BlameLine>, Error> {
+    self.0.blame(path, branch)
+  }
+
+  fn list_worktrees(&self) -> Result<Vec<GitWorktree>, Error> {
+    self.0.list_worktrees()
+  }
+
+  fn remove_worktree(&self, worktree: &GitWorktree) -> Result<(), Error> {
+    self.0.remove_worktree(worktree)
+  }
+
+  fn create_worktree(&self, branch: &GitBranch, path: &str) -> Result<(), Error> {
+    self.0.create_worktree(branch, path)
+  }
+
+  fn list_remotes(&self) -> Result<Vec<GitRemote>, Error> {
+    self.0.list_remotes()
+  }
+
+  fn add_remote(&self, name: &str, url: &str) -> Result<(), Error> {
+    self.0.add_remote(name, url)
+  }
+
+  fn remove_remote(&self, name: &str) -> Result<(), Error> {
+    self.0.remove_remote(name)
+  }
+
+  fn rename_remote(&self, old_name: &str, new_name: &str) -> Result<(), Error> {
+    self.0.rename_remote(old_name, new_name)
+  }
+
+  fn get_and_clear_warnings(&self) -> Vec<String> {
+    self.0.get_and_clear_warnings()
+  }
+}
+
+/// Lets a repository that can't be shared between threads, like `Git2Repo`, be used by background operations too.
+/// Calls take turns.
+struct LockedGitRepo<T>(Mutex<T>);
+
+impl<T> LockedGitRepo<T> {
+  fn lock(&self) -> MutexGuard<'_, T> {
+    // Nothing is left half updated by a panicking git call, the repository is still usable
+    self.0.lock().unwrap_or_else(PoisonError::into_inner)
+  }
+}
+
+impl<T: GitRepo> GitRepo for LockedGitRepo<T> {
+  fn local_branches(&self) -> Result<Vec<GitBranch>, Error> {
+    self.lock().local_branches()
+  }
+
+  fn current_branch(&self) -> Result<GitBranch, Error> {
+    self.lock().current_branch()
+  }
+
+  fn branches_merged_into(&self, target: &GitBranch) -> Result<Vec<GitBranch>, Error> {
+    self.lock().branches_merged_into(target)
+  }
+
+  fn remote_branches(&self) -> Result<Vec<GitRemoteBranch>, Error> {
+    self.lock().remote_branches()
+  }
+
+  fn stashes(&self) -> Result<Vec<GitStash>, Error> {
+    self.lock().stashes()
+  }
+
+  fn get_stash_count(&self) -> Result<usize, Error> {
+    self.lock().get_stash_count()
+  }
+
+  fn checkout_branch_from_name(&self, branch_name: &str) -> Result<(), Error> {
+    self.lock().checkout_branch_from_name(branch_name)
+  }
+
+  fn checkout_tracking_branch(&self, remote_branch: &GitRemoteBranch) -> Result<(), Error> {
+    self.lock().checkout_tracking_branch(remote_branch)
+  }
+
+  fn checkout_branch(&self, branch: &GitBranch) -> Result<(), Error> {
+    self.lock().checkout_branch(branch)
+  }
+
+  fn validate_branch_name(&self, name: &str) -> Result<bool, Error> {
+    self.lock().validate_branch_name(name)
+  }
+
+  fn create_branch(&self, to_create: &GitBranch) -> Result<(), Error> {
+    self.lock().create_branch(to_create)
+  }
+
+  fn create_branch_at(&self, name: &str, sha: &str) -> Result<(), Error> {
+    self.lock().create_branch_at(name, sha)
+  }
+
+  fn delete_branch(&self, to_delete: &GitBranch) -> Result<(), Error> {
+    self.lock().delete_branch(to_delete)
+  }
+
+  fn rename_branch(&self, branch: &GitBranch, new_name: &str) -> Result<(), Error> {
+    self.lock().rename_branch(branch, new_name)
+  }
+
+  fn set_upstream(&self, branch: &GitBranch, upstream: &str) -> Result<(), Error> {
+    self.lock().set_upstream(branch, upstream)
+  }
+
+  fn unset_upstream(&self, branch: &GitBranch) -> Result<(), Error> {
+    self.lock().unset_upstream(branch)
+  }
+
+  fn delete_remote_branch(&self, remote: &str, branch: &GitRemoteBranch) -> Result<(), Error> {
+    self.lock().delete_remote_branch(remote, branch)
+  }
+
+  fn list_tags(&self) -> Result<Vec<GitTag>, Error> {
+    self.lock().list_tags()
+  }
+
+  fn delete_tag(&self, tag: &GitTag) -> Result<(), Error> {
+    self.lock().delete_tag(tag)
+  }
+
+  fn create_stash(&self, message: &str, include_untracked: bool, include_ignored: bool) -> Result<(), Error> {
+    self.lock().create_stash(message, include_untracked, include_ignored)
+  }
+
+  fn stash_no_verify(&self, message: &str) -> Result<bool, Error> {
+    self.lock().stash_no_verify(message)
+  }
+
+  fn stash_push_pathspec(&self, message: &str, pathspec: &[&str]) -> Result<bool, Error> {
+    self.lock().stash_push_pathspec(message, pathspec)
+  }
+
+  fn apply_stash(&self, stash: &GitStash) -> Result<(), Error> {
+    self.lock().apply_stash(stash)
+  }
+
+  fn create_branch_from_stash(&self, stash: &GitStash, branch_name: &str) -> Result<Vec<String>, Error> {
+    self.lock().create_branch_from_stash(stash, branch_name)
+  }
+
+  fn pop_stash(&self, stash: &GitStash) -> Result<(), Error> {
+    self.lock().pop_stash(stash)
+  }
+
+  fn drop_stash(&self, stash: &GitStash) -> Result<(), Error> {
+    self.lock().drop_stash(stash)
+  }
+
+  fn get_stash_conflict_files(&self, stash: &GitStash) -> Result<Vec<String>, Error> {
+    self.lock().get_stash_conflict_files(stash)
+  }
+
+  fn stash_show_stat(&self, stash: &GitStash) -> Result<String, Error> {
+    self.lock().stash_show_stat(stash)
+  }
+
+  fn stash_diff(&self, stash: &GitStash) -> Result<String, Error> {
+    self.lock().stash_diff(stash)
+  }
+
+  fn fetch(&self, remote: Option<&str>) -> Result<(), Error> {
+    self.lock().fetch(remote)
+  }
+
+  fn merge_branch(&self, from: &GitBranch) -> Result<(), Error> {
+    self.lock().merge_branch(from)
+  }
+
+  fn rebase_branch(&self, onto: &GitBranch) -> Result<(), Error> {
+    self.lock().rebase_branch(onto)
+  }
+
+  fn push_branch(&self, branch: &GitBranch, remote: Option<&str>, force: bool) -> Result<(), Error> {
+    self.lock().push_branch(branch, remote, force)
+  }
+
+  fn pull(&self, remote: Option<&str>, rebase: bool) -> Result<(), Error> {
+    self.lock().pull(remote, rebase)
+  }
+
+  fn prune_gone_branches(&self) -> Result<Vec<GitBranch>, Error> {
+    self.lock().prune_gone_branches()
+  }
+
+  fn pull_all_branches(&self) -> Result<Vec<PullResult>, Error> {
+    self.lock().pull_all_branches()
+  }
+
+  fn get_last_commit_times(&self) -> Result<HashMap<String, i64>, Error> {
+    self.lock().get_last_commit_times()
+  }
+
+  fn get_ahead_behind_bulk(&self) -> Result<HashMap<String, (u32, u32)>, Error> {
+    self.lock().get_ahead_behind_bulk()
+  }
+
+  fn get_branch_creation_time(&self, branch: &GitBranch) -> Result<Option<i64>, Error> {
+    self.lock().get_branch_creation_time(branch)
+  }
+
+  fn show_commit_diff(&self, sha: &str) -> Result<String, Error> {
+    self.lock().show_commit_diff(sha)
+  }
+
+  fn diff_branch(&self, from: &GitBranch, to: &GitBranch) -> Result<String, Error> {
+    self.lock().diff_branch(from, to)
+  }
+
+  fn commit_log(&self, branch: &GitBranch, limit: usize) -> Result<Vec<GitCommit>, Error> {
+    self.lock().commit_log(branch, limit)
+  }
+
+  fn cherry_pick(&self, commit_sha: &str) -> Result<(), Error> {
+    self.lock().cherry_pick(commit_sha)
+  }
+
+  fn get_branch_details(&self, branch: &GitBranch, main_branch: &str) -> Result<BranchDetails, Error> {
+    self.lock().get_branch_details(branch, main_branch)
+  }
+
+  fn get_repository_state(&self) -> Result<RepoState, Error> {
+    self.lock().get_repository_state()
+  }
+
+  fn current_head(&self) -> Result<(String, String), Error> {
+    self.lock().current_head()
+  }
+
+  fn work_dir(&self) -> Result<PathBuf, Error> {
+    self.lock().work_dir()
+  }
+
+  fn working_status(&self) -> Result<Vec<FileStatus>, Error> {
+    self.lock().working_status()
+  }
+
+  fn stage_file(&self, path: &str) -> Result<(), Error> {
+    self.lock().stage_file(path)
+  }
+
+  fn unstage_file(&self, path: &str) -> Result<(), Error> {
+    self.lock().unstage_file(path)
+  }
+
+  fn blame(&self, path: &Path, branch: Option<&GitBranch>) -> Result<Vec<BlameLine>, Error> {
+    self.lock().blame(path, branch)
+  }
+
+  fn list_worktrees(&self) -> Result<Vec<GitWorktree>, Error> {
+    self.lock().list_worktrees()
+  }
+
+  fn remove_worktree(&self, worktree: &GitWorktree) -> Result<(), Error> {
+    self.lock().remove_worktree(worktree)
+  }
+
+  fn create_worktree(&self, branch: &GitBranch, path: &str) -> Result<(), Error> {
+    self.lock().create_worktree(branch, path)
+  }
+
+  fn list_remotes(&self) -> Result<Vec<GitRemote>, Error> {
+    self.lock().list_remotes()
+  }
+
+  fn add_remote(&self, name: &str, url: &str) -> Result<(), Error> {
+    self.lock().add_remote(name, url)
+  }
+
+  fn remove_remote(&self, name: &str) -> Result<(), Error> {
+    self.lock().remove_remote(name)
+  }
+
+  fn rename_remote(&self, old_name: &str, new_name: &str) -> Result<(), Error> {
+    self.lock().rename_remote(old_name, new_name)
+  }
+
+  fn get_and_clear_warnings(&self) -> Vec<String> {
+    self.lock().get_and_clear_warnings()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::cell::RefCell;
+
+  use super::*;
+
+  #[test]
+  fn falls_back_to_the_other_backend() {
+    let tried = RefCell::new(Vec::new());
+    // Fails the first call, whichever backend it is for
+    let result = open_with_fallback(GitRepoBackend::Cli, |backend| {
+      tried.borrow_mut().push(backend);
+      if tried.borrow().len() == 1 {
+        return Err(Error::Git(String::from("not a git repository")));
+      }
+      Ok(backend)
+    });
+    assert_eq!(result.unwrap(), GitRepoBackend::Libgit2);
+    assert_eq!(tried.into_inner(), vec![GitRepoBackend::Cli, GitRepoBackend::Libgit2]);
+  }
+
+  #[test]
+  fn uses_the_preferred_backend_when_it_opens() {
+    let tried = RefCell::new(Vec::new());
+    let result = open_with_fallback(GitRepoBackend::Libgit2, |backend| {
+      tried.borrow_mut().push(backend);
+      Ok(backend)
+    });
+    assert_eq!(result.unwrap(), GitRepoBackend::Libgit2);
+    assert_eq!(tried.into_inner(), vec![GitRepoBackend::Libgit2]);
+  }
+
+  #[test]
+  fn reports_the_preferred_backends_error_when_both_fail() {
+    let result = open_with_fallback(GitRepoBackend::Cli, |backend| -> Result<(), Error> {
+      Err(Error::Git(format!("{:?} failed", backend)))
+    });
+    assert_eq!(result.unwrap_err().to_string(), "Git operation failed: Cli failed");
+  }
+}
@@ -1,12 +1,23 @@
-use std::env::current_dir;
+use std::{
+  collections::{HashMap, HashSet},
+  env::current_dir,
+  path::{Path, PathBuf},
+};
 
-use git2::{Branch, BranchType, Repository};
-use tracing::{error, info};
+use git2::{
+  BlameOptions, Branch, BranchType, Commit, Diff, DiffFormat, DiffStatsFormat, ErrorCode, IndexAddOption, ObjectType,
+  Oid, Repository, RepositoryState, StashFlags, StatusOptions, WorktreeAddOptions, WorktreeLockStatus,
+};
+use tracing::{error, info, warn};
 
 use super::git_repo::GitStash;
 use crate::{
   error::Error,
-  git::git_repo::{GitBranch, GitRemoteBranch, GitRepo},
+  git::git_repo::{
+    cherry_pick_conflict, BlameLine, BranchDetails, FileStatus, GitBranch, GitCommit, GitRemote, GitRemoteBranch,
+    GitRepo, GitTag, GitWorktree, PullResult, RepoState, StatusKind,
+  },
+  utils::time::format_relative_time,
 };
 
 pub struct Git2Repo {
@@ -23,8 +34,14 @@ impl Git2Repo {
   fn create_git_branch(&self, result: Result<(Branch, BranchType), git2::Error>) -> Option<GitBranch> {
     let (branch, _branch_type) = result.ok()?;
     let name = branch.name().ok()??;
-    let upstream = extract_upstream_branch(&branch);
-    Some(GitBranch { name: String::from(name), is_head: branch.is_head(), upstream })
+    let upstream = extract_upstream_branch(&self.repo, &branch);
+    let sha = branch.get().target().map(|oid| oid.to_string()).unwrap_or_default();
+    Some(GitBranch { name: String::from(name), is_head: branch.is_head(), upstream, sha, ..Default::default() })
+  }
+
+  /// Stash operations in git2 need a mutable repository, so open a fresh handle onto the same repo for them.
+  fn open_mut(&self) -> Result<Repository, Error> {
+    Ok(Repository::open(self.repo.path())?)
   }
 }
 
@@ -39,16 +56,64 @@ impl GitRepo for Git2Repo {
     Ok(loaded_branches)
   }
 
-  fn stashes(&mut self) -> Result<Vec<GitStash>, Error> {
+  fn current_branch(&self) -> Result<GitBranch, Error> {
+    let head = self.repo.head()?;
+    if !head.is_branch() {
+      return Err(Error::Git(String::from("HEAD is detached")));
+    }
+    let name = head.shorthand().ok_or_else(|| Error::Git(String::from("HEAD is not valid UTF-8")))?;
+    Ok(GitBranch { name: String::from(name), is_head: true, ..Default::default() })
+  }
+
+  fn branches_merged_into(&self, target: &GitBranch) -> Result<Vec<GitBranch>, Error> {
+    let target = self.repo.revparse_single(&target.name)?.peel_to_commit()?.id();
+    let mut merged = Vec::new();
+    for branch in self.local_branches()? {
+      let tip = Oid::from_str(&branch.sha)?;
+      if tip == target || self.repo.graph_descendant_of(target, tip)? {
+        merged.push(branch);
+      }
+    }
+    Ok(merged)
+  }
+
+  fn remote_branches(&self) -> Result<Vec<GitRemoteBranch>, Error> {
+    let mut branches = Vec::new();
+    for (branch, _branch_type) in self.repo.branches(Some(BranchType::Remote))?.flatten() {
+      // Symbolic refs like `origin/HEAD` point at another remote branch, they would be listed twice
+      if branch.get().symbolic_target().is_some() {
+        continue;
+      }
+      if let Ok(Some(name)) = branch.name() {
+        branches.push(GitRemoteBranch::new(String::from(name)));
+      }
+    }
+    Ok(branches)
+  }
+
+  fn stashes(&self) -> Result<Vec<GitStash>, Error> {
     let mut stashes: Vec<GitStash> = vec![];
-    self.repo.stash_foreach(|index, message, stash_id| {
+    self.open_mut()?.stash_foreach(|index, message, stash_id| {
       stashes.push(GitStash::new(index, String::from(message), stash_id.to_string()));
       true
     })?;
+    for stash in stashes.iter_mut() {
+      let commit = self.repo.find_commit(Oid::from_str(&stash.stash_id)?)?;
+      stash.created_at = Some(commit.time().seconds());
+    }
 
     Ok(stashes)
   }
 
+  fn get_stash_count(&self) -> Result<usize, Error> {
+    // Each stash is an entry in the stash ref's reflog, it is empty when there are no stashes
+    Ok(self.repo.reflog("refs/stash")?.len())
+  }
+
+  fn checkout_tracking_branch(&self, _remote_branch: &GitRemoteBranch) -> Result<(), Error> {
+    Err(Error::Git("Checking out a tracking branch is not supported by the git2 backend".to_string()))
+  }
+
   fn checkout_branch_from_name(&self, branch_name: &str) -> Result<(), Error> {
     info!("Checking out branch {}", branch_name);
     let branch = self.repo.find_branch(branch_name, BranchType::Local)?;
@@ -58,8 +123,8 @@ impl GitRepo for Git2Repo {
     let tree = branch_ref.peel_to_tree()?;
     let checkout_result = self.repo.checkout_tree(tree.as_object(), None);
 
-    if checkout_result.is_err() {
-      error!("Failed to checkout tree: {}", checkout_result.unwrap_err());
+    if let Err(err) = checkout_result {
+      error!("Failed to checkout tree: {}", err);
       return Err(Error::Git("Failed to checkout tree".to_string()));
     }
 
@@ -86,7 +151,7 @@ impl GitRepo for Git2Repo {
     let head_oid = head.target();
 
     if head_oid.is_none() {
-      error!("Attempted to create a branch from a symbolic reference: {}", head_oid.unwrap());
+      error!("Attempted to create a branch from a symbolic reference: {:?}", head.name());
       return Err(Error::Git("Attempted to create a branch from a symbolic reference".to_string()));
     }
 
@@ -97,6 +162,14 @@ impl GitRepo for Git2Repo {
     Ok(())
   }
 
+  fn create_branch_at(&self, name: &str, sha: &str) -> Result<(), Error> {
+    info!("Creating branch {} at {}", name, sha);
+    // The sha may be abbreviated
+    let commit = self.repo.revparse_single(sha)?.peel_to_commit()?;
+    self.repo.branch(name, &commit, false)?;
+    Ok(())
+  }
+
   fn delete_branch(&self, to_delete: &GitBranch) -> Result<(), Error> {
     let branches = self.repo.branches(Some(BranchType::Local))?;
     for res in branches.into_iter() {
@@ -115,10 +188,525 @@ impl GitRepo for Git2Repo {
     }
     Ok(())
   }
+
+  fn rename_branch(&self, branch: &GitBranch, new_name: &str) -> Result<(), Error> {
+    self.repo.find_branch(&branch.name, BranchType::Local)?.rename(new_name, false)?;
+    Ok(())
+  }
+
+  fn set_upstream(&self, branch: &GitBranch, upstream: &str) -> Result<(), Error> {
+    info!("Setting the upstream of {} to {}", branch.name, upstream);
+    self.repo.find_branch(&branch.name, BranchType::Local)?.set_upstream(Some(upstream))?;
+    Ok(())
+  }
+
+  fn unset_upstream(&self, branch: &GitBranch) -> Result<(), Error> {
+    info!("Unsetting the upstream of {}", branch.name);
+    self.repo.find_branch(&branch.name, BranchType::Local)?.set_upstream(None)?;
+    Ok(())
+  }
+
+  fn delete_remote_branch(&self, _remote: &str, _branch: &GitRemoteBranch) -> Result<(), Error> {
+    Err(Error::Git("Deleting remote branches is not supported by the git2 backend".to_string()))
+  }
+
+  fn list_tags(&self) -> Result<Vec<GitTag>, Error> {
+    let mut tags = Vec::new();
+    for name in self.repo.tag_names(None)?.iter().flatten() {
+      let object = self.repo.revparse_single(&format!("refs/tags/{}", name))?;
+      let commit = object.peel_to_commit()?;
+      tags.push(GitTag {
+        name: String::from(name),
+        commit_sha: commit.id().to_string(),
+        is_annotated: object.kind() == Some(ObjectType::Tag),
+      });
+    }
+    Ok(tags)
+  }
+
+  fn delete_tag(&self, tag: &GitTag) -> Result<(), Error> {
+    self.repo.tag_delete(&tag.name)?;
+    Ok(())
+  }
+
+  fn create_stash(&self, message: &str, include_untracked: bool, include_ignored: bool) -> Result<(), Error> {
+    info!("Creating stash {}", message);
+    let mut repo = self.open_mut()?;
+    let signature = repo.signature()?;
+    let mut flags = StashFlags::DEFAULT;
+    flags.set(StashFlags::INCLUDE_UNTRACKED, include_untracked);
+    flags.set(StashFlags::INCLUDE_IGNORED, include_ignored);
+    repo.stash_save(&signature, message, Some(flags))?;
+    Ok(())
+  }
+
+  fn stash_no_verify(&self, message: &str) -> Result<bool, Error> {
+    info!("Creating stash {} without hooks", message);
+    // libgit2 never runs hooks, so this is an ordinary stash
+    let mut repo = self.open_mut()?;
+    let signature = repo.signature()?;
+    match repo.stash_save(&signature, message, None) {
+      Ok(_) => Ok(true),
+      Err(err) if err.code() == ErrorCode::NotFound => Ok(false),
+      Err(err) => Err(err.into()),
+    }
+  }
+
+  fn stash_push_pathspec(&self, _message: &str, _pathspec: &[&str]) -> Result<bool, Error> {
+    // libgit2's pathspec stash can't be given a message
+    Err(Error::Git("Stashing some files is not supported by the git2 backend".to_string()))
+  }
+
+  fn apply_stash(&self, stash: &GitStash) -> Result<(), Error> {
+    info!("Applying stash {}", stash.stash_id);
+    self.open_mut()?.stash_apply(stash.index, None)?;
+    Ok(())
+  }
+
+  fn create_branch_from_stash(&self, stash: &GitStash, branch_name: &str) -> Result<Vec<String>, Error> {
+    info!("Creating branch {} from stash {}", branch_name, stash.stash_id);
+    let base = self.repo.find_commit(Oid::from_str(&stash.stash_id)?)?.parent(0)?;
+    let branch = self.repo.branch(branch_name, &base, false)?;
+    self.repo.checkout_tree(base.as_object(), None)?;
+    self.repo.set_head(branch.get().name().unwrap_or_default())?;
+
+    let pop_result = self.open_mut()?.stash_pop(stash.index, None);
+    let statuses = self.repo.statuses(None)?;
+    let conflicts: Vec<String> = statuses
+      .iter()
+      .filter(|entry| entry.status().is_conflicted())
+      .filter_map(|entry| entry.path().map(String::from))
+      .collect();
+    if conflicts.is_empty() {
+      pop_result?;
+    }
+    Ok(conflicts)
+  }
+
+  fn pop_stash(&self, stash: &GitStash) -> Result<(), Error> {
+    info!("Popping stash {}", stash.stash_id);
+    self.open_mut()?.stash_pop(stash.index, None)?;
+    Ok(())
+  }
+
+  fn drop_stash(&self, stash: &GitStash) -> Result<(), Error> {
+    info!("Dropping stash {}", stash.stash_id);
+    self.open_mut()?.stash_drop(stash.index)?;
+    Ok(())
+  }
+
+  fn get_stash_conflict_files(&self, stash: &GitStash) -> Result<Vec<String>, Error> {
+    let stash_commit = self.repo.find_commit(Oid::from_str(&stash.stash_id)?)?;
+    let base_tree = stash_commit.parent(0)?.tree()?;
+    let diff = self.repo.diff_tree_to_tree(Some(&base_tree), Some(&stash_commit.tree()?), None)?;
+
+    let mut options = StatusOptions::new();
+    options.include_untracked(false);
+    let statuses = self.repo.statuses(Some(&mut options))?;
+    let modified: HashSet<String> = statuses.iter().filter_map(|entry| entry.path().map(String::from)).collect();
+
+    let conflicts = diff
+      .deltas()
+      .filter_map(|delta| delta.new_file().path())
+      .map(|path| path.to_string_lossy().to_string())
+      .filter(|path| modified.contains(path))
+      .collect();
+    Ok(conflicts)
+  }
+
+  fn stash_show_stat(&self, stash: &GitStash) -> Result<String, Error> {
+    let stash_commit = self.repo.find_commit(Oid::from_str(&stash.stash_id)?)?;
+    let base_tree = stash_commit.parent(0)?.tree()?;
+    let diff = self.repo.diff_tree_to_tree(Some(&base_tree), Some(&stash_commit.tree()?), None)?;
+    let stats = diff.stats()?.to_buf(DiffStatsFormat::SHORT, 80)?;
+    Ok(String::from(stats.as_str().unwrap_or_default().trim()))
+  }
+
+  fn stash_diff(&self, stash: &GitStash) -> Result<String, Error> {
+    let stash_commit = self.repo.find_commit(Oid::from_str(&stash.stash_id)?)?;
+    let base_tree = stash_commit.parent(0)?.tree()?;
+    let diff = self.repo.diff_tree_to_tree(Some(&base_tree), Some(&stash_commit.tree()?), None)?;
+    patch_text(&diff)
+  }
+
+  fn fetch(&self, remote: Option<&str>) -> Result<(), Error> {
+    let names: Vec<String> = match remote {
+      Some(remote) => vec![String::from(remote)],
+      None => self.repo.remotes()?.iter().flatten().map(String::from).collect(),
+    };
+    for name in names {
+      // An empty refspec list uses the remote's configured refspecs
+      self.repo.find_remote(&name)?.fetch(&[] as &[&str], None, None)?;
+    }
+    Ok(())
+  }
+
+  fn merge_branch(&self, _from: &GitBranch) -> Result<(), Error> {
+    Err(Error::Git("Merging is not supported by the git2 backend".to_string()))
+  }
+
+  fn rebase_branch(&self, _onto: &GitBranch) -> Result<(), Error> {
+    Err(Error::Git("Rebasing is not supported by the git2 backend".to_string()))
+  }
+
+  fn push_branch(&self, _branch: &GitBranch, _remote: Option<&str>, _force: bool) -> Result<(), Error> {
+    Err(Error::Git("Pushing is not supported by the git2 backend".to_string()))
+  }
+
+  fn pull(&self, _remote: Option<&str>, _rebase: bool) -> Result<(), Error> {
+    Err(Error::Git("Pulling is not supported by the git2 backend".to_string()))
+  }
+
+  fn prune_gone_branches(&self) -> Result<Vec<GitBranch>, Error> {
+    Err(Error::Git("Pruning is not supported by the git2 backend".to_string()))
+  }
+
+  fn pull_all_branches(&self) -> Result<Vec<PullResult>, Error> {
+    Err(Error::Git("Updating all branches is not supported by the git2 backend".to_string()))
+  }
+
+  fn get_last_commit_times(&self) -> Result<HashMap<String, i64>, Error> {
+    let mut times = HashMap::new();
+    for (branch, _branch_type) in self.repo.branches(Some(BranchType::Local))?.flatten() {
+      let Ok(Some(name)) = branch.name() else {
+        continue;
+      };
+      let commit = branch.get().peel_to_commit()?;
+      times.insert(String::from(name), commit.time().seconds());
+    }
+    Ok(times)
+  }
+
+  fn get_branch_creation_time(&self, branch: &GitBranch) -> Result<Option<i64>, Error> {
+    let reflog = self.repo.reflog(&format!("refs/heads/{}", branch.name))?;
+    // The reflog is newest first so the last entry is when the branch was created
+    Ok(reflog.iter().next_back().map(|entry| entry.committer().when().seconds()))
+  }
+
+  fn get_ahead_behind_bulk(&self) -> Result<HashMap<String, (u32, u32)>, Error> {
+    let mut counts = HashMap::new();
+    for (branch, _branch_type) in self.repo.branches(Some(BranchType::Local))?.flatten() {
+      let (Ok(Some(name)), Ok(upstream)) = (branch.name(), branch.upstream()) else {
+        continue;
+      };
+      let (Some(local_oid), Some(upstream_oid)) = (branch.get().target(), upstream.get().target()) else {
+        continue;
+      };
+      let (ahead, behind) = self.repo.graph_ahead_behind(local_oid, upstream_oid)?;
+      counts.insert(String::from(name), (ahead as u32, behind as u32));
+    }
+    Ok(counts)
+  }
+
+  fn current_head(&self) -> Result<(String, String), Error> {
+    let head = self.repo.head()?;
+    let branch = if head.is_branch() { head.shorthand().unwrap_or("HEAD") } else { "HEAD" };
+    let commit = head.peel_to_commit()?;
+    let sha = commit.as_object().short_id()?;
+    Ok((String::from(branch), String::from(sha.as_str().unwrap_or_default())))
+  }
+
+  fn get_repository_state(&self) -> Result<RepoState, Error> {
+    Ok(match self.repo.state() {
+      RepositoryState::Clean => RepoState::Clean,
+      RepositoryState::Merge => RepoState::Merging,
+      RepositoryState::Revert | RepositoryState::RevertSequence => RepoState::Reverting,
+      RepositoryState::CherryPick | RepositoryState::CherryPickSequence => RepoState::CherryPicking,
+      RepositoryState::Bisect => RepoState::Bisecting,
+      RepositoryState::Rebase
+      | RepositoryState::RebaseInteractive
+      | RepositoryState::RebaseMerge
+      | RepositoryState::ApplyMailbox
+      | RepositoryState::ApplyMailboxOrRebase => RepoState::Rebasing,
+    })
+  }
+
+  fn work_dir(&self) -> Result<PathBuf, Error> {
+    let work_dir =
+      self.repo.workdir().ok_or_else(|| Error::Git(String::from("A bare repository has no working tree")))?;
+    Ok(work_dir.to_path_buf())
+  }
+
+  fn working_status(&self) -> Result<Vec<FileStatus>, Error> {
+    let mut options = StatusOptions::new();
+    options.include_untracked(true).include_ignored(false).renames_head_to_index(true);
+    let statuses = self.repo.statuses(Some(&mut options))?;
+    let mut files = Vec::new();
+    for entry in statuses.iter() {
+      let status = entry.status();
+      let Some(path) = entry.path().map(PathBuf::from) else {
+        continue;
+      };
+      if status.is_conflicted() {
+        files.push(FileStatus { path, status: StatusKind::Modified, staged: false });
+        continue;
+      }
+      let staged = if status.is_index_new() {
+        Some(StatusKind::Added)
+      } else if status.is_index_modified() || status.is_index_typechange() {
+        Some(StatusKind::Modified)
+      } else if status.is_index_deleted() {
+        Some(StatusKind::Deleted)
+      } else if status.is_index_renamed() {
+        Some(StatusKind::Renamed)
+      } else {
+        None
+      };
+      // A rename is listed under its new name
+      let staged_path = entry
+        .head_to_index()
+        .and_then(|delta| delta.new_file().path().map(PathBuf::from))
+        .unwrap_or_else(|| path.clone());
+      if let Some(kind) = staged {
+        files.push(FileStatus { path: staged_path, status: kind, staged: true });
+      }
+      let unstaged = if status.is_wt_new() {
+        Some(StatusKind::Untracked)
+      } else if status.is_wt_modified() || status.is_wt_typechange() {
+        Some(StatusKind::Modified)
+      } else if status.is_wt_deleted() {
+        Some(StatusKind::Deleted)
+      } else if status.is_wt_renamed() {
+        Some(StatusKind::Renamed)
+      } else {
+        None
+      };
+      if let Some(kind) = unstaged {
+        files.push(FileStatus { path, status: kind, staged: false });
+      }
+    }
+    Ok(files)
+  }
+
+  fn stage_file(&self, path: &str) -> Result<(), Error> {
+    info!("Staging {}", path);
+    let mut index = self.repo.index()?;
+    // add_all picks up new and changed files, update_all the deleted ones
+    index.add_all([path], IndexAddOption::DEFAULT, None)?;
+    index.update_all([path], None)?;
+    index.write()?;
+    Ok(())
+  }
+
+  fn blame(&self, path: &Path, branch: Option<&GitBranch>) -> Result<Vec<BlameLine>, Error> {
+    // libgit2 only blames committed content, so without a branch this is HEAD rather than the working tree
+    let commit = match branch {
+      Some(branch) => self.repo.revparse_single(&branch.name)?.peel_to_commit()?,
+      None => self.repo.head()?.peel_to_commit()?,
+    };
+    let mut options = BlameOptions::new();
+    options.newest_commit(commit.id());
+    let blame = self.repo.blame_file(path, Some(&mut options))?;
+    let blob = commit.tree()?.get_path(path)?.to_object(&self.repo)?.peel_to_blob()?;
+    let mut lines = Vec::new();
+    for (index, content) in String::from_utf8_lossy(blob.content()).lines().enumerate() {
+      let line_number = index + 1;
+      let Some(hunk) = blame.get_line(line_number) else {
+        continue;
+      };
+      let signature = hunk.final_signature();
+      lines.push(BlameLine {
+        sha: hunk.final_commit_id().to_string(),
+        author: String::from(signature.name().unwrap_or_default()),
+        date: format_relative_time(signature.when().seconds()),
+        line_number,
+        content: String::from(content),
+      });
+    }
+    Ok(lines)
+  }
+
+  fn unstage_file(&self, path: &str) -> Result<(), Error> {
+    info!("Unstaging {}", path);
+    // Without a commit yet the file is just removed from the index
+    let head = self.repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+    self.repo.reset_default(head.as_ref().map(|commit| commit.as_object()), [path])?;
+    Ok(())
+  }
+
+  fn list_worktrees(&self) -> Result<Vec<GitWorktree>, Error> {
+    let head_branch = |repo: &Repository| {
+      let head = repo.head().ok().filter(|head| head.is_branch())?;
+      head.shorthand().map(String::from)
+    };
+    let mut worktrees = Vec::new();
+    if let Some(workdir) = self.repo.workdir() {
+      worktrees.push(GitWorktree {
+        path: workdir.to_path_buf(),
+        branch: head_branch(&self.repo),
+        is_main: true,
+        ..Default::default()
+      });
+    } else {
+      worktrees.push(GitWorktree {
+        path: self.repo.path().to_path_buf(),
+        is_main: true,
+        is_bare: true,
+        ..Default::default()
+      });
+    }
+    for name in self.repo.worktrees()?.iter().flatten() {
+      let worktree = self.repo.find_worktree(name)?;
+      let branch = Repository::open_from_worktree(&worktree).ok().and_then(|repo| head_branch(&repo));
+      let is_locked = matches!(worktree.is_locked(), Ok(WorktreeLockStatus::Locked(_)));
+      worktrees.push(GitWorktree { path: worktree.path().to_path_buf(), branch, is_locked, ..Default::default() });
+    }
+    Ok(worktrees)
+  }
+
+  fn remove_worktree(&self, _worktree: &GitWorktree) -> Result<(), Error> {
+    Err(Error::Git("Removing worktrees is not supported by the git2 backend".to_string()))
+  }
+
+  fn create_worktree(&self, branch: &GitBranch, path: &str) -> Result<(), Error> {
+    let reference = self.repo.find_branch(&branch.name, BranchType::Local)?.into_reference();
+    let mut options = WorktreeAddOptions::new();
+    options.reference(Some(&reference));
+    // Worktrees are named after their directory, like `git worktree add`
+    let name = Path::new(path).file_name().and_then(|name| name.to_str()).unwrap_or(&branch.name).replace('/', "-");
+    self.repo.worktree(&name, Path::new(path), Some(&options))?;
+    Ok(())
+  }
+
+  fn list_remotes(&self) -> Result<Vec<GitRemote>, Error> {
+    let mut remotes = Vec::new();
+    for name in self.repo.remotes()?.iter().flatten() {
+      let remote = self.repo.find_remote(name)?;
+      let fetch_url = String::from(remote.url().unwrap_or_default());
+      let push_url = remote.pushurl().map_or_else(|| fetch_url.clone(), String::from);
+      remotes.push(GitRemote { name: String::from(name), fetch_url, push_url });
+    }
+    Ok(remotes)
+  }
+
+  fn add_remote(&self, name: &str, url: &str) -> Result<(), Error> {
+    info!("Adding remote {} at {}", name, url);
+    self.repo.remote(name, url)?;
+    Ok(())
+  }
+
+  fn remove_remote(&self, name: &str) -> Result<(), Error> {
+    info!("Removing remote {}", name);
+    self.repo.remote_delete(name)?;
+    Ok(())
+  }
+
+  fn rename_remote(&self, old_name: &str, new_name: &str) -> Result<(), Error> {
+    info!("Renaming remote {} to {}", old_name, new_name);
+    // Refspecs git2 couldn't rewrite are returned rather than failing the rename, git only warns about them too
+    for problem in self.repo.remote_rename(old_name, new_name)?.iter().flatten() {
+      warn!("Could not rename the refspec {} of {}", problem, old_name);
+    }
+    Ok(())
+  }
+
+  fn cherry_pick(&self, commit_sha: &str) -> Result<(), Error> {
+    info!("Cherry-picking {}", commit_sha);
+    let commit = self.repo.find_commit(Oid::from_str(commit_sha)?)?;
+    // Only updates the index and working tree, the commit is made below like git does
+    self.repo.cherrypick(&commit, None)?;
+    let mut index = self.repo.index()?;
+    if index.has_conflicts() {
+      let conflicts: Vec<String> = index
+        .conflicts()?
+        .flatten()
+        .filter_map(|conflict| conflict.our.or(conflict.their))
+        .map(|entry| String::from_utf8_lossy(&entry.path).into_owned())
+        .collect();
+      let conflicts: Vec<&str> = conflicts.iter().map(String::as_str).collect();
+      return Err(cherry_pick_conflict(commit_sha, &conflicts));
+    }
+    let tree = self.repo.find_tree(index.write_tree()?)?;
+    let head = self.repo.head()?.peel_to_commit()?;
+    let committer = self.repo.signature()?;
+    let message = commit.message().unwrap_or_default();
+    self.repo.commit(Some("HEAD"), &commit.author(), &committer, message, &tree, &[&head])?;
+    self.repo.cleanup_state()?;
+    Ok(())
+  }
+
+  fn commit_log(&self, branch: &GitBranch, limit: usize) -> Result<Vec<GitCommit>, Error> {
+    let mut revwalk = self.repo.revwalk()?;
+    revwalk.push(self.repo.revparse_single(&branch.name)?.peel_to_commit()?.id())?;
+    let mut commits = Vec::new();
+    for oid in revwalk.take(limit) {
+      commits.push(to_git_commit(&self.repo.find_commit(oid?)?));
+    }
+    Ok(commits)
+  }
+
+  fn get_branch_details(&self, branch: &GitBranch, main_branch: &str) -> Result<BranchDetails, Error> {
+    let commit = self.repo.revparse_single(&branch.name)?.peel_to_commit()?;
+    let main_commit = self.repo.revparse_single(main_branch)?.peel_to_commit()?;
+    let last_commit = to_git_commit(&commit);
+    let (ahead, behind) = self.repo.graph_ahead_behind(commit.id(), main_commit.id())?;
+    let merge_base_sha = self.repo.merge_base(commit.id(), main_commit.id())?.to_string();
+    Ok(BranchDetails { last_commit, ahead: ahead as u32, behind: behind as u32, merge_base_sha, is_merged: ahead == 0 })
+  }
+
+  fn show_commit_diff(&self, sha: &str) -> Result<String, Error> {
+    let commit = self.repo.revparse_single(sha)?.peel_to_commit()?;
+    let parent_tree = match commit.parent(0) {
+      Ok(parent) => Some(parent.tree()?),
+      Err(_) => None,
+    };
+    let diff = self.repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+
+    let author = commit.author();
+    let mut output = format!(
+      "commit {}\nAuthor: {} <{}>\nDate:   {}\n\n",
+      commit.id(),
+      author.name().unwrap_or_default(),
+      author.email().unwrap_or_default(),
+      format_relative_time(commit.time().seconds())
+    );
+    for line in commit.message().unwrap_or_default().lines() {
+      output.push_str(&format!("    {}\n", line));
+    }
+    output.push('\n');
+    output.push_str(diff.stats()?.to_buf(DiffStatsFormat::FULL, 80)?.as_str().unwrap_or_default());
+    output.push('\n');
+    output.push_str(&patch_text(&diff)?);
+    Ok(output)
+  }
+
+  fn diff_branch(&self, from: &GitBranch, to: &GitBranch) -> Result<String, Error> {
+    let from_tree = self.repo.revparse_single(&from.name)?.peel_to_tree()?;
+    let to_tree = self.repo.revparse_single(&to.name)?.peel_to_tree()?;
+    let diff = self.repo.diff_tree_to_tree(Some(&from_tree), Some(&to_tree), None)?;
+    patch_text(&diff)
+  }
+}
+
+fn to_git_commit(commit: &Commit) -> GitCommit {
+  GitCommit {
+    sha: commit.id().to_string(),
+    author: String::from(commit.author().name().unwrap_or_default()),
+    committed_at: commit.time().seconds(),
+    subject: String::from(commit.summary().unwrap_or_default()),
+  }
+}
+
+fn patch_text(diff: &Diff) -> Result<String, Error> {
+  let mut output = String::new();
+  diff.print(DiffFormat::Patch, |_delta, _hunk, line| {
+    // Content lines carry their origin marker separately, headers already include their text
+    if matches!(line.origin(), '+' | '-' | ' ') {
+      output.push(line.origin());
+    }
+    output.push_str(&String::from_utf8_lossy(line.content()));
+    true
+  })?;
+  Ok(output)
 }
 
-fn extract_upstream_branch(local_branch: &Branch) -> Option<GitRemoteBranch> {
-  let upstream_branch = local_branch.upstream().ok()?;
-  let upstream_name = upstream_branch.name().ok()??;
-  Some(GitRemoteBranch { name: String::from(upstream_name) })
+fn extract_upstream_branch(repo: &Repository, local_branch: &Branch) -> Option<GitRemoteBranch> {
+  // The configured upstream outlives its ref, which is gone once the remote branch is deleted and pruned
+  let local_ref = local_branch.get().name()?;
+  let upstream_ref = repo.branch_upstream_name(local_ref).ok()?;
+  let upstream_ref = upstream_ref.as_str()?;
+  let name = upstream_ref.strip_prefix("refs/remotes/").unwrap_or(upstream_ref);
+  let mut upstream = GitRemoteBranch::new(String::from(name));
+  upstream.gone = repo.find_reference(upstream_ref).is_err();
+  Some(upstream)
 }
@@ -1,71 +1,188 @@
-use std::process::Command;
+use std::{
+  collections::{HashMap, HashSet},
+  env,
+  path::{Path, PathBuf},
+  process::Command,
+  sync::{Arc, Mutex},
+};
 
+use lazy_static::lazy_static;
 use regex::Regex;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 use crate::{
   error::Error,
-  git::git_repo::{GitBranch, GitRemoteBranch, GitRepo, GitStash},
+  git::git_repo::{
+    cherry_pick_conflict, BlameLine, BranchDetails, FileStatus, GitBranch, GitCommit, GitRemote, GitRemoteBranch,
+    GitRepo, GitStash, GitTag, GitWorktree, PullResult, RepoState, StatusKind,
+  },
+  utils::time::format_relative_time,
 };
 
-pub struct GitCliRepo {}
+pub struct GitCliRepo {
+  // Set when the repo comes from `GIT_DIR`, passed to git explicitly so a relative path survives a change of directory
+  git_dir: Option<PathBuf>,
+  // Commands run from here when `GIT_WORK_TREE` points somewhere other than the current directory
+  work_tree: Option<PathBuf>,
+  git_executable: PathBuf,
+  /// What `git --version` printed when the repo was opened, e.g. `git version 2.45.1`.
+  pub git_version: String,
+  warnings: Arc<Mutex<Vec<String>>>,
+}
 
 impl GitCliRepo {
-  pub fn from_cwd() -> Result<GitCliRepo, Error> {
-    // TODO check that the user is in a repo and throw if not
-    Ok(GitCliRepo {})
+  pub fn from_cwd(git_executable: &Path) -> Result<GitCliRepo, Error> {
+    if env::var_os("GIT_DIR").is_some() {
+      return GitCliRepo::from_env(git_executable);
+    }
+    let git_version = git_version(git_executable)?;
+    info!("Using {}", git_version);
+    let git_executable = git_executable.to_path_buf();
+    let repo = GitCliRepo { git_dir: None, work_tree: None, git_executable, git_version, warnings: Arc::default() };
+    repo.run_git_command(&["rev-parse", "--git-dir"])?;
+    Ok(repo)
+  }
+
+  /// Uses `GIT_DIR` and `GIT_WORK_TREE` when set rather than discovering the repo from the current directory.
+  pub fn from_env(git_executable: &Path) -> Result<GitCliRepo, Error> {
+    let Some(git_dir) = env::var_os("GIT_DIR") else {
+      return GitCliRepo::from_cwd(git_executable);
+    };
+    let git_version = git_version(git_executable)?;
+    info!("Using {}", git_version);
+    let git_dir = absolute_path(Path::new(&git_dir))?;
+    let cwd = env::current_dir().map_err(|err| Error::Git(err.to_string()))?;
+    let work_tree = match env::var_os("GIT_WORK_TREE") {
+      Some(work_tree) => Some(absolute_path(Path::new(&work_tree))?).filter(|work_tree| *work_tree != cwd),
+      None => None,
+    };
+    let git_executable = git_executable.to_path_buf();
+    Ok(GitCliRepo { git_dir: Some(git_dir), work_tree, git_executable, git_version, warnings: Arc::default() })
+  }
+
+  fn git_command(&self, args: &[&str]) -> Command {
+    let mut command = Command::new(&self.git_executable);
+    command.args(args);
+    if let Some(git_dir) = &self.git_dir {
+      command.env("GIT_DIR", git_dir);
+    }
+    if let Some(work_tree) = &self.work_tree {
+      command.env("GIT_WORK_TREE", work_tree).current_dir(work_tree);
+    }
+    command
+  }
+
+  /// Runs a git command only for its exit status. Unlike `run_git_command` a failure that prints nothing, like
+  /// `show-ref --quiet` not finding the ref, still counts as a failure.
+  fn git_command_succeeds(&self, args: &[&str]) -> Result<bool, Error> {
+    info!("Running `git {}`", args.join(" "));
+    let output = self.git_command(args).output().map_err(|err| Error::Git(err.to_string()))?;
+    Ok(output.status.success())
+  }
+
+  fn run_git_command(&self, args: &[&str]) -> Result<String, Error> {
+    let args_log_command = args.join(" ");
+    info!("Running `git {}`", args_log_command);
+    let res = self.git_command(args).output();
+    if res.is_err() {
+      let err = res.err().unwrap();
+      error!("Failed to run `git {}`, error: {}", args_log_command, err);
+      return Err(Error::Git(format!("{}", err)));
+    }
+
+    let output = res.unwrap();
+    let err_output = String::from_utf8(output.stderr)?;
+    if !output.status.success() && !err_output.is_empty() {
+      error!("Failed to run `git {}`, error: {}", args_log_command, err_output);
+      return Err(Error::Git(err_output));
+    }
+    if !err_output.trim().is_empty() {
+      warn!(stderr = %err_output, "Git command produced warnings");
+      self.warnings.lock().unwrap().push(String::from(err_output.trim()));
+    }
+    let content = String::from_utf8(output.stdout)?;
+    info!("Received git cli reply:\n{}", content);
+    Ok(content)
+  }
+
+  fn fast_forward_branch(&self, branch: &GitBranch, upstream: &GitRemoteBranch) -> PullResult {
+    let update = || -> Result<(usize, String), Error> {
+      let before = self.run_git_command(&["rev-parse", "--short", &branch.name])?.trim().to_string();
+      if branch.is_head {
+        // git refuses to fetch into the checked out branch
+        self.run_git_command(&["merge", "--ff-only", &upstream.name])?;
+      } else {
+        // A local fetch only updates the ref if it is a fast-forward and doesn't need a checkout
+        self.run_git_command(&["fetch", ".", &format!("{}:{}", upstream.name, branch.name)])?;
+      }
+      let after = self.run_git_command(&["rev-parse", "--short", &branch.name])?.trim().to_string();
+      let range = format!("{}..{}", before, after);
+      let new_commits = self.run_git_command(&["rev-list", "--count", &range])?.trim().parse().unwrap_or(0);
+      Ok((new_commits, range))
+    };
+
+    match update() {
+      Ok((new_commits, range)) => PullResult { branch_name: branch.name.clone(), new_commits, result: Ok(range) },
+      Err(err) => PullResult { branch_name: branch.name.clone(), new_commits: 0, result: Err(err.to_string()) },
+    }
   }
 }
 
 impl GitRepo for GitCliRepo {
   fn local_branches(&self) -> Result<Vec<GitBranch>, Error> {
-    let res = run_git_command(&["branch", "--list", "-vv"])?;
+    let res = self.run_git_command(&["branch", "--list", "-vv"])?;
+
+    let branches: Vec<GitBranch> = res.lines().map(parse_branch_line).collect();
 
-    let branches: Vec<GitBranch> = res
+    Ok(branches)
+  }
+
+  fn current_branch(&self) -> Result<GitBranch, Error> {
+    let name = self.run_git_command(&["symbolic-ref", "--short", "HEAD"])?;
+    Ok(GitBranch { name: String::from(name.trim()), is_head: true, ..Default::default() })
+  }
+
+  fn branches_merged_into(&self, target: &GitBranch) -> Result<Vec<GitBranch>, Error> {
+    let res = self.run_git_command(&["branch", "--merged", &target.name, "--format=%(refname:short)"])?;
+    Ok(res.lines().filter(|line| !line.is_empty()).map(|name| GitBranch::new(String::from(name))).collect())
+  }
+
+  fn remote_branches(&self) -> Result<Vec<GitRemoteBranch>, Error> {
+    let res =
+      self.run_git_command(&["branch", "--remotes", "--format=%(refname:short)%(if)%(symref)%(then) symref%(end)"])?;
+    // Symbolic refs like `origin/HEAD` point at another remote branch, they would be listed twice
+    let branches = res
       .lines()
-      .map(|line| {
-        let trimmed = line.trim();
-        // A regex to capture the following git list outputs
-        // * git-cli-repo 911ec26 [origin/git-cli-repo] Linting
-        //   main         8fb5d9b [origin/main] Fix build
-        //   stash-list   6442450 [origin/stash-list: gone] Formatting
-        //   test         dbcf785 Updates
-        let re = Regex::new(
-          r"((?<head>\*)\s+)?(?<name>\S+)\s+(?<sha>[A-Fa-f0-9]+)\s+(\[(?<upstream>[^:|^\]]+)(?<gone>[:\sgone]+)?)?",
-        )
-        .unwrap();
-        let Some(captures) = re.captures(trimmed) else {
-          error!("Failed to capture git branch information for: {}", trimmed);
-          return GitBranch::new(String::from(trimmed));
-        };
-        let is_head = captures.name("head").is_some();
-        let name = String::from(captures.name("name").unwrap().as_str());
-        let upstream = captures.name("upstream");
-        GitBranch {
-          name,
-          is_head,
-          upstream: upstream.map(|upstream_name| GitRemoteBranch::new(String::from(upstream_name.as_str()))),
-        }
-      })
+      .filter(|line| !line.trim().is_empty() && !line.ends_with(" symref"))
+      .map(|line| GitRemoteBranch::new(String::from(line.trim())))
       .collect();
-
     Ok(branches)
   }
 
-  fn stashes(&mut self) -> Result<Vec<GitStash>, Error> {
-    let res = run_git_command(&["branch", "--list"])?;
+  fn stashes(&self) -> Result<Vec<GitStash>, Error> {
+    let res = self.run_git_command(&["stash", "list", "--format=%H %ct %gs"])?;
 
     let stashes: Vec<GitStash> = res
       .lines()
       .enumerate()
-      .map(|(index, line)| GitStash::new(index, String::from(line.trim()), String::new()))
+      .map(|(index, line)| {
+        let mut parts = line.splitn(3, ' ');
+        let stash_id = parts.next().unwrap_or_default();
+        let created_at = parts.next().and_then(|time| time.parse().ok());
+        let message = parts.next().unwrap_or_default();
+        GitStash { created_at, ..GitStash::new(index, String::from(message), String::from(stash_id)) }
+      })
       .collect();
 
     Ok(stashes)
   }
 
+  fn get_stash_count(&self) -> Result<usize, Error> {
+    Ok(self.run_git_command(&["stash", "list", "--format=%gd"])?.lines().count())
+  }
+
   fn checkout_branch_from_name(&self, branch_name: &str) -> Result<(), Error> {
-    run_git_command(&["checkout", branch_name])?;
+    self.run_git_command(&["checkout", branch_name])?;
     Ok(())
   }
 
@@ -73,39 +190,886 @@ impl GitRepo for GitCliRepo {
     self.checkout_branch_from_name(&branch.name)
   }
 
+  fn checkout_tracking_branch(&self, remote_branch: &GitRemoteBranch) -> Result<(), Error> {
+    let local_ref = format!("refs/heads/{}", remote_branch.short_name());
+    if self.git_command_succeeds(&["show-ref", "--verify", "--quiet", &local_ref])? {
+      return Err(Error::Git(format!("branch already exists: {}", remote_branch.short_name())));
+    }
+    self.run_git_command(&["checkout", "--track", &remote_branch.name])?;
+    Ok(())
+  }
+
   fn validate_branch_name(&self, name: &str) -> Result<bool, Error> {
-    let res = run_git_command(&["check-ref-format", "--branch", name]);
+    let res = self.run_git_command(&["check-ref-format", "--branch", name]);
     Ok(res.is_ok())
   }
 
   fn create_branch(&self, to_create: &GitBranch) -> Result<(), Error> {
-    run_git_command(&["checkout", "-b", &to_create.name])?;
+    self.run_git_command(&["checkout", "-b", &to_create.name])?;
+    Ok(())
+  }
+
+  fn create_branch_at(&self, name: &str, sha: &str) -> Result<(), Error> {
+    self.run_git_command(&["branch", name, sha])?;
     Ok(())
   }
 
   fn delete_branch(&self, to_delete: &GitBranch) -> Result<(), Error> {
-    run_git_command(&["branch", "-D", &to_delete.name])?;
+    self.run_git_command(&["branch", "-D", &to_delete.name])?;
+    Ok(())
+  }
+
+  fn rename_branch(&self, branch: &GitBranch, new_name: &str) -> Result<(), Error> {
+    self.run_git_command(&["branch", "-m", &branch.name, new_name])?;
+    Ok(())
+  }
+
+  fn set_upstream(&self, branch: &GitBranch, upstream: &str) -> Result<(), Error> {
+    self.run_git_command(&["branch", &format!("--set-upstream-to={}", upstream), &branch.name])?;
+    Ok(())
+  }
+
+  fn unset_upstream(&self, branch: &GitBranch) -> Result<(), Error> {
+    self.run_git_command(&["branch", "--unset-upstream", &branch.name])?;
+    Ok(())
+  }
+
+  fn delete_remote_branch(&self, remote: &str, branch: &GitRemoteBranch) -> Result<(), Error> {
+    let prefix = format!("{}/", remote);
+    let short_name = branch.name.strip_prefix(&prefix).unwrap_or(&branch.name);
+    self.run_git_command(&["push", remote, "--delete", short_name])?;
+    Ok(())
+  }
+
+  fn list_tags(&self) -> Result<Vec<GitTag>, Error> {
+    // `%(*objectname)` is the peeled commit, only set for annotated tags
+    let res =
+      self.run_git_command(&["tag", "-l", "--format=%(refname:short) %(objecttype) %(objectname) %(*objectname)"])?;
+    let tags = res
+      .lines()
+      .filter_map(|line| {
+        let mut parts = line.split_whitespace();
+        let (name, object_type, object_sha) = (parts.next()?, parts.next()?, parts.next()?);
+        let commit_sha = parts.next().unwrap_or(object_sha);
+        Some(GitTag {
+          name: String::from(name),
+          commit_sha: String::from(commit_sha),
+          is_annotated: object_type == "tag",
+        })
+      })
+      .collect();
+    Ok(tags)
+  }
+
+  fn delete_tag(&self, tag: &GitTag) -> Result<(), Error> {
+    self.run_git_command(&["tag", "-d", &tag.name])?;
+    Ok(())
+  }
+
+  fn create_stash(&self, message: &str, include_untracked: bool, include_ignored: bool) -> Result<(), Error> {
+    let mut args = vec!["stash", "push"];
+    if include_untracked {
+      args.push("--include-untracked");
+    }
+    if include_ignored {
+      // --all stashes untracked files as well as ignored ones
+      args.push("--all");
+    }
+    args.extend(["-m", message]);
+    self.run_git_command(&args)?;
+    Ok(())
+  }
+
+  fn stash_no_verify(&self, message: &str) -> Result<bool, Error> {
+    // `git stash` has no --no-verify, but it does run hooks like reference-transaction, so point git at no hooks
+    let output = self.run_git_command(&["-c", "core.hooksPath=/dev/null", "stash", "push", "-m", message])?;
+    Ok(!output.contains("No local changes to save"))
+  }
+
+  fn stash_push_pathspec(&self, message: &str, pathspec: &[&str]) -> Result<bool, Error> {
+    let mut args = vec!["stash", "push", "-m", message, "--"];
+    args.extend(pathspec);
+    match self.run_git_command(&args) {
+      Ok(output) => Ok(!output.contains("No local changes to save")),
+      Err(Error::Git(err)) if err.contains("did not match any file") => Ok(false),
+      Err(err) => Err(err),
+    }
+  }
+
+  fn apply_stash(&self, stash: &GitStash) -> Result<(), Error> {
+    self.run_git_command(&["stash", "apply", &format!("stash@{{{}}}", stash.index)])?;
+    Ok(())
+  }
+
+  fn create_branch_from_stash(&self, stash: &GitStash, branch_name: &str) -> Result<Vec<String>, Error> {
+    let result = self.run_git_command(&["stash", "branch", branch_name, &format!("stash@{{{}}}", stash.index)]);
+    // A conflicting pop fails the command but still leaves the new branch checked out
+    let status = self.run_git_command(&["status", "--porcelain"])?;
+    let conflicts: Vec<String> = status
+      .lines()
+      .filter(|line| matches!(line.get(..2), Some("DD" | "AU" | "UD" | "UA" | "DU" | "AA" | "UU")))
+      .filter_map(|line| line.get(3..))
+      .map(String::from)
+      .collect();
+    if conflicts.is_empty() {
+      result?;
+    }
+    Ok(conflicts)
+  }
+
+  fn pop_stash(&self, stash: &GitStash) -> Result<(), Error> {
+    self.run_git_command(&["stash", "pop", &format!("stash@{{{}}}", stash.index)])?;
+    Ok(())
+  }
+
+  fn drop_stash(&self, stash: &GitStash) -> Result<(), Error> {
+    self.run_git_command(&["stash", "drop", &format!("stash@{{{}}}", stash.index)])?;
+    Ok(())
+  }
+
+  fn get_stash_conflict_files(&self, stash: &GitStash) -> Result<Vec<String>, Error> {
+    let stash_files =
+      self.run_git_command(&["diff", "--name-only", &format!("{}^1", stash.stash_id), &stash.stash_id])?;
+    let status = self.run_git_command(&["status", "--porcelain"])?;
+    // Porcelain lines look like `XY path` or `XY old -> new` for renames
+    let modified: HashSet<&str> =
+      status.lines().filter_map(|line| line.get(3..)).map(|path| path.rsplit(" -> ").next().unwrap_or(path)).collect();
+
+    Ok(stash_files.lines().filter(|file| modified.contains(file)).map(String::from).collect())
+  }
+
+  fn stash_show_stat(&self, stash: &GitStash) -> Result<String, Error> {
+    let res = self.run_git_command(&["stash", "show", "--stat", &stash.stash_id])?;
+    // The per file lines come first, the summary is always last
+    Ok(res.lines().last().map(|line| String::from(line.trim())).unwrap_or_default())
+  }
+
+  fn stash_diff(&self, stash: &GitStash) -> Result<String, Error> {
+    self.run_git_command(&["stash", "show", "-p", "--color=always", &stash.stash_id])
+  }
+
+  fn fetch(&self, remote: Option<&str>) -> Result<(), Error> {
+    match remote {
+      Some(remote) => self.run_git_command(&["fetch", remote])?,
+      None => self.run_git_command(&["fetch", "--all"])?,
+    };
+    Ok(())
+  }
+
+  fn merge_branch(&self, from: &GitBranch) -> Result<(), Error> {
+    let result = self.run_git_command(&["merge", &from.name]);
+    // git reports conflicts on stdout, ask for the unmerged files rather than parsing it
+    let unmerged = self.run_git_command(&["diff", "--name-only", "--diff-filter=U"])?;
+    let conflicts: Vec<&str> = unmerged.lines().filter(|line| !line.trim().is_empty()).collect();
+    if !conflicts.is_empty() {
+      return Err(Error::Git(format!("Merging {} conflicted in: {}", from.name, conflicts.join(", "))));
+    }
+    result?;
+    Ok(())
+  }
+
+  fn rebase_branch(&self, onto: &GitBranch) -> Result<(), Error> {
+    let result = self.run_git_command(&["rebase", &onto.name]);
+    // A rebase that stops part way leaves its state in the git dir for `git rebase --continue`
+    let git_dir = PathBuf::from(self.run_git_command(&["rev-parse", "--absolute-git-dir"])?.trim());
+    if ["rebase-merge", "rebase-apply"].iter().any(|state_dir| git_dir.join(state_dir).exists()) {
+      return Err(Error::Git(format!(
+        "Rebasing onto {} stopped part way, resolve any conflicts then run git rebase --continue or --abort",
+        onto.name
+      )));
+    }
+    result?;
     Ok(())
   }
+
+  fn push_branch(&self, branch: &GitBranch, remote: Option<&str>, force: bool) -> Result<(), Error> {
+    let mut args = vec!["push"];
+    if force {
+      args.push("--force");
+    }
+    if let Some(remote) = remote {
+      args.extend([remote, &branch.name]);
+    }
+    self.run_git_command(&args)?;
+    Ok(())
+  }
+
+  fn pull(&self, remote: Option<&str>, rebase: bool) -> Result<(), Error> {
+    let mut args = vec!["pull"];
+    if rebase {
+      args.push("--rebase");
+    }
+    args.extend(remote);
+    let result = self.run_git_command(&args);
+    // Like merging, ask for the unmerged files rather than parsing git's report of them
+    let unmerged = self.run_git_command(&["diff", "--name-only", "--diff-filter=U"])?;
+    let conflicts: Vec<&str> = unmerged.lines().filter(|line| !line.trim().is_empty()).collect();
+    if !conflicts.is_empty() {
+      return Err(Error::Git(format!("Pulling conflicted in: {}", conflicts.join(", "))));
+    }
+    result?;
+    Ok(())
+  }
+
+  fn prune_gone_branches(&self) -> Result<Vec<GitBranch>, Error> {
+    let remotes = self.run_git_command(&["remote"])?;
+    for remote in remotes.lines().map(str::trim).filter(|remote| !remote.is_empty()) {
+      self.run_git_command(&["remote", "prune", remote])?;
+    }
+    let gone: Vec<GitBranch> = self
+      .local_branches()?
+      .into_iter()
+      .filter(|branch| !branch.is_head && !branch.is_worktree)
+      .filter(|branch| branch.upstream.as_ref().is_some_and(|upstream| upstream.gone))
+      .collect();
+    for branch in &gone {
+      self.delete_branch(branch)?;
+    }
+    Ok(gone)
+  }
+
+  fn pull_all_branches(&self) -> Result<Vec<PullResult>, Error> {
+    self.run_git_command(&["fetch", "--all"])?;
+    let results = self
+      .local_branches()?
+      .iter()
+      .filter_map(|branch| {
+        let upstream = branch.upstream.as_ref().filter(|upstream| !upstream.gone)?;
+        Some(self.fast_forward_branch(branch, upstream))
+      })
+      .collect();
+    Ok(results)
+  }
+
+  fn get_last_commit_times(&self) -> Result<HashMap<String, i64>, Error> {
+    let res =
+      self.run_git_command(&["for-each-ref", "--format=%(refname:short) %(committerdate:unix)", "refs/heads/"])?;
+    let times = res
+      .lines()
+      .filter_map(|line| {
+        let (name, time) = line.rsplit_once(' ')?;
+        Some((String::from(name), time.parse().ok()?))
+      })
+      .collect();
+    Ok(times)
+  }
+
+  fn get_branch_creation_time(&self, branch: &GitBranch) -> Result<Option<i64>, Error> {
+    // The reflog is newest first so the last entry is when the branch was created
+    let reflog =
+      self.run_git_command(&["reflog", "show", "--format=%ct", &format!("refs/heads/{}", branch.name), "--"])?;
+    Ok(reflog.lines().last().and_then(|time| time.trim().parse().ok()))
+  }
+
+  fn get_ahead_behind_bulk(&self) -> Result<HashMap<String, (u32, u32)>, Error> {
+    // Lines look like `feature/foo [ahead 2, behind 1]`, `main [gone]` or `test ` without an upstream
+    let res = self.run_git_command(&["for-each-ref", "--format=%(refname:short) %(upstream:track)", "refs/heads/"])?;
+    let counts = res
+      .lines()
+      .filter_map(|line| {
+        let (name, track) = line.split_once(' ')?;
+        Some((String::from(name), parse_upstream_track(track)))
+      })
+      .collect();
+    Ok(counts)
+  }
+
+  fn show_commit_diff(&self, sha: &str) -> Result<String, Error> {
+    self.run_git_command(&["show", sha, "--stat", "--patch", "--no-color"])
+  }
+
+  fn diff_branch(&self, from: &GitBranch, to: &GitBranch) -> Result<String, Error> {
+    let range = format!("{}..{}", from.name, to.name);
+    self.run_git_command(&["diff", "--color=always", &range, "--"])
+  }
+
+  fn current_head(&self) -> Result<(String, String), Error> {
+    let branch = self.run_git_command(&["rev-parse", "--abbrev-ref", "HEAD"])?;
+    let sha = self.run_git_command(&["rev-parse", "--short", "HEAD"])?;
+    Ok((String::from(branch.trim()), String::from(sha.trim())))
+  }
+
+  fn get_repository_state(&self) -> Result<RepoState, Error> {
+    // git marks each in progress operation with a file or directory in the git dir
+    let git_dir = PathBuf::from(self.run_git_command(&["rev-parse", "--absolute-git-dir"])?.trim());
+    let markers = [
+      ("rebase-merge", RepoState::Rebasing),
+      ("rebase-apply", RepoState::Rebasing),
+      ("MERGE_HEAD", RepoState::Merging),
+      ("CHERRY_PICK_HEAD", RepoState::CherryPicking),
+      ("REVERT_HEAD", RepoState::Reverting),
+      ("BISECT_LOG", RepoState::Bisecting),
+    ];
+    let state = markers.into_iter().find(|(marker, _)| git_dir.join(marker).exists()).map(|(_, state)| state);
+    Ok(state.unwrap_or_default())
+  }
+
+  fn work_dir(&self) -> Result<PathBuf, Error> {
+    Ok(PathBuf::from(self.run_git_command(&["rev-parse", "--show-toplevel"])?.trim()))
+  }
+
+  fn working_status(&self) -> Result<Vec<FileStatus>, Error> {
+    // -z leaves paths unquoted, they are relative to the top of the working tree in porcelain output
+    let output = self.run_git_command(&["status", "--porcelain=v1", "-z"])?;
+    Ok(parse_status(&output))
+  }
+
+  fn stage_file(&self, path: &str) -> Result<(), Error> {
+    self.run_git_command(&["add", "--", &top_level_pathspec(path)])?;
+    Ok(())
+  }
+
+  fn unstage_file(&self, path: &str) -> Result<(), Error> {
+    self.run_git_command(&["reset", "--quiet", "--", &top_level_pathspec(path)])?;
+    Ok(())
+  }
+
+  fn blame(&self, path: &Path, branch: Option<&GitBranch>) -> Result<Vec<BlameLine>, Error> {
+    // blame takes a file rather than a pathspec, relative to the current directory
+    let path = self.work_dir()?.join(path);
+    let path = path.to_string_lossy();
+    let mut args = vec!["blame", "--porcelain"];
+    if let Some(branch) = branch {
+      args.push(&branch.name);
+    }
+    args.extend(["--", &path]);
+    Ok(parse_blame(&self.run_git_command(&args)?))
+  }
+
+  fn list_worktrees(&self) -> Result<Vec<GitWorktree>, Error> {
+    // Each worktree is a block of `worktree <path>`, `HEAD <sha>` then `branch <ref>` or `detached`, with `bare` and
+    // `locked [reason]` lines when they apply
+    let res = self.run_git_command(&["worktree", "list", "--porcelain"])?;
+    let mut worktrees: Vec<GitWorktree> = Vec::new();
+    for line in res.lines() {
+      if let Some(path) = line.strip_prefix("worktree ") {
+        let is_main = worktrees.is_empty();
+        worktrees.push(GitWorktree { path: PathBuf::from(path), is_main, ..Default::default() });
+      } else if let Some(worktree) = worktrees.last_mut() {
+        if let Some(branch) = line.strip_prefix("branch ") {
+          worktree.branch = Some(String::from(branch.strip_prefix("refs/heads/").unwrap_or(branch)));
+        } else if line == "bare" {
+          worktree.is_bare = true;
+        } else if line == "locked" || line.starts_with("locked ") {
+          worktree.is_locked = true;
+        }
+      }
+    }
+    Ok(worktrees)
+  }
+
+  fn remove_worktree(&self, worktree: &GitWorktree) -> Result<(), Error> {
+    self.run_git_command(&["worktree", "remove", "--force", &worktree.path.to_string_lossy()])?;
+    Ok(())
+  }
+
+  fn create_worktree(&self, branch: &GitBranch, path: &str) -> Result<(), Error> {
+    self.run_git_command(&["worktree", "add", path, &branch.name])?;
+    Ok(())
+  }
+
+  fn list_remotes(&self) -> Result<Vec<GitRemote>, Error> {
+    let res = self.run_git_command(&["remote", "-v"])?;
+    Ok(parse_remotes(&res))
+  }
+
+  fn add_remote(&self, name: &str, url: &str) -> Result<(), Error> {
+    self.run_git_command(&["remote", "add", name, url])?;
+    Ok(())
+  }
+
+  fn remove_remote(&self, name: &str) -> Result<(), Error> {
+    self.run_git_command(&["remote", "rm", name])?;
+    Ok(())
+  }
+
+  fn rename_remote(&self, old_name: &str, new_name: &str) -> Result<(), Error> {
+    self.run_git_command(&["remote", "rename", old_name, new_name])?;
+    Ok(())
+  }
+
+  fn cherry_pick(&self, commit_sha: &str) -> Result<(), Error> {
+    let result = self.run_git_command(&["cherry-pick", commit_sha]);
+    // Like merging, ask for the unmerged files rather than parsing git's report of them
+    let unmerged = self.run_git_command(&["diff", "--name-only", "--diff-filter=U"])?;
+    let conflicts: Vec<&str> = unmerged.lines().filter(|line| !line.trim().is_empty()).collect();
+    if !conflicts.is_empty() {
+      return Err(cherry_pick_conflict(commit_sha, &conflicts));
+    }
+    result?;
+    Ok(())
+  }
+
+  fn commit_log(&self, branch: &GitBranch, limit: usize) -> Result<Vec<GitCommit>, Error> {
+    let limit = format!("-n{}", limit);
+    let log = self.run_git_command(&["log", COMMIT_FORMAT, &limit, &branch.name, "--"])?;
+    Ok(log.lines().filter(|line| !line.is_empty()).map(parse_commit).collect())
+  }
+
+  fn get_branch_details(&self, branch: &GitBranch, main_branch: &str) -> Result<BranchDetails, Error> {
+    let log = self.run_git_command(&["log", COMMIT_FORMAT, "-1", &branch.name])?;
+    let last_commit = parse_commit(log.trim_end());
+
+    let range = format!("{}...{}", branch.name, main_branch);
+    let counts = self.run_git_command(&["rev-list", "--count", "--left-right", &range])?;
+    let (ahead, behind) = counts.trim().split_once('\t').unwrap_or_default();
+    let ahead = ahead.parse().unwrap_or(0);
+    let behind = behind.parse().unwrap_or(0);
+
+    let merge_base_sha = self.run_git_command(&["merge-base", &branch.name, main_branch])?.trim().to_string();
+    // Nothing on the branch is missing from main, so it has been merged
+    Ok(BranchDetails { last_commit, ahead, behind, merge_base_sha, is_merged: ahead == 0 })
+  }
+
+  fn get_and_clear_warnings(&self) -> Vec<String> {
+    std::mem::take(&mut *self.warnings.lock().unwrap())
+  }
+}
+
+// Fields are separated by the unit separator so subjects can contain anything
+const COMMIT_FORMAT: &str = "--format=%H%x1f%an%x1f%ct%x1f%s";
+
+lazy_static! {
+  // Captures the following `git branch --list -vv` outputs
+  // * git-cli-repo 911ec26 [origin/git-cli-repo] Linting
+  //   main         8fb5d9b [origin/main] Fix build
+  //   stash-list   6442450 [origin/stash-list: gone] Formatting
+  //   feature      1a2b3c4 [origin/feature: ahead 2, behind 1] Diverged
+  //   test         dbcf785 Updates
+  // * (HEAD detached at 1a2b3c4) 1a2b3c4 Detached
+  static ref BRANCH_LINE: Regex = Regex::new(
+    r"((?<head>\*)\s+)?(?<name>\([^)]*\)|\S+)\s+(?<sha>[A-Fa-f0-9]+)(\s+|$)(\[(?<upstream>[^:|^\]]+)(:\s+(?<track>[^\]]+))?)?",
+  )
+  .unwrap();
+}
+
+/// Parses a line of `git branch --list -vv` output. Lines that can't be parsed become a branch named after the line.
+fn parse_branch_line(line: &str) -> GitBranch {
+  let trimmed = line.trim();
+  let Some(captures) = BRANCH_LINE.captures(trimmed) else {
+    error!("Failed to capture git branch information for: {}", trimmed);
+    return GitBranch::new(String::from(trimmed));
+  };
+  let is_head = captures.name("head").is_some();
+  let name = String::from(captures.name("name").unwrap().as_str());
+  let upstream = captures.name("upstream");
+  let track = captures.name("track").map_or("", |track| track.as_str());
+  let gone = track == "gone";
+  let (ahead, behind) = parse_upstream_track(track);
+  let sha = String::from(captures.name("sha").unwrap().as_str());
+  GitBranch {
+    name,
+    is_head,
+    sha,
+    upstream: upstream.map(|upstream_name| GitRemoteBranch { name: String::from(upstream_name.as_str()), gone }),
+    ahead,
+    behind,
+    ..Default::default()
+  }
+}
+
+/// A pathspec matching exactly `path`, relative to the top of the working tree rather than the current directory.
+fn top_level_pathspec(path: &str) -> String {
+  format!(":(top,literal){}", path)
+}
+
+fn status_kind(code: char) -> Option<StatusKind> {
+  match code {
+    'M' | 'T' => Some(StatusKind::Modified),
+    'A' | 'C' => Some(StatusKind::Added),
+    'D' => Some(StatusKind::Deleted),
+    'R' => Some(StatusKind::Renamed),
+    _ => None,
+  }
+}
+
+/// Parses `git blame --porcelain`. Each line comes as a `<sha> <original line> <final line> [<count>]` header, then
+/// details of the commit the first time it appears, then the line itself prefixed with a tab.
+fn parse_blame(output: &str) -> Vec<BlameLine> {
+  // Author and time by sha, as they are only given once per commit
+  let mut commits: HashMap<String, (String, i64)> = HashMap::new();
+  let mut current: Option<(String, usize)> = None;
+  let mut lines = Vec::new();
+  for line in output.lines() {
+    if let Some(content) = line.strip_prefix('\t') {
+      let Some((sha, line_number)) = current.take() else {
+        continue;
+      };
+      let (author, time) = commits.get(&sha).cloned().unwrap_or_default();
+      lines.push(BlameLine {
+        sha,
+        author,
+        date: format_relative_time(time),
+        line_number,
+        content: String::from(content),
+      });
+      continue;
+    }
+    let Some((sha, _)) = &current else {
+      let mut parts = line.split(' ');
+      let sha = parts.next().unwrap_or_default();
+      let line_number = parts.nth(1).and_then(|number| number.parse().ok()).unwrap_or_default();
+      current = Some((String::from(sha), line_number));
+      continue;
+    };
+    if let Some(author) = line.strip_prefix("author ") {
+      commits.entry(sha.clone()).or_default().0 = String::from(author);
+    } else if let Some(time) = line.strip_prefix("author-time ") {
+      commits.entry(sha.clone()).or_default().1 = time.parse().unwrap_or_default();
+    }
+  }
+  lines
 }
 
-fn run_git_command(args: &[&str]) -> Result<String, Error> {
-  let args_log_command = args.join(" ");
-  info!("Running `git {}`", args_log_command);
-  let res = Command::new("git").args(args).output();
-  if res.is_err() {
-    let err = res.err().unwrap();
-    error!("Failed to run `git {}`, error: {}", args_log_command, err);
-    return Err(Error::Git(format!("{}", err)));
-  }
-
-  let output = res.unwrap();
-  let err = String::from_utf8(output.stderr)?;
-  if !output.status.success() && !err.is_empty() {
-    error!("Failed to run `git {}`, error: {}", args_log_command, err);
-    return Err(Error::Git(err));
-  }
-  let content = String::from_utf8(output.stdout)?;
-  info!("Received git cli reply:\n{}", content);
-  Ok(content)
+/// Parses `git status --porcelain=v1 -z` output, entries look like `XY path` where `X` is the index's status and `Y`
+/// the working tree's. Renames and copies are followed by the original path.
+fn parse_status(output: &str) -> Vec<FileStatus> {
+  let mut files = Vec::new();
+  let mut entries = output.split('\0').filter(|entry| !entry.is_empty());
+  while let Some(entry) = entries.next() {
+    let mut codes = entry.chars();
+    let (Some(index), Some(work_tree)) = (codes.next(), codes.next()) else {
+      continue;
+    };
+    let Some(path) = entry.get(3..) else {
+      continue;
+    };
+    if matches!(index, 'R' | 'C') {
+      entries.next();
+    }
+    let path = PathBuf::from(path);
+    match (index, work_tree) {
+      ('?', '?') => files.push(FileStatus { path, status: StatusKind::Untracked, staged: false }),
+      ('!', '!') => {},
+      // Conflicted, both sides have changes that need resolving in the working tree
+      ('D', 'D') | ('A', 'U') | ('U', _) | (_, 'U') | ('A', 'A') => {
+        files.push(FileStatus { path, status: StatusKind::Modified, staged: false });
+      },
+      _ => {
+        if let Some(status) = status_kind(index) {
+          files.push(FileStatus { path: path.clone(), status, staged: true });
+        }
+        if let Some(status) = status_kind(work_tree) {
+          files.push(FileStatus { path, status, staged: false });
+        }
+      },
+    }
+  }
+  files
+}
+
+/// Parses `git remote -v` output, each remote has a line like `origin<TAB>url (fetch)` followed by one ending in
+/// `(push)`.
+fn parse_remotes(output: &str) -> Vec<GitRemote> {
+  let mut remotes: Vec<GitRemote> = Vec::new();
+  for line in output.lines() {
+    let Some((name, rest)) = line.split_once('\t') else {
+      continue;
+    };
+    let Some((url, kind)) = rest.rsplit_once(' ') else {
+      continue;
+    };
+    if remotes.last().is_none_or(|remote| remote.name != name) {
+      remotes.push(GitRemote { name: String::from(name), ..Default::default() });
+    }
+    let remote = remotes.last_mut().unwrap();
+    match kind {
+      "(fetch)" => remote.fetch_url = String::from(url),
+      "(push)" => remote.push_url = String::from(url),
+      _ => {},
+    }
+  }
+  remotes
+}
+
+/// Parses a line of `git log` output in `COMMIT_FORMAT`.
+fn parse_commit(line: &str) -> GitCommit {
+  let mut fields = line.splitn(4, '\x1f');
+  GitCommit {
+    sha: String::from(fields.next().unwrap_or_default()),
+    author: String::from(fields.next().unwrap_or_default()),
+    committed_at: fields.next().and_then(|time| time.parse().ok()).unwrap_or_default(),
+    subject: String::from(fields.next().unwrap_or_default()),
+  }
+}
+
+/// The output of `git --version` for `git_executable`, which also checks it can be run.
+pub fn git_version(git_executable: &Path) -> Result<String, Error> {
+  let output = Command::new(git_executable)
+    .arg("--version")
+    .output()
+    .map_err(|err| Error::Git(format!("Unable to run git at {}: {}", git_executable.display(), err)))?;
+  if !output.status.success() {
+    return Err(Error::Git(format!("Unable to run git at {}: {}", git_executable.display(), output.status)));
+  }
+  Ok(String::from(String::from_utf8(output.stdout)?.trim()))
+}
+
+fn absolute_path(path: &Path) -> Result<PathBuf, Error> {
+  std::path::absolute(path).map_err(|err| Error::Git(format!("Invalid path {}: {}", path.display(), err)))
+}
+
+fn parse_upstream_track(track: &str) -> (u32, u32) {
+  let mut counts = (0, 0);
+  for part in track.trim().trim_start_matches('[').trim_end_matches(']').split(", ") {
+    if let Some(ahead) = part.strip_prefix("ahead ") {
+      counts.0 = ahead.parse().unwrap_or(0);
+    } else if let Some(behind) = part.strip_prefix("behind ") {
+      counts.1 = behind.parse().unwrap_or(0);
+    }
+  }
+  counts
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{components::ui::ansi::styled_lines, git::temp_repo::TempRepo};
+
+  const NAMES: &[&str] =
+    &["main", "feature/login", "user/fix/deep/nesting", "release-1.2.3", "under_score", "emoji-🚀", "issue#12@v2", "a"];
+  const SHAS: &[&str] = &["1a2b3c4", "DEADBEEF", "0123456789abcdef0123456789abcdef01234567"];
+  const UPSTREAMS: &[Option<&str>] = &[None, Some("origin/main"), Some("upstream/feature/login")];
+  const TRACKS: &[(&str, bool, u32, u32)] =
+    &[("", false, 0, 0), ("gone", true, 0, 0), ("ahead 2", false, 2, 0), ("ahead 3, behind 14", false, 3, 14)];
+  const MESSAGES: &[&str] = &["Fix build", "", "Merge branch 'x' into y", "feat: add [brackets] later"];
+
+  fn branch_line(is_head: bool, name: &str, sha: &str, upstream: Option<&str>, track: &str, message: &str) -> String {
+    let marker = if is_head { "*" } else { " " };
+    let upstream = match (upstream, track) {
+      (Some(upstream), "") => format!("[{}] ", upstream),
+      (Some(upstream), track) => format!("[{}: {}] ", upstream, track),
+      (None, _) => String::new(),
+    };
+    format!("{} {:<30} {} {}{}", marker, name, sha, upstream, message)
+  }
+
+  #[test]
+  fn generated_branch_lines_round_trip() {
+    for &is_head in &[true, false] {
+      for name in NAMES {
+        for sha in SHAS {
+          for upstream in UPSTREAMS {
+            for &(track, gone, ahead, behind) in TRACKS {
+              for message in MESSAGES {
+                let line = branch_line(is_head, name, sha, *upstream, track, message);
+                let branch = parse_branch_line(&line);
+                assert_eq!(branch.name, *name, "{}", line);
+                assert_eq!(branch.sha, *sha, "{}", line);
+                assert_eq!(branch.is_head, is_head, "{}", line);
+                let expected_upstream = upstream.map(|name| GitRemoteBranch { name: String::from(name), gone });
+                assert_eq!(branch.upstream, expected_upstream, "{}", line);
+                if upstream.is_some() {
+                  assert_eq!((branch.ahead, branch.behind), (ahead, behind), "{}", line);
+                }
+              }
+            }
+          }
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn detached_head_keeps_its_description_as_the_name() {
+    let branch = parse_branch_line("* (HEAD detached at 1a2b3c4) 1a2b3c4 Detached");
+    assert_eq!(branch.name, "(HEAD detached at 1a2b3c4)");
+    assert_eq!(branch.sha, "1a2b3c4");
+    assert!(branch.is_head);
+    assert_eq!(branch.upstream, None);
+  }
+
+  #[test]
+  fn arbitrary_lines_never_panic() {
+    const ALPHABET: &[char] = &['*', ' ', '[', ']', ':', '(', ')', ',', '/', 'a', 'f', '0', '9', 'z', '🚀', '\t'];
+    // A fixed xorshift so failures are reproducible
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = || {
+      state ^= state << 13;
+      state ^= state >> 7;
+      state ^= state << 17;
+      state
+    };
+    for _ in 0..2000 {
+      let length = next() % 40;
+      let line: String = (0..length).map(|_| ALPHABET[(next() % ALPHABET.len() as u64) as usize]).collect();
+      let branch = parse_branch_line(&line);
+      if !BRANCH_LINE.is_match(line.trim()) {
+        assert_eq!(branch, GitBranch::new(String::from(line.trim())));
+      }
+    }
+  }
+
+  fn branch_names(repo: &GitCliRepo) -> Vec<String> {
+    repo.local_branches().unwrap().into_iter().map(|branch| branch.name).collect()
+  }
+
+  #[test]
+  fn a_new_repo_only_has_its_initial_branch() {
+    let temp_repo = TempRepo::init();
+    let repo = temp_repo.cli_repo();
+    let branches = repo.local_branches().unwrap();
+    assert_eq!(branches.len(), 1);
+    assert_eq!(branches[0].name, "main");
+    assert!(branches[0].is_head);
+  }
+
+  #[test]
+  fn created_branches_are_listed_and_checked_out() {
+    let temp_repo = TempRepo::init();
+    let repo = temp_repo.cli_repo();
+    repo.create_branch(&GitBranch::new(String::from("feature/a"))).unwrap();
+    assert_eq!(branch_names(&repo), vec!["feature/a", "main"]);
+    assert_eq!(repo.current_branch().unwrap().name, "feature/a");
+  }
+
+  #[test]
+  fn deleted_branches_are_no_longer_listed() {
+    let temp_repo = TempRepo::init();
+    let repo = temp_repo.cli_repo();
+    let branch = GitBranch::new(String::from("feature/a"));
+    repo.create_branch(&branch).unwrap();
+    repo.checkout_branch_from_name("main").unwrap();
+    repo.delete_branch(&branch).unwrap();
+    assert_eq!(branch_names(&repo), vec!["main"]);
+  }
+
+  #[test]
+  fn stashing_keeps_the_message() {
+    let temp_repo = TempRepo::init();
+    temp_repo.commit_file("notes.txt", "first");
+    temp_repo.write_file("notes.txt", "second");
+    let repo = temp_repo.cli_repo();
+    repo.create_stash("Work in progress", false, false).unwrap();
+    let stashes = repo.stashes().unwrap();
+    assert_eq!(stashes.len(), 1);
+    assert!(stashes[0].message.contains("Work in progress"), "{}", stashes[0].message);
+    assert_eq!(temp_repo.git(&["status", "--porcelain"]), "");
+  }
+
+  #[test]
+  fn dropped_stashes_are_removed() {
+    let temp_repo = TempRepo::init();
+    temp_repo.commit_file("notes.txt", "first");
+    temp_repo.write_file("notes.txt", "second");
+    let repo = temp_repo.cli_repo();
+    repo.create_stash("Work in progress", false, false).unwrap();
+    let stash = repo.stashes().unwrap().remove(0);
+    repo.drop_stash(&stash).unwrap();
+    assert!(repo.stashes().unwrap().is_empty());
+    assert_eq!(repo.get_stash_count().unwrap(), 0);
+  }
+
+  #[test]
+  fn pathspec_stashes_only_take_matching_files() {
+    let temp_repo = TempRepo::init();
+    temp_repo.commit_file("a.txt", "a");
+    temp_repo.commit_file("b.txt", "b");
+    temp_repo.write_file("a.txt", "changed");
+    temp_repo.write_file("b.txt", "changed");
+    let repo = temp_repo.cli_repo();
+    assert!(repo.stash_push_pathspec("Only a", &["a.txt"]).unwrap());
+    assert_eq!(temp_repo.git(&["status", "--porcelain"]), " M b.txt\n");
+    assert!(!repo.stash_push_pathspec("Nothing", &["a.txt"]).unwrap());
+  }
+
+  #[test]
+  fn merged_branches_are_reachable_from_the_target() {
+    let temp_repo = TempRepo::init();
+    temp_repo.git(&["branch", "merged"]);
+    temp_repo.git(&["checkout", "-q", "-b", "unmerged"]);
+    temp_repo.commit_file("a.txt", "a");
+    temp_repo.git(&["checkout", "-q", "main"]);
+    let repo = temp_repo.cli_repo();
+    let merged = repo.branches_merged_into(&GitBranch::new(String::from("main"))).unwrap();
+    let names: Vec<&str> = merged.iter().map(|branch| branch.name.as_str()).collect();
+    assert_eq!(names, vec!["main", "merged"]);
+  }
+
+  #[test]
+  fn remotes_can_be_added_renamed_and_removed() {
+    let temp_repo = TempRepo::init();
+    let repo = temp_repo.cli_repo();
+    repo.add_remote("origin", "https://example.com/repo.git").unwrap();
+    temp_repo.git(&["remote", "set-url", "--push", "origin", "git@example.com:repo.git"]);
+    repo.rename_remote("origin", "upstream").unwrap();
+    assert_eq!(repo.list_remotes().unwrap(), vec![GitRemote {
+      name: String::from("upstream"),
+      fetch_url: String::from("https://example.com/repo.git"),
+      push_url: String::from("git@example.com:repo.git"),
+    }]);
+    repo.remove_remote("upstream").unwrap();
+    assert!(repo.list_remotes().unwrap().is_empty());
+  }
+
+  #[test]
+  fn conflicting_cherry_picks_explain_how_to_continue() {
+    let temp_repo = TempRepo::init();
+    temp_repo.commit_file("a.txt", "base");
+    temp_repo.git(&["checkout", "-q", "-b", "other"]);
+    temp_repo.commit_file("a.txt", "other");
+    let sha = temp_repo.git(&["rev-parse", "HEAD"]);
+    temp_repo.git(&["checkout", "-q", "main"]);
+    temp_repo.commit_file("a.txt", "main");
+    let err = temp_repo.cli_repo().cherry_pick(sha.trim()).unwrap_err().to_string();
+    assert!(err.contains("\n  a.txt\n"), "{}", err);
+    assert!(err.contains("git cherry-pick --continue"), "{}", err);
+  }
+
+  #[test]
+  fn blame_attributes_each_line_to_its_commit() {
+    let temp_repo = TempRepo::init();
+    temp_repo.commit_file("a.txt", "first\nsecond\n");
+    let first = temp_repo.git(&["rev-parse", "HEAD"]);
+    temp_repo.commit_file("a.txt", "first\nchanged\n");
+    let blame = temp_repo.cli_repo().blame(Path::new("a.txt"), None).unwrap();
+    let lines: Vec<(usize, &str, &str)> =
+      blame.iter().map(|line| (line.line_number, line.author.as_str(), line.content.as_str())).collect();
+    assert_eq!(lines, vec![(1, "Test", "first"), (2, "Test", "changed")]);
+    assert_eq!(blame[0].sha, first.trim());
+    assert_ne!(blame[1].sha, first.trim());
+  }
+
+  #[test]
+  fn branch_diffs_go_from_the_first_branch_to_the_second() {
+    let temp_repo = TempRepo::init();
+    temp_repo.commit_file("a.txt", "main\n");
+    temp_repo.git(&["checkout", "-q", "-b", "other"]);
+    temp_repo.commit_file("a.txt", "other\n");
+    let diff = temp_repo
+      .cli_repo()
+      .diff_branch(&GitBranch::new(String::from("main")), &GitBranch::new(String::from("other")))
+      .unwrap();
+    // The CLI colours its diffs, compare the text without the escape codes
+    let lines: Vec<String> = styled_lines(&diff).into_iter().map(|(line, _)| line).collect();
+    assert!(lines.iter().any(|line| line == "-main"), "{:?}", lines);
+    assert!(lines.iter().any(|line| line == "+other"), "{:?}", lines);
+  }
+
+  #[test]
+  fn remote_branches_are_checked_out_as_tracking_branches() {
+    let temp_repo = TempRepo::init();
+    temp_repo.git(&["remote", "add", "origin", "https://example.com/repo.git"]);
+    temp_repo.git(&["update-ref", "refs/remotes/origin/feature", "HEAD"]);
+    let repo = temp_repo.cli_repo();
+    let remote_branch = GitRemoteBranch::new(String::from("origin/feature"));
+    repo.checkout_tracking_branch(&remote_branch).unwrap();
+    let current = repo.current_branch().unwrap();
+    assert_eq!(current.name, "feature");
+    assert_eq!(temp_repo.git(&["rev-parse", "--abbrev-ref", "feature@{upstream}"]).trim(), "origin/feature");
+    let err = repo.checkout_tracking_branch(&remote_branch).unwrap_err();
+    assert!(err.to_string().contains("branch already exists"), "{}", err);
+  }
+
+  #[test]
+  fn opening_with_a_missing_git_fails_cleanly() {
+    let err = GitCliRepo::from_cwd(Path::new("/nonexistent/git")).err().unwrap();
+    assert!(err.to_string().contains("Unable to run git at /nonexistent/git"), "{}", err);
+  }
+
+  #[test]
+  fn opening_records_the_git_version() {
+    let temp_repo = TempRepo::init();
+    assert!(temp_repo.cli_repo().git_version.starts_with("git version "));
+  }
 }
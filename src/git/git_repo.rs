@@ -1,13 +1,26 @@
+use std::{
+  collections::HashMap,
+  path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
 use crate::error::Error;
 
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct GitRemoteBranch {
   pub name: String,
+  pub gone: bool,
 }
 
 impl GitRemoteBranch {
   pub fn new(name: String) -> Self {
-    GitRemoteBranch { name }
+    GitRemoteBranch { name, gone: false }
+  }
+
+  /// The name without the remote, what a local branch tracking it would be called.
+  pub fn short_name(&self) -> &str {
+    self.name.split_once('/').map_or(self.name.as_str(), |(_, name)| name)
   }
 }
 
@@ -16,33 +29,305 @@ pub struct GitBranch {
   pub name: String,
   pub is_head: bool,
   pub upstream: Option<GitRemoteBranch>,
+  /// The sha of the commit the branch points at, empty when unknown.
+  pub sha: String,
+  /// Unix timestamp of the latest commit on the branch, only loaded when needed.
+  pub last_commit_at: Option<i64>,
+  /// Unix timestamp of the branch's oldest reflog entry, only loaded when needed.
+  pub created_at: Option<i64>,
+  /// Commits ahead of and behind the upstream.
+  pub ahead: u32,
+  pub behind: u32,
+  /// Checked out in a linked worktree.
+  pub is_worktree: bool,
 }
 
 impl GitBranch {
   pub fn new(name: String) -> Self {
-    GitBranch { name, is_head: false, upstream: None }
+    GitBranch { name, ..Default::default() }
   }
 }
 
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GitTag {
+  pub name: String,
+  /// The commit the tag points at, annotated tags are peeled to their commit.
+  pub commit_sha: String,
+  pub is_annotated: bool,
+}
+
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct GitStash {
   pub index: usize,
   pub message: String,
   pub stash_id: String,
+  /// The branch the stash was created on, parsed from the message.
+  pub branch_name: String,
+  /// Unix timestamp of when the stash was created.
+  pub created_at: Option<i64>,
 }
 
 impl GitStash {
   pub fn new(index: usize, message: String, stash_id: String) -> Self {
-    GitStash { index, message, stash_id }
+    // Stash messages look like `On main: message` or `WIP on main: 8fb5d9b Commit subject`
+    let branch_name = message
+      .strip_prefix("WIP on ")
+      .or_else(|| message.strip_prefix("On "))
+      .and_then(|rest| rest.split_once(':'))
+      .map(|(branch_name, _)| String::from(branch_name))
+      .unwrap_or_default();
+    GitStash { index, message, stash_id, branch_name, created_at: None }
+  }
+}
+
+/// The outcome of fast-forwarding a single local branch to its upstream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PullResult {
+  pub branch_name: String,
+  pub new_commits: usize,
+  /// The updated commit range, e.g. `abc1234..def5678`, or the reason the update failed.
+  pub result: Result<String, String>,
+}
+
+impl PullResult {
+  pub fn summary(&self) -> String {
+    match &self.result {
+      Ok(_) if self.new_commits == 0 => format!("Skipping {}: up to date", self.branch_name),
+      Ok(range) => format!("Updating {}: {} new commits ({})", self.branch_name, self.new_commits, range),
+      Err(err) => format!("Failed {}: {}", self.branch_name, err.trim()),
+    }
+  }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GitCommit {
+  pub sha: String,
+  pub author: String,
+  pub committed_at: i64,
+  pub subject: String,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BranchDetails {
+  pub last_commit: GitCommit,
+  /// Commits on the branch that aren't on the main branch.
+  pub ahead: u32,
+  /// Commits on the main branch that aren't on the branch.
+  pub behind: u32,
+  pub merge_base_sha: String,
+  pub is_merged: bool,
+}
+
+/// An operation git has paused part way through, e.g. on conflicts.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RepoState {
+  #[default]
+  Clean,
+  Merging,
+  Rebasing,
+  CherryPicking,
+  Reverting,
+  Bisecting,
+}
+
+impl RepoState {
+  /// A short uppercase label for badges, e.g. `REBASING`.
+  pub fn label(&self) -> &'static str {
+    match self {
+      RepoState::Clean => "CLEAN",
+      RepoState::Merging => "MERGING",
+      RepoState::Rebasing => "REBASING",
+      RepoState::CherryPicking => "CHERRY-PICKING",
+      RepoState::Reverting => "REVERTING",
+      RepoState::Bisecting => "BISECTING",
+    }
+  }
+
+  /// The in progress operation as a noun, e.g. `rebase`.
+  pub fn operation(&self) -> &'static str {
+    match self {
+      RepoState::Clean => "nothing",
+      RepoState::Merging => "merge",
+      RepoState::Rebasing => "rebase",
+      RepoState::CherryPicking => "cherry-pick",
+      RepoState::Reverting => "revert",
+      RepoState::Bisecting => "bisect",
+    }
   }
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum StatusKind {
+  #[default]
+  Modified,
+  Added,
+  Deleted,
+  Renamed,
+  Untracked,
+}
+
+/// A changed file in the working tree. Files with both staged and unstaged changes are listed once for each.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FileStatus {
+  /// Relative to the top of the working tree.
+  pub path: PathBuf,
+  pub status: StatusKind,
+  /// The change is in the index rather than only the working tree.
+  pub staged: bool,
+}
+
+/// Who last changed a line of a file, from `git blame`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BlameLine {
+  pub sha: String,
+  pub author: String,
+  /// When the author made the change, relative to now, e.g. `3 days ago`.
+  pub date: String,
+  /// Starts at 1.
+  pub line_number: usize,
+  pub content: String,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GitRemote {
+  pub name: String,
+  pub fetch_url: String,
+  /// The same as `fetch_url` unless a separate push URL is configured.
+  pub push_url: String,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GitWorktree {
+  pub path: PathBuf,
+  /// The checked out branch, `None` when detached.
+  pub branch: Option<String>,
+  /// The worktree the repository was cloned into, the others are linked to it.
+  pub is_main: bool,
+  pub is_bare: bool,
+  /// Locked worktrees aren't pruned, e.g. when they live on removable storage.
+  pub is_locked: bool,
+}
+
 pub trait GitRepo {
   fn local_branches(&self) -> Result<Vec<GitBranch>, Error>;
-  fn stashes(&mut self) -> Result<Vec<GitStash>, Error>;
+  /// The checked out branch, errors when HEAD is detached.
+  fn current_branch(&self) -> Result<GitBranch, Error>;
+  /// Local branches whose tip is reachable from `target`, like `git branch --merged`. Includes `target` itself.
+  fn branches_merged_into(&self, target: &GitBranch) -> Result<Vec<GitBranch>, Error>;
+  /// Remote tracking branches, named with their remote like `origin/main`.
+  fn remote_branches(&self) -> Result<Vec<GitRemoteBranch>, Error>;
+  fn stashes(&self) -> Result<Vec<GitStash>, Error>;
+  /// The number of stashes without parsing them, cheap enough to check before loading `stashes`.
+  fn get_stash_count(&self) -> Result<usize, Error>;
   fn checkout_branch_from_name(&self, branch_name: &str) -> Result<(), Error>;
+  /// Create and check out a local branch tracking `remote_branch`, like `git checkout --track`. Errors if a local
+  /// branch with its short name already exists.
+  fn checkout_tracking_branch(&self, remote_branch: &GitRemoteBranch) -> Result<(), Error>;
   fn checkout_branch(&self, branch: &GitBranch) -> Result<(), Error>;
   fn validate_branch_name(&self, name: &str) -> Result<bool, Error>;
   fn create_branch(&self, to_create: &GitBranch) -> Result<(), Error>;
+  /// Create a branch at the commit `sha` without checking it out, used to bring back a deleted branch.
+  fn create_branch_at(&self, name: &str, sha: &str) -> Result<(), Error>;
   fn delete_branch(&self, to_delete: &GitBranch) -> Result<(), Error>;
+  fn rename_branch(&self, branch: &GitBranch, new_name: &str) -> Result<(), Error>;
+  /// Make `branch` track `upstream`, a remote branch like `origin/main`.
+  fn set_upstream(&self, branch: &GitBranch, upstream: &str) -> Result<(), Error>;
+  fn unset_upstream(&self, branch: &GitBranch) -> Result<(), Error>;
+  /// Delete the branch from `remote`, like `git push <remote> --delete <branch>`.
+  fn delete_remote_branch(&self, remote: &str, branch: &GitRemoteBranch) -> Result<(), Error>;
+  fn list_tags(&self) -> Result<Vec<GitTag>, Error>;
+  fn delete_tag(&self, tag: &GitTag) -> Result<(), Error>;
+  /// Stash the working tree changes, optionally sweeping up untracked and ignored files too.
+  fn create_stash(&self, message: &str, include_untracked: bool, include_ignored: bool) -> Result<(), Error>;
+  /// Stash only the changes to files matching `pathspec`, like `git stash push -- <pathspec>`. Returns false when none
+  /// of the matching files had changes, so nothing was stashed.
+  fn stash_push_pathspec(&self, message: &str, pathspec: &[&str]) -> Result<bool, Error>;
+  /// Stash tracked changes with git's hooks turned off, `false` when there was nothing to stash.
+  fn stash_no_verify(&self, message: &str) -> Result<bool, Error>;
+  fn apply_stash(&self, stash: &GitStash) -> Result<(), Error>;
+  /// Check out a new branch at the commit the stash was made on and pop the stash onto it, like `git stash branch`.
+  /// Returns the files left conflicted, the stash is only dropped when there are none.
+  fn create_branch_from_stash(&self, stash: &GitStash, branch_name: &str) -> Result<Vec<String>, Error>;
+  fn pop_stash(&self, stash: &GitStash) -> Result<(), Error>;
+  fn drop_stash(&self, stash: &GitStash) -> Result<(), Error>;
+  /// Files changed by the stash that also have local modifications, these are likely to conflict on apply.
+  fn get_stash_conflict_files(&self, stash: &GitStash) -> Result<Vec<String>, Error>;
+  /// The `--stat` summary line for a stash, e.g. `2 files changed, 5 insertions(+), 1 deletion(-)`.
+  fn stash_show_stat(&self, stash: &GitStash) -> Result<String, Error>;
+  /// The patch a stash would apply, like `git stash show -p`.
+  fn stash_diff(&self, stash: &GitStash) -> Result<String, Error>;
+  /// Fetch `remote`, or every remote when `None`.
+  fn fetch(&self, remote: Option<&str>) -> Result<(), Error>;
+  /// Merge `from` into the checked out branch.
+  fn merge_branch(&self, from: &GitBranch) -> Result<(), Error>;
+  /// Rebase the checked out branch onto `onto`. Errors when the rebase stops part way, e.g. on a conflict.
+  fn rebase_branch(&self, onto: &GitBranch) -> Result<(), Error>;
+  /// Push `branch` to `remote`, or the current branch to its upstream when `remote` is `None`.
+  fn push_branch(&self, branch: &GitBranch, remote: Option<&str>, force: bool) -> Result<(), Error>;
+  /// Pull into the checked out branch from `remote`, or its upstream when `None`, rebasing rather than merging when
+  /// `rebase` is set. Errors listing the conflicted files when the pull stops on a conflict.
+  fn pull(&self, remote: Option<&str>, rebase: bool) -> Result<(), Error>;
+  /// Prune every remote's deleted branches then delete the local branches that tracked them, other than the checked
+  /// out branch and those checked out in a worktree. Returns the deleted branches.
+  fn prune_gone_branches(&self) -> Result<Vec<GitBranch>, Error>;
+  /// Fetch all remotes then fast-forward every local branch that tracks an upstream, without checking them out.
+  fn pull_all_branches(&self) -> Result<Vec<PullResult>, Error>;
+  /// Unix timestamps of the latest commit on each local branch, keyed by branch name.
+  fn get_last_commit_times(&self) -> Result<HashMap<String, i64>, Error>;
+  /// Commits ahead of and behind the upstream for every local branch with one, keyed by branch name.
+  fn get_ahead_behind_bulk(&self) -> Result<HashMap<String, (u32, u32)>, Error>;
+  /// When `branch` was created going by its reflog, `None` when the reflog has expired or is disabled.
+  fn get_branch_creation_time(&self, branch: &GitBranch) -> Result<Option<i64>, Error>;
+  /// The commit message, file stats and patch for a single commit, formatted like `git show`.
+  fn show_commit_diff(&self, sha: &str) -> Result<String, Error>;
+  /// The patch turning `from`'s tree into `to`'s, like `git diff from..to`.
+  fn diff_branch(&self, from: &GitBranch, to: &GitBranch) -> Result<String, Error>;
+  /// Up to `limit` commits reachable from `branch`, newest first.
+  fn commit_log(&self, branch: &GitBranch, limit: usize) -> Result<Vec<GitCommit>, Error>;
+  /// Apply the commit `commit_sha` onto HEAD, like `git cherry-pick <sha>`. A conflict leaves the cherry-pick in
+  /// progress for the user to resolve.
+  fn cherry_pick(&self, commit_sha: &str) -> Result<(), Error>;
+  /// The latest commit on `branch` and how it compares with `main_branch`.
+  fn get_branch_details(&self, branch: &GitBranch, main_branch: &str) -> Result<BranchDetails, Error>;
+  /// Whether a merge, rebase or similar operation is in progress.
+  fn get_repository_state(&self) -> Result<RepoState, Error>;
+  /// The checked out branch, `HEAD` when detached, and the short sha of the commit it points at.
+  fn current_head(&self) -> Result<(String, String), Error>;
+  /// The top of the working tree.
+  fn work_dir(&self) -> Result<PathBuf, Error>;
+  /// The staged, unstaged and untracked changes in the working tree, like `git status`. Ignored files are left out.
+  fn working_status(&self) -> Result<Vec<FileStatus>, Error>;
+  /// Stage the changes to `path`, relative to the top of the working tree, including its deletion.
+  fn stage_file(&self, path: &str) -> Result<(), Error>;
+  /// Move the staged changes to `path` back to the working tree.
+  fn unstage_file(&self, path: &str) -> Result<(), Error>;
+  /// Who last changed each line of `path`, relative to the top of the working tree, as of `branch` or the working
+  /// tree when `None`.
+  fn blame(&self, path: &Path, branch: Option<&GitBranch>) -> Result<Vec<BlameLine>, Error>;
+  /// Every worktree of the repository, starting with the main one.
+  fn list_worktrees(&self) -> Result<Vec<GitWorktree>, Error>;
+  /// Remove a linked worktree, discarding any changes in it.
+  fn remove_worktree(&self, worktree: &GitWorktree) -> Result<(), Error>;
+  /// Check out `branch` in a new worktree at `path`, like `git worktree add <path> <branch>`.
+  fn create_worktree(&self, branch: &GitBranch, path: &str) -> Result<(), Error>;
+  fn list_remotes(&self) -> Result<Vec<GitRemote>, Error>;
+  fn add_remote(&self, name: &str, url: &str) -> Result<(), Error>;
+  /// Remove a remote along with its remote tracking branches.
+  fn remove_remote(&self, name: &str) -> Result<(), Error>;
+  fn rename_remote(&self, old_name: &str, new_name: &str) -> Result<(), Error>;
+  /// Warnings git printed while running commands that otherwise succeeded, clearing them so each is reported once.
+  fn get_and_clear_warnings(&self) -> Vec<String> {
+    vec![]
+  }
+}
+
+/// Explains how to carry on from a cherry-pick that stopped on conflicts in `conflicts`.
+pub fn cherry_pick_conflict(commit_sha: &str, conflicts: &[&str]) -> Error {
+  let short_sha = commit_sha.get(..7).unwrap_or(commit_sha);
+  Error::Git(format!(
+    "Cherry-picking {} conflicted in:\n{}\nResolve the conflicts then run git cherry-pick --continue, or git \
+     cherry-pick --abort to give up",
+    short_sha,
+    conflicts.iter().map(|path| format!("  {}", path)).collect::<Vec<_>>().join("\n")
+  ))
 }
@@ -0,0 +1,301 @@
+use std::{
+  collections::HashMap,
+  path::{Path, PathBuf},
+  sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+  },
+};
+
+use crate::{
+  error::Error,
+  git::git_repo::{
+    cherry_pick_conflict, BlameLine, BranchDetails, FileStatus, GitBranch, GitCommit, GitRemote, GitRemoteBranch,
+    GitRepo, GitStash, GitTag, GitWorktree, PullResult, RepoState,
+  },
+};
+
+/// An in memory `GitRepo` for tests, operations succeed without doing anything unless configured otherwise.
+#[derive(Debug, Default)]
+pub struct MockGitRepo {
+  pub branches: Vec<GitBranch>,
+  pub remote_branches: Vec<GitRemoteBranch>,
+  pub stashes: Vec<GitStash>,
+  /// What `get_stash_count` reports, independent of `stashes` so a stale count can be simulated.
+  pub stash_count: usize,
+  /// How many times `stashes` has been called, shared so it can be read once the mock is boxed.
+  pub stash_loads: Arc<AtomicUsize>,
+  /// Files each stash would conflict with, by stash id.
+  pub stash_conflict_files: HashMap<String, Vec<String>>,
+  pub tags: Vec<GitTag>,
+  /// What `commit_log` lists, for every branch.
+  pub commits: Vec<GitCommit>,
+  /// Files `pull` reports as conflicted, pulling succeeds when empty.
+  pub pull_conflicts: Vec<String>,
+  /// Decides whether a branch name is valid, every name is valid when unset.
+  pub validate_branch_name: Option<fn(&str) -> bool>,
+  pub repo_state: RepoState,
+}
+
+impl GitRepo for MockGitRepo {
+  fn local_branches(&self) -> Result<Vec<GitBranch>, Error> {
+    Ok(self.branches.clone())
+  }
+
+  fn current_branch(&self) -> Result<GitBranch, Error> {
+    let head = self.branches.iter().find(|branch| branch.is_head);
+    head.cloned().ok_or_else(|| Error::Git(String::from("HEAD is detached")))
+  }
+
+  fn branches_merged_into(&self, target: &GitBranch) -> Result<Vec<GitBranch>, Error> {
+    let merged = self.branches.iter().filter(|branch| branch.name == target.name || branch.name.contains("merged"));
+    Ok(merged.cloned().collect())
+  }
+
+  fn remote_branches(&self) -> Result<Vec<GitRemoteBranch>, Error> {
+    Ok(self.remote_branches.clone())
+  }
+
+  fn stashes(&self) -> Result<Vec<GitStash>, Error> {
+    self.stash_loads.fetch_add(1, Ordering::Relaxed);
+    Ok(self.stashes.clone())
+  }
+
+  fn get_stash_count(&self) -> Result<usize, Error> {
+    Ok(self.stash_count)
+  }
+
+  fn checkout_branch_from_name(&self, _branch_name: &str) -> Result<(), Error> {
+    Ok(())
+  }
+
+  fn checkout_branch(&self, _branch: &GitBranch) -> Result<(), Error> {
+    Ok(())
+  }
+
+  fn checkout_tracking_branch(&self, remote_branch: &GitRemoteBranch) -> Result<(), Error> {
+    if self.branches.iter().any(|branch| branch.name == remote_branch.short_name()) {
+      return Err(Error::Git(format!("branch already exists: {}", remote_branch.short_name())));
+    }
+    Ok(())
+  }
+
+  fn validate_branch_name(&self, name: &str) -> Result<bool, Error> {
+    Ok(self.validate_branch_name.is_none_or(|validate| validate(name)))
+  }
+
+  fn create_branch(&self, _to_create: &GitBranch) -> Result<(), Error> {
+    Ok(())
+  }
+
+  fn create_branch_at(&self, _name: &str, _sha: &str) -> Result<(), Error> {
+    Ok(())
+  }
+
+  fn delete_branch(&self, to_delete: &GitBranch) -> Result<(), Error> {
+    if to_delete.name.contains("fail") {
+      return Err(Error::Git(format!("Failed to delete {}", to_delete.name)));
+    }
+    Ok(())
+  }
+
+  fn rename_branch(&self, _branch: &GitBranch, new_name: &str) -> Result<(), Error> {
+    if new_name.contains("fail") {
+      return Err(Error::Git(format!("Failed to rename to {}", new_name)));
+    }
+    Ok(())
+  }
+
+  fn set_upstream(&self, _branch: &GitBranch, _upstream: &str) -> Result<(), Error> {
+    Ok(())
+  }
+
+  fn unset_upstream(&self, _branch: &GitBranch) -> Result<(), Error> {
+    Ok(())
+  }
+
+  fn delete_remote_branch(&self, _remote: &str, _branch: &GitRemoteBranch) -> Result<(), Error> {
+    Ok(())
+  }
+
+  fn list_tags(&self) -> Result<Vec<GitTag>, Error> {
+    Ok(self.tags.clone())
+  }
+
+  fn delete_tag(&self, _tag: &GitTag) -> Result<(), Error> {
+    Ok(())
+  }
+
+  fn create_stash(&self, _message: &str, _include_untracked: bool, _include_ignored: bool) -> Result<(), Error> {
+    Ok(())
+  }
+
+  fn stash_no_verify(&self, _message: &str) -> Result<bool, Error> {
+    Ok(true)
+  }
+
+  fn stash_push_pathspec(&self, _message: &str, pathspec: &[&str]) -> Result<bool, Error> {
+    Ok(pathspec != ["nonexistent"])
+  }
+
+  fn apply_stash(&self, _stash: &GitStash) -> Result<(), Error> {
+    Ok(())
+  }
+
+  fn create_branch_from_stash(&self, stash: &GitStash, _branch_name: &str) -> Result<Vec<String>, Error> {
+    if stash.message.contains("conflict") {
+      return Ok(vec![String::from("src/main.rs")]);
+    }
+    Ok(vec![])
+  }
+
+  fn pop_stash(&self, _stash: &GitStash) -> Result<(), Error> {
+    Ok(())
+  }
+
+  fn drop_stash(&self, _stash: &GitStash) -> Result<(), Error> {
+    Ok(())
+  }
+
+  fn get_stash_conflict_files(&self, stash: &GitStash) -> Result<Vec<String>, Error> {
+    Ok(self.stash_conflict_files.get(&stash.stash_id).cloned().unwrap_or_default())
+  }
+
+  fn stash_show_stat(&self, _stash: &GitStash) -> Result<String, Error> {
+    Ok(String::new())
+  }
+
+  fn stash_diff(&self, stash: &GitStash) -> Result<String, Error> {
+    Ok(format!("diff of {}", stash.stash_id))
+  }
+
+  fn fetch(&self, remote: Option<&str>) -> Result<(), Error> {
+    if remote == Some("bad-remote") {
+      return Err(Error::Git(String::from("fatal: 'bad-remote' does not appear to be a git repository")));
+    }
+    Ok(())
+  }
+
+  fn merge_branch(&self, _from: &GitBranch) -> Result<(), Error> {
+    Ok(())
+  }
+
+  fn rebase_branch(&self, onto: &GitBranch) -> Result<(), Error> {
+    if onto.name.contains("conflict") {
+      return Err(Error::Git(format!("Rebasing onto {} stopped part way", onto.name)));
+    }
+    Ok(())
+  }
+
+  fn push_branch(&self, _branch: &GitBranch, _remote: Option<&str>, _force: bool) -> Result<(), Error> {
+    Ok(())
+  }
+
+  fn pull(&self, _remote: Option<&str>, _rebase: bool) -> Result<(), Error> {
+    if !self.pull_conflicts.is_empty() {
+      return Err(Error::Git(format!("Pulling conflicted in: {}", self.pull_conflicts.join(", "))));
+    }
+    Ok(())
+  }
+
+  fn prune_gone_branches(&self) -> Result<Vec<GitBranch>, Error> {
+    let gone = self.branches.iter().filter(|branch| branch.upstream.as_ref().is_some_and(|upstream| upstream.gone));
+    Ok(gone.filter(|branch| !branch.is_head && !branch.is_worktree).cloned().collect())
+  }
+
+  fn pull_all_branches(&self) -> Result<Vec<PullResult>, Error> {
+    Ok(vec![])
+  }
+
+  fn get_last_commit_times(&self) -> Result<HashMap<String, i64>, Error> {
+    Ok(self.branches.iter().filter_map(|branch| Some((branch.name.clone(), branch.last_commit_at?))).collect())
+  }
+
+  fn get_branch_creation_time(&self, branch: &GitBranch) -> Result<Option<i64>, Error> {
+    Ok(branch.created_at)
+  }
+
+  fn get_ahead_behind_bulk(&self) -> Result<HashMap<String, (u32, u32)>, Error> {
+    Ok(HashMap::new())
+  }
+
+  fn show_commit_diff(&self, sha: &str) -> Result<String, Error> {
+    Ok(format!("commit {}\n", sha))
+  }
+
+  fn diff_branch(&self, _from: &GitBranch, _to: &GitBranch) -> Result<String, Error> {
+    Ok(String::new())
+  }
+
+  fn cherry_pick(&self, commit_sha: &str) -> Result<(), Error> {
+    if commit_sha.contains("badf00d") {
+      return Err(cherry_pick_conflict(commit_sha, &["src/main.rs"]));
+    }
+    Ok(())
+  }
+
+  fn commit_log(&self, _branch: &GitBranch, limit: usize) -> Result<Vec<GitCommit>, Error> {
+    Ok(self.commits.iter().take(limit).cloned().collect())
+  }
+
+  fn get_branch_details(&self, _branch: &GitBranch, _main_branch: &str) -> Result<BranchDetails, Error> {
+    Ok(BranchDetails::default())
+  }
+
+  fn current_head(&self) -> Result<(String, String), Error> {
+    let head = self.branches.iter().find(|branch| branch.is_head);
+    Ok(head.map(|branch| (branch.name.clone(), branch.sha.clone())).unwrap_or_default())
+  }
+
+  fn get_repository_state(&self) -> Result<RepoState, Error> {
+    Ok(self.repo_state)
+  }
+
+  fn work_dir(&self) -> Result<PathBuf, Error> {
+    Ok(PathBuf::from("."))
+  }
+
+  fn working_status(&self) -> Result<Vec<FileStatus>, Error> {
+    Ok(vec![])
+  }
+
+  fn stage_file(&self, _path: &str) -> Result<(), Error> {
+    Ok(())
+  }
+
+  fn unstage_file(&self, _path: &str) -> Result<(), Error> {
+    Ok(())
+  }
+
+  fn blame(&self, _path: &Path, _branch: Option<&GitBranch>) -> Result<Vec<BlameLine>, Error> {
+    Ok(vec![])
+  }
+
+  fn list_worktrees(&self) -> Result<Vec<GitWorktree>, Error> {
+    Ok(vec![])
+  }
+
+  fn remove_worktree(&self, _worktree: &GitWorktree) -> Result<(), Error> {
+    Ok(())
+  }
+
+  fn create_worktree(&self, _branch: &GitBranch, _path: &str) -> Result<(), Error> {
+    Ok(())
+  }
+
+  fn list_remotes(&self) -> Result<Vec<GitRemote>, Error> {
+    Ok(vec![])
+  }
+
+  fn add_remote(&self, _name: &str, _url: &str) -> Result<(), Error> {
+    Ok(())
+  }
+
+  fn remove_remote(&self, _name: &str) -> Result<(), Error> {
+    Ok(())
+  }
+
+  fn rename_remote(&self, _old_name: &str, _new_name: &str) -> Result<(), Error> {
+    Ok(())
+  }
+}
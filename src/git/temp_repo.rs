@@ -0,0 +1,71 @@
+use std::{
+  env, fs,
+  path::{Path, PathBuf},
+  process::Command,
+  sync::{
+    atomic::{AtomicUsize, Ordering},
+    Mutex, MutexGuard,
+  },
+};
+
+use crate::git::git_cli_repo::GitCliRepo;
+
+// The working directory is shared by every test thread, so only one temp repo can be current at a time
+static CWD_LOCK: Mutex<()> = Mutex::new(());
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A real repository in a temporary directory with `main` checked out on an empty initial commit. It is the current
+/// directory while it lives, the previous one is restored and the repository deleted when it drops.
+pub struct TempRepo {
+  path: PathBuf,
+  previous_dir: PathBuf,
+  _cwd_guard: MutexGuard<'static, ()>,
+}
+
+impl TempRepo {
+  pub fn init() -> Self {
+    let cwd_guard = CWD_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    let path = env::temp_dir().join(format!("{}-test-{}-{}", env!("CARGO_PKG_NAME"), std::process::id(), id));
+    fs::create_dir_all(&path).unwrap();
+    let previous_dir = env::current_dir().unwrap();
+    env::set_current_dir(&path).unwrap();
+    let temp_repo = TempRepo { path, previous_dir, _cwd_guard: cwd_guard };
+    temp_repo.git(&["init", "--quiet", "--initial-branch", "main"]);
+    temp_repo.git(&["config", "user.name", "Test"]);
+    temp_repo.git(&["config", "user.email", "test@example.com"]);
+    temp_repo.git(&["config", "commit.gpgsign", "false"]);
+    temp_repo.git(&["commit", "--quiet", "--allow-empty", "-m", "init"]);
+    temp_repo
+  }
+
+  /// A `GitCliRepo` for the temp repo, found from the current directory like the app does.
+  pub fn cli_repo(&self) -> GitCliRepo {
+    GitCliRepo::from_cwd(Path::new("git")).unwrap()
+  }
+
+  /// Runs git in the repo, panicking if it fails, and returns its output.
+  pub fn git(&self, args: &[&str]) -> String {
+    let output = Command::new("git").args(args).current_dir(&self.path).output().unwrap();
+    assert!(output.status.success(), "git {} failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+  }
+
+  pub fn write_file(&self, name: &str, contents: &str) {
+    fs::write(self.path.join(name), contents).unwrap();
+  }
+
+  /// Writes and commits `name`, so later changes to it can be stashed.
+  pub fn commit_file(&self, name: &str, contents: &str) {
+    self.write_file(name, contents);
+    self.git(&["add", name]);
+    self.git(&["commit", "--quiet", "-m", &format!("Add {}", name)]);
+  }
+}
+
+impl Drop for TempRepo {
+  fn drop(&mut self) {
+    let _ = env::set_current_dir(&self.previous_dir);
+    let _ = fs::remove_dir_all(&self.path);
+  }
+}
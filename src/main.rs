@@ -1,10 +1,14 @@
+use std::path::PathBuf;
+
 use clap::Parser;
 use color_eyre::eyre::Result;
 
 use crate::{
   app::App,
-  cli::Cli,
-  utils::{initialize_logging, initialize_panic_handler},
+  cli::{completions, Cli},
+  config::Config,
+  git::git_cli_repo::git_version,
+  utils::{initialize_logging, initialize_panic_handler, version},
 };
 
 pub mod action;
@@ -19,12 +23,25 @@ pub mod tui;
 pub mod utils;
 
 async fn tokio_main() -> Result<()> {
+  let args = Cli::parse();
+  if let Some(shell) = args.completions {
+    print!("{}", completions::generate(shell));
+    return Ok(());
+  }
+  if args.version {
+    // Handled here rather than by clap so git only runs when asked, and it is the git from `--git-path` or the config
+    let git_path = args.git_path.clone().or_else(|| Config::new().ok().map(|config| config.git_executable));
+    let git_path = git_path.unwrap_or_else(|| PathBuf::from("git"));
+    let git = git_version(&git_path).unwrap_or_else(|err| format!("unavailable ({})", err));
+    println!("{} {}\nGit: {}", env!("CARGO_PKG_NAME"), version(), git);
+    return Ok(());
+  }
+
   initialize_logging()?;
 
   initialize_panic_handler()?;
 
-  Cli::parse();
-  let mut app = App::new()?;
+  let mut app = App::new(args)?;
   app.run().await?;
 
   Ok(())
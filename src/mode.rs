@@ -5,4 +5,11 @@ pub enum Mode {
   #[default]
   Default,
   Input,
+  Confirm,
+  /// Typing goes to the search bar over the active view.
+  Search,
+  /// The help overlay is open, any key closes it.
+  Help,
+  /// The terminal is too small to render the UI.
+  TooSmall,
 }
@@ -13,7 +13,7 @@ use crossterm::{
   terminal::{EnterAlternateScreen, LeaveAlternateScreen},
 };
 use futures::{FutureExt, StreamExt};
-use ratatui::backend::CrosstermBackend as Backend;
+use ratatui::{backend::CrosstermBackend as Backend, layout::Size};
 use serde::{Deserialize, Serialize};
 use tokio::{
   sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
@@ -215,6 +215,11 @@ impl Tui {
     Ok(())
   }
 
+  /// The terminal's current size in columns and rows.
+  pub fn current_size(&self) -> Result<Size> {
+    Ok(self.terminal.size()?)
+  }
+
   pub async fn next(&mut self) -> Option<Event> {
     self.event_rx.recv().await
   }
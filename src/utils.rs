@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use color_eyre::eyre::Result;
 use directories::ProjectDirs;
@@ -7,6 +7,12 @@ use tracing::error;
 use tracing_error::ErrorLayer;
 use tracing_subscriber::{prelude::__tracing_subscriber_SubscriberExt, util::SubscriberInitExt, Layer};
 
+pub mod accessibility;
+pub mod browser;
+pub mod clipboard;
+pub mod crash_report;
+pub mod time;
+
 const VERSION_MESSAGE: &str =
   concat!(env!("CARGO_PKG_VERSION"), "-", env!("VERGEN_GIT_DESCRIBE"), " (", env!("VERGEN_BUILD_DATE"), ")");
 
@@ -16,6 +22,8 @@ lazy_static! {
     std::env::var(format!("{}_DATA", PROJECT_NAME.clone())).ok().map(PathBuf::from);
   pub static ref CONFIG_FOLDER: Option<PathBuf> =
     std::env::var(format!("{}_CONFIG", PROJECT_NAME.clone())).ok().map(PathBuf::from);
+  pub static ref STATE_FOLDER: Option<PathBuf> =
+    std::env::var(format!("{}_STATE", PROJECT_NAME.clone())).ok().map(PathBuf::from);
   pub static ref LOG_ENV: String = format!("{}_LOGLEVEL", PROJECT_NAME.clone());
   pub static ref LOG_FILE: String = format!("{}.log", env!("CARGO_PKG_NAME"));
 }
@@ -39,6 +47,11 @@ pub fn initialize_panic_handler() -> Result<()> {
       }
     }
 
+    match crash_report::write_crash_report(&get_state_dir(), panic_info) {
+      Ok(path) => eprintln!("A crash report was written to {}", path.display()),
+      Err(err) => error!("Unable to write crash report: {:?}", err),
+    }
+
     #[cfg(not(debug_assertions))]
     {
       use human_panic::{handle_dump, print_msg, Metadata};
@@ -94,6 +107,19 @@ pub fn get_config_dir() -> PathBuf {
   directory
 }
 
+pub fn get_state_dir() -> PathBuf {
+  let directory = if let Some(s) = STATE_FOLDER.clone() {
+    s
+  } else if let Ok(s) = std::env::var("XDG_STATE_HOME") {
+    PathBuf::from(s).join("gbm")
+  } else if let Some(s) = project_directory().and_then(|proj_dirs| proj_dirs.state_dir().map(Path::to_path_buf)) {
+    s
+  } else {
+    get_data_dir()
+  };
+  directory
+}
+
 pub fn initialize_logging() -> Result<()> {
   let directory = get_data_dir();
   std::fs::create_dir_all(directory.clone())?;
@@ -149,7 +175,6 @@ pub fn version() -> String {
   let config_dir_path = get_config_dir().display().to_string();
   let data_dir_path = get_data_dir().display().to_string();
   let log_level = std::env::var(LOG_ENV.clone()).unwrap_or_else(|_| format!("{}=info", env!("CARGO_CRATE_NAME")));
-
   format!(
     "\
 {VERSION_MESSAGE}
@@ -0,0 +1,29 @@
+use std::{fs::OpenOptions, io::Write, path::PathBuf, sync::OnceLock};
+
+use tracing::error;
+
+use crate::{config::Config, utils::get_state_dir};
+
+// Where announcements are written, only set when accessibility mode is on
+static ANNOUNCEMENT_FILE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Enables announcements when `Config::accessibility_mode` is set, called once at startup.
+pub fn init(config: &Config) {
+  if !config.accessibility_mode {
+    return;
+  }
+  let path = config.announcement_file.clone().unwrap_or_else(|| get_state_dir().join("announcements"));
+  let _ = ANNOUNCEMENT_FILE.set(path);
+}
+
+/// Write `text` as a line to the announcement file, which can be a pipe read by a screen reader. Does nothing unless
+/// accessibility mode is on.
+pub fn announce(text: &str) {
+  let Some(path) = ANNOUNCEMENT_FILE.get() else {
+    return;
+  };
+  let result = OpenOptions::new().create(true).append(true).open(path).and_then(|mut file| writeln!(file, "{}", text));
+  if let Err(err) = result {
+    error!("Failed to write announcement to {}: {}", path.display(), err);
+  }
+}
@@ -0,0 +1,61 @@
+use std::{
+  io,
+  process::{Command, Stdio},
+};
+
+/// Opens `url` in the default browser with the platform's opener, `open` on macOS, `start` on Windows and `xdg-open`
+/// elsewhere.
+pub fn open(url: &str) -> io::Result<()> {
+  let mut command = if cfg!(target_os = "macos") {
+    Command::new("open")
+  } else if cfg!(windows) {
+    // The empty title stops start treating a quoted url as the window title
+    let mut command = Command::new("cmd");
+    command.args(["/C", "start", ""]);
+    command
+  } else {
+    Command::new("xdg-open")
+  };
+  let status = command.arg(url).stdout(Stdio::null()).stderr(Stdio::null()).status()?;
+  if !status.success() {
+    return Err(io::Error::other(format!("opening {} exited with {}", url, status)));
+  }
+  Ok(())
+}
+
+/// The web page for a remote's `url`, `None` for urls that aren't hosted like local paths.
+/// `git@github.com:owner/repo.git` becomes `https://github.com/owner/repo`.
+pub fn remote_web_url(url: &str) -> Option<String> {
+  let url = url.trim_end_matches('/');
+  let url = url.strip_suffix(".git").unwrap_or(url);
+  if url.starts_with("https://") || url.starts_with("http://") {
+    return Some(String::from(url));
+  }
+  if let Some(rest) = url.strip_prefix("ssh://") {
+    let (host, path) = rest.split_once('/')?;
+    // Drop the user and any port, the web server won't be on the ssh port
+    let host = host.rsplit_once('@').map_or(host, |(_, host)| host);
+    let host = host.split_once(':').map_or(host, |(host, _)| host);
+    return Some(format!("https://{}/{}", host, path));
+  }
+  // scp-like syntax, `user@host:path`
+  let (user_host, path) = url.split_once(':')?;
+  let (_, host) = user_host.split_once('@')?;
+  Some(format!("https://{}/{}", host, path))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn remote_web_urls() {
+    assert_eq!(remote_web_url("https://github.com/owner/repo.git").as_deref(), Some("https://github.com/owner/repo"));
+    assert_eq!(remote_web_url("git@github.com:owner/repo.git").as_deref(), Some("https://github.com/owner/repo"));
+    assert_eq!(
+      remote_web_url("ssh://git@example.com:2222/owner/repo.git").as_deref(),
+      Some("https://example.com/owner/repo")
+    );
+    assert_eq!(remote_web_url("/srv/git/repo.git"), None);
+  }
+}
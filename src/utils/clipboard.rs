@@ -0,0 +1,40 @@
+use std::io;
+
+/// Writes `text` to the system clipboard with the first clipboard tool found, `pbcopy` on macOS, `clip` on Windows and
+/// `wl-copy`, `xclip` or `xsel` elsewhere.
+#[cfg(feature = "clipboard")]
+pub fn set_text(text: &str) -> io::Result<()> {
+  use std::{
+    io::Write,
+    process::{Command, Stdio},
+  };
+
+  let candidates: &[(&str, &[&str])] = if cfg!(target_os = "macos") {
+    &[("pbcopy", &[])]
+  } else if cfg!(windows) {
+    &[("clip", &[])]
+  } else {
+    &[("wl-copy", &[]), ("xclip", &["-selection", "clipboard"]), ("xsel", &["--clipboard", "--input"])]
+  };
+  for (program, args) in candidates {
+    let mut child = match Command::new(program).args(*args).stdin(Stdio::piped()).stderr(Stdio::null()).spawn() {
+      Ok(child) => child,
+      Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+      Err(err) => return Err(err),
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+      stdin.write_all(text.as_bytes())?;
+    }
+    let status = child.wait()?;
+    if !status.success() {
+      return Err(io::Error::other(format!("{} exited with {}", program, status)));
+    }
+    return Ok(());
+  }
+  Err(io::Error::new(io::ErrorKind::NotFound, "no clipboard tool found"))
+}
+
+#[cfg(not(feature = "clipboard"))]
+pub fn set_text(_text: &str) -> io::Result<()> {
+  Err(io::Error::new(io::ErrorKind::Unsupported, "built without the clipboard feature"))
+}
@@ -0,0 +1,71 @@
+use std::{
+  backtrace::Backtrace,
+  cell::RefCell,
+  fs,
+  panic::PanicHookInfo,
+  path::{Path, PathBuf},
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::action::Action;
+
+thread_local! {
+  // The action being handled when a panic happens is usually the best clue to what caused it
+  static CURRENT_ACTION: RefCell<Option<Action>> = const { RefCell::new(None) };
+}
+
+/// Record the action being processed, or `None` once it has been handled, so it can be included in a crash report.
+pub fn set_current_action(action: Option<Action>) {
+  CURRENT_ACTION.with(|current| *current.borrow_mut() = action);
+}
+
+/// Write the panic, a backtrace and the action being processed to `crash_<timestamp>.log` in `directory`.
+pub fn write_crash_report(directory: &Path, panic_info: &PanicHookInfo) -> std::io::Result<PathBuf> {
+  let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+  fs::create_dir_all(directory)?;
+  let path = directory.join(format!("crash_{}.log", timestamp));
+  let action = CURRENT_ACTION.with(|current| current.borrow().as_ref().map(|action| format!("{:?}", action)));
+  let report = format!(
+    "{}\n\nAction being processed: {}\n\nBacktrace:\n{}\n",
+    panic_info,
+    action.as_deref().unwrap_or("none"),
+    Backtrace::force_capture()
+  );
+  fs::write(&path, report)?;
+  Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    panic,
+    sync::{Arc, Mutex},
+  };
+
+  use super::*;
+
+  #[test]
+  fn a_panic_writes_a_crash_report() {
+    let directory = std::env::temp_dir().join(format!("{}-crash-{}", env!("CARGO_PKG_NAME"), std::process::id()));
+    let written = Arc::new(Mutex::new(None));
+    let previous_hook = panic::take_hook();
+    panic::set_hook({
+      let (directory, written) = (directory.clone(), Arc::clone(&written));
+      Box::new(move |panic_info| *written.lock().unwrap() = Some(write_crash_report(&directory, panic_info)))
+    });
+    let result = panic::catch_unwind(|| {
+      set_current_action(Some(Action::Refresh));
+      panic!("the component failed");
+    });
+    panic::set_hook(previous_hook);
+    set_current_action(None);
+    assert!(result.is_err());
+
+    let path = written.lock().unwrap().take().unwrap().unwrap();
+    let report = fs::read_to_string(&path).unwrap();
+    fs::remove_dir_all(&directory).unwrap();
+    assert_eq!(path.parent(), Some(directory.as_path()));
+    assert!(report.contains("the component failed"), "{}", report);
+    assert!(report.contains("Action being processed: Refresh"), "{}", report);
+  }
+}
@@ -0,0 +1,60 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const MINUTE: i64 = 60;
+const HOUR: i64 = 60 * MINUTE;
+const DAY: i64 = 24 * HOUR;
+const WEEK: i64 = 7 * DAY;
+const MONTH: i64 = 30 * DAY;
+const YEAR: i64 = 365 * DAY;
+
+/// Format a unix timestamp relative to now, e.g. `3 days ago`.
+pub fn format_relative_time(unix: i64) -> String {
+  let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs() as i64);
+  format_elapsed((now - unix).max(0))
+}
+
+fn format_elapsed(seconds: i64) -> String {
+  let (count, unit) = match seconds {
+    s if s < MINUTE => return String::from("just now"),
+    s if s < HOUR => (s / MINUTE, "minute"),
+    s if s < DAY => (s / HOUR, "hour"),
+    s if s < WEEK => (s / DAY, "day"),
+    s if s < MONTH => (s / WEEK, "week"),
+    s if s < YEAR => (s / MONTH, "month"),
+    s => (s / YEAR, "year"),
+  };
+  let plural = if count == 1 { "" } else { "s" };
+  format!("{} {}{} ago", count, unit, plural)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn each_bucket_starts_at_its_unit() {
+    assert_eq!(format_elapsed(0), "just now");
+    assert_eq!(format_elapsed(MINUTE - 1), "just now");
+    assert_eq!(format_elapsed(MINUTE), "1 minute ago");
+    assert_eq!(format_elapsed(5 * MINUTE), "5 minutes ago");
+    assert_eq!(format_elapsed(HOUR - 1), "59 minutes ago");
+    assert_eq!(format_elapsed(2 * HOUR), "2 hours ago");
+    assert_eq!(format_elapsed(DAY - 1), "23 hours ago");
+    assert_eq!(format_elapsed(3 * DAY), "3 days ago");
+    assert_eq!(format_elapsed(WEEK - 1), "6 days ago");
+    assert_eq!(format_elapsed(2 * WEEK), "2 weeks ago");
+    assert_eq!(format_elapsed(MONTH - 1), "4 weeks ago");
+    assert_eq!(format_elapsed(3 * MONTH), "3 months ago");
+    assert_eq!(format_elapsed(YEAR - 1), "12 months ago");
+    assert_eq!(format_elapsed(YEAR), "1 year ago");
+    assert_eq!(format_elapsed(2 * YEAR), "2 years ago");
+  }
+
+  #[test]
+  fn timestamps_are_relative_to_now() {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+    assert_eq!(format_relative_time(now - 3 * DAY), "3 days ago");
+    // Clock skew can put a stash in the future
+    assert_eq!(format_relative_time(now + HOUR), "just now");
+  }
+}